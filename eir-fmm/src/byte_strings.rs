@@ -0,0 +1,369 @@
+use crate::{error::CompileError, reference_count, types};
+
+// Bytes of strings are copied and compared by functions in the C standard
+// library.
+const COPY_FUNCTION_NAME: &str = "memcpy";
+const COMPARE_FUNCTION_NAME: &str = "memcmp";
+
+const LENGTH_ELEMENT_INDEX: usize = 0;
+const BYTES_ELEMENT_INDEX: usize = 1;
+
+pub fn compile_function_declarations(module_builder: &fmm::build::ModuleBuilder) {
+    module_builder.declare_function(COPY_FUNCTION_NAME, compile_copy_function_type());
+    module_builder.declare_function(COMPARE_FUNCTION_NAME, compile_compare_function_type());
+}
+
+pub fn compile_length(
+    builder: &fmm::build::InstructionBuilder,
+    string: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let length = compile_raw_length(builder, &string)?;
+
+    reference_count::drop_pointer(builder, &string, |_| Ok(()))?;
+
+    Ok(fmm::build::bit_cast(fmm::types::Primitive::Integer64, length).into())
+}
+
+pub fn compile_concatenation(
+    builder: &fmm::build::InstructionBuilder,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let lhs_length = compile_raw_length(builder, &lhs)?;
+    let rhs_length = compile_raw_length(builder, &rhs)?;
+
+    // Empty strings are null pointers and the other strings are reused as
+    // they are.
+    builder.if_(
+        compile_is_zero(&lhs_length)?,
+        |builder| -> Result<_, CompileError> { Ok(builder.branch(rhs.clone())) },
+        |builder| {
+            Ok(builder.branch(builder.if_(
+                compile_is_zero(&rhs_length)?,
+                |builder| -> Result<_, CompileError> { Ok(builder.branch(lhs.clone())) },
+                |builder| {
+                    let string = compile_allocation(
+                        &builder,
+                        fmm::build::arithmetic_operation(
+                            fmm::ir::ArithmeticOperator::Add,
+                            lhs_length.clone(),
+                            rhs_length.clone(),
+                        )?
+                        .into(),
+                    )?;
+                    let bytes = compile_bytes(&string)?;
+
+                    compile_copy(
+                        &builder,
+                        bytes.clone(),
+                        compile_bytes(&lhs)?,
+                        lhs_length.clone(),
+                    )?;
+                    compile_copy(
+                        &builder,
+                        fmm::build::pointer_address(bytes, lhs_length.clone())?.into(),
+                        compile_bytes(&rhs)?,
+                        rhs_length.clone(),
+                    )?;
+
+                    reference_count::drop_pointer(&builder, &lhs, |_| Ok(()))?;
+                    reference_count::drop_pointer(&builder, &rhs, |_| Ok(()))?;
+
+                    Ok(builder.branch(string))
+                },
+            )?))
+        },
+    )
+}
+
+pub fn compile_slice(
+    builder: &fmm::build::InstructionBuilder,
+    string: fmm::build::TypedExpression,
+    start: fmm::build::TypedExpression,
+    end: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let start: fmm::build::TypedExpression =
+        fmm::build::bit_cast(fmm::types::Primitive::PointerInteger, start).into();
+    let end = compile_minimum(
+        builder,
+        fmm::build::bit_cast(fmm::types::Primitive::PointerInteger, end).into(),
+        compile_raw_length(builder, &string)?,
+    )?;
+
+    let slice = builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::LessThan(false),
+            start.clone(),
+            end.clone(),
+        )?,
+        |builder| -> Result<_, CompileError> {
+            let length: fmm::build::TypedExpression = fmm::build::arithmetic_operation(
+                fmm::ir::ArithmeticOperator::Subtract,
+                end.clone(),
+                start.clone(),
+            )?
+            .into();
+            let slice = compile_allocation(&builder, length.clone())?;
+
+            compile_copy(
+                &builder,
+                compile_bytes(&slice)?,
+                fmm::build::pointer_address(compile_bytes(&string)?, start.clone())?.into(),
+                length,
+            )?;
+
+            Ok(builder.branch(slice))
+        },
+        |builder| Ok(builder.branch(fmm::ir::Undefined::new(types::compile_string()))),
+    )?;
+
+    reference_count::drop_pointer(builder, &string, |_| Ok(()))?;
+
+    Ok(slice)
+}
+
+// Strings are compared lexicographically by their bytes as unsigned integers.
+// The result is a negative, zero or positive 32-bit integer if the left-hand
+// side is less than, equal to or greater than the right-hand side.
+pub fn compile_comparison(
+    builder: &fmm::build::InstructionBuilder,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let lhs_length = compile_raw_length(builder, &lhs)?;
+    let rhs_length = compile_raw_length(builder, &rhs)?;
+    let length = compile_minimum(builder, lhs_length.clone(), rhs_length.clone())?;
+
+    let ordering = builder.if_(
+        compile_is_zero(&length)?,
+        |builder| -> Result<_, CompileError> { Ok(builder.branch(compile_ordering(0))) },
+        |builder| {
+            Ok(builder.branch(builder.call(
+                fmm::build::variable(COMPARE_FUNCTION_NAME, compile_compare_function_type()),
+                vec![
+                    fmm::build::bit_cast(fmm::types::generic_pointer_type(), compile_bytes(&lhs)?)
+                        .into(),
+                    fmm::build::bit_cast(fmm::types::generic_pointer_type(), compile_bytes(&rhs)?)
+                        .into(),
+                    length.clone(),
+                ],
+            )?))
+        },
+    )?;
+
+    // Shorter strings are less than longer ones when they have the same
+    // prefixes.
+    let ordering = builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            ordering.clone(),
+            compile_ordering(0),
+        )?,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(builder.if_(
+                fmm::build::comparison_operation(
+                    fmm::ir::ComparisonOperator::LessThan(false),
+                    lhs_length.clone(),
+                    rhs_length.clone(),
+                )?,
+                |builder| -> Result<_, CompileError> { Ok(builder.branch(compile_ordering(-1))) },
+                |builder| {
+                    Ok(builder.branch(builder.if_(
+                        fmm::build::comparison_operation(
+                            fmm::ir::ComparisonOperator::Equal,
+                            lhs_length.clone(),
+                            rhs_length.clone(),
+                        )?,
+                        |builder| -> Result<_, CompileError> {
+                            Ok(builder.branch(compile_ordering(0)))
+                        },
+                        |builder| Ok(builder.branch(compile_ordering(1))),
+                    )?))
+                },
+            )?))
+        },
+        |builder| Ok(builder.branch(ordering.clone())),
+    )?;
+
+    reference_count::drop_pointer(builder, &lhs, |_| Ok(()))?;
+    reference_count::drop_pointer(builder, &rhs, |_| Ok(()))?;
+
+    Ok(ordering)
+}
+
+// Strings are equal if they have the same lengths and bytes. Unlike
+// comparison, this does not consume strings.
+pub fn compile_equality(
+    builder: &fmm::build::InstructionBuilder,
+    lhs: &fmm::build::TypedExpression,
+    rhs: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let length = compile_raw_length(builder, lhs)?;
+
+    builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            length.clone(),
+            compile_raw_length(builder, rhs)?,
+        )?,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(builder.if_(
+                compile_is_zero(&length)?,
+                |builder| -> Result<_, CompileError> {
+                    Ok(builder.branch(fmm::ir::Primitive::Boolean(true)))
+                },
+                |builder| {
+                    Ok(builder.branch(fmm::build::comparison_operation(
+                        fmm::ir::ComparisonOperator::Equal,
+                        builder.call(
+                            fmm::build::variable(
+                                COMPARE_FUNCTION_NAME,
+                                compile_compare_function_type(),
+                            ),
+                            vec![
+                                fmm::build::bit_cast(
+                                    fmm::types::generic_pointer_type(),
+                                    compile_bytes(lhs)?,
+                                )
+                                .into(),
+                                fmm::build::bit_cast(
+                                    fmm::types::generic_pointer_type(),
+                                    compile_bytes(rhs)?,
+                                )
+                                .into(),
+                                length.clone(),
+                            ],
+                        )?,
+                        compile_ordering(0),
+                    )?))
+                },
+            )?))
+        },
+        |builder| Ok(builder.branch(fmm::ir::Primitive::Boolean(false))),
+    )
+}
+
+pub fn compile_ordering(ordering: i32) -> fmm::build::TypedExpression {
+    fmm::ir::Primitive::Integer32(ordering as u32).into()
+}
+
+// Empty strings are null pointers.
+pub fn compile_raw_length(
+    builder: &fmm::build::InstructionBuilder,
+    string: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            fmm::build::bit_cast(fmm::types::Primitive::PointerInteger, string.clone()),
+            fmm::ir::Undefined::new(fmm::types::Primitive::PointerInteger),
+        )?,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(fmm::ir::Primitive::PointerInteger(0)))
+        },
+        |builder| {
+            Ok(builder.branch(builder.load(fmm::build::record_address(
+                reference_count::compile_untagged_pointer(string)?,
+                LENGTH_ELEMENT_INDEX,
+            )?)?))
+        },
+    )
+}
+
+fn compile_bytes(
+    string: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(fmm::build::record_address(
+        reference_count::compile_untagged_pointer(string)?,
+        BYTES_ELEMENT_INDEX,
+    )?
+    .into())
+}
+
+// Allocated strings are not empty and their bytes are left uninitialized.
+fn compile_allocation(
+    builder: &fmm::build::InstructionBuilder,
+    length: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let string = reference_count::allocate_variable_sized_heap(
+        builder,
+        types::compile_string_body(),
+        length.clone(),
+    )?;
+
+    builder.store(
+        length,
+        fmm::build::record_address(string.clone(), LENGTH_ELEMENT_INDEX)?,
+    );
+
+    Ok(string)
+}
+
+fn compile_copy(
+    builder: &fmm::build::InstructionBuilder,
+    destination: fmm::build::TypedExpression,
+    source: fmm::build::TypedExpression,
+    length: fmm::build::TypedExpression,
+) -> Result<(), CompileError> {
+    builder.call(
+        fmm::build::variable(COPY_FUNCTION_NAME, compile_copy_function_type()),
+        vec![
+            fmm::build::bit_cast(fmm::types::generic_pointer_type(), destination).into(),
+            fmm::build::bit_cast(fmm::types::generic_pointer_type(), source).into(),
+            length,
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn compile_minimum(
+    builder: &fmm::build::InstructionBuilder,
+    one: fmm::build::TypedExpression,
+    other: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::LessThan(false),
+            one.clone(),
+            other.clone(),
+        )?,
+        |builder| -> Result<_, CompileError> { Ok(builder.branch(one.clone())) },
+        |builder| Ok(builder.branch(other.clone())),
+    )
+}
+
+fn compile_is_zero(
+    length: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::comparison_operation(
+        fmm::ir::ComparisonOperator::Equal,
+        length.clone(),
+        fmm::ir::Primitive::PointerInteger(0),
+    )?
+    .into())
+}
+
+fn compile_copy_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![
+            fmm::types::generic_pointer_type().into(),
+            fmm::types::generic_pointer_type().into(),
+            fmm::types::Primitive::PointerInteger.into(),
+        ],
+        fmm::types::generic_pointer_type(),
+        fmm::types::CallingConvention::Target,
+    )
+}
+
+fn compile_compare_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![
+            fmm::types::generic_pointer_type().into(),
+            fmm::types::generic_pointer_type().into(),
+            fmm::types::Primitive::PointerInteger.into(),
+        ],
+        fmm::types::Primitive::Integer32,
+        fmm::types::CallingConvention::Target,
+    )
+}
@@ -1,4 +1,4 @@
-use super::{expression, reference_count, types, CompileError};
+use super::{expression, reference_count, thunk_lock, types, CompileError};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
@@ -86,6 +86,36 @@ pub fn compile_closure_content(
     .into()
 }
 
+pub fn compile_closure(
+    builder: &fmm::build::InstructionBuilder,
+    definition: &eir::ir::Definition,
+    entry_function: impl Into<fmm::build::TypedExpression>,
+    drop_function: impl Into<fmm::build::TypedExpression>,
+    free_variables: Vec<fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let closure_pointer =
+        reference_count::allocate_heap(builder, types::compile_sized_closure(definition, types))?;
+
+    builder.store(
+        compile_closure_content(entry_function, drop_function, free_variables),
+        closure_pointer.clone(),
+    );
+
+    if definition.is_thunk() {
+        builder.store(
+            fmm::ir::Primitive::PointerInteger(thunk_lock::NO_OWNER),
+            fmm::build::record_address(fmm::build::record_address(closure_pointer.clone(), 3)?, 1)?,
+        );
+    }
+
+    Ok(fmm::build::bit_cast(
+        fmm::types::Pointer::new(types::compile_unsized_closure(definition.type_(), types)),
+        closure_pointer,
+    )
+    .into())
+}
+
 pub fn compile_drop_function(
     module_builder: &fmm::build::ModuleBuilder,
     definition: &eir::ir::Definition,
@@ -126,10 +156,15 @@ pub fn compile_normal_thunk_drop_function(
             reference_count::drop_expression(
                 builder,
                 &builder.load(fmm::build::union_address(
-                    fmm::build::bit_cast(
-                        fmm::types::Pointer::new(types::compile_closure_payload(definition, types)),
-                        environment_pointer.clone(),
-                    ),
+                    fmm::build::record_address(
+                        fmm::build::bit_cast(
+                            fmm::types::Pointer::new(types::compile_thunk_payload(
+                                definition, types,
+                            )),
+                            environment_pointer.clone(),
+                        ),
+                        0,
+                    )?,
                     1,
                 )?)?,
                 definition.result_type(),
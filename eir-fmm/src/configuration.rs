@@ -0,0 +1,51 @@
+// Names of runtime functions called by compiled programs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Configuration {
+    wait_function_name: String,
+    thread_id_function_name: String,
+    thunk_reentrance_error_function_name: String,
+}
+
+impl Configuration {
+    pub fn new(
+        wait_function_name: impl Into<String>,
+        thread_id_function_name: impl Into<String>,
+        thunk_reentrance_error_function_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            wait_function_name: wait_function_name.into(),
+            thread_id_function_name: thread_id_function_name.into(),
+            thunk_reentrance_error_function_name: thunk_reentrance_error_function_name.into(),
+        }
+    }
+
+    // A function of `(pointer integer pointer, pointer integer) -> void`
+    // called by threads waiting for thunks locked by other threads. It must
+    // not return until the value at the pointer differs from the given one.
+    // It can spin, yield, or park threads meanwhile.
+    pub fn wait_function_name(&self) -> &str {
+        &self.wait_function_name
+    }
+
+    // A function of `() -> pointer integer` which returns a non-zero ID of
+    // the current thread.
+    pub fn thread_id_function_name(&self) -> &str {
+        &self.thread_id_function_name
+    }
+
+    // A function of `() -> void` called when a thunk is forced again during
+    // its own evaluation in the same thread. It must not return.
+    pub fn thunk_reentrance_error_function_name(&self) -> &str {
+        &self.thunk_reentrance_error_function_name
+    }
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self::new(
+            "eir_thunk_wait",
+            "eir_thread_id",
+            "eir_thunk_reentrance_error",
+        )
+    }
+}
@@ -1,58 +1,101 @@
 use super::error::CompileError;
-use crate::{closure, expression, reference_count, types};
+use crate::{closure, expression, reference_count, thunk_lock, types};
 use std::collections::HashMap;
 
 const CLOSURE_NAME: &str = "_closure";
 
+// Another definition in a recursive group referred to by a definition.
+pub enum Sibling<'a> {
+    // Siblings sharing closures with their callers are called directly by
+    // their entry functions. Definitions are also their own shared siblings
+    // then.
+    Shared(&'a eir::ir::Definition, fmm::build::TypedExpression),
+    // Closures of other siblings are created with their entry and drop
+    // functions.
+    Unshared(
+        &'a eir::ir::Definition,
+        fmm::build::TypedExpression,
+        fmm::build::TypedExpression,
+    ),
+}
+
 pub fn compile(
     module_builder: &fmm::build::ModuleBuilder,
     definition: &eir::ir::Definition,
     variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    compile_with_name(
+        module_builder,
+        &module_builder.generate_name(),
+        definition,
+        &[],
+        variables,
+        types,
+        sums,
+    )
+}
+
+pub fn compile_with_name(
+    module_builder: &fmm::build::ModuleBuilder,
+    name: &str,
+    definition: &eir::ir::Definition,
+    siblings: &[Sibling],
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     Ok(if definition.is_thunk() {
-        compile_thunk(module_builder, definition, variables, types)?
+        compile_initial_thunk_entry(
+            module_builder,
+            name,
+            definition,
+            siblings,
+            variables,
+            types,
+            sums,
+        )?
     } else {
-        compile_non_thunk(module_builder, definition, variables, types)?
+        compile_non_thunk(
+            module_builder,
+            name,
+            definition,
+            siblings,
+            variables,
+            types,
+            sums,
+        )?
     })
 }
 
 fn compile_non_thunk(
     module_builder: &fmm::build::ModuleBuilder,
+    name: &str,
     definition: &eir::ir::Definition,
+    siblings: &[Sibling],
     variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
-    module_builder.define_anonymous_function(
+    module_builder.define_function(
+        name,
         compile_arguments(definition, types),
         types::compile(definition.result_type(), types),
         |instruction_builder| {
-            Ok(instruction_builder.return_(compile_body(
+            compile_body(
                 module_builder,
                 &instruction_builder,
                 definition,
+                siblings,
                 variables,
                 types,
-            )?))
+                sums,
+            )
         },
         fmm::ir::FunctionDefinitionOptions::new()
-            .set_calling_convention(fmm::types::CallingConvention::Source),
-    )
-}
-
-fn compile_thunk(
-    module_builder: &fmm::build::ModuleBuilder,
-    definition: &eir::ir::Definition,
-    variables: &HashMap<String, fmm::build::TypedExpression>,
-    types: &HashMap<String, eir::types::RecordBody>,
-) -> Result<fmm::build::TypedExpression, CompileError> {
-    compile_initial_thunk_entry(
-        module_builder,
-        definition,
-        compile_normal_thunk_entry(module_builder, definition, types)?,
-        compile_locked_thunk_entry(module_builder, definition, types)?,
-        variables,
-        types,
+            .set_calling_convention(fmm::types::CallingConvention::Source)
+            .set_linkage(fmm::ir::Linkage::Internal),
     )
 }
 
@@ -60,45 +103,102 @@ fn compile_body(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
     definition: &eir::ir::Definition,
+    siblings: &[Sibling],
     variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
-) -> Result<fmm::build::TypedExpression, CompileError> {
-    let payload_pointer = compile_payload_pointer(definition, types)?;
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::ir::Block, CompileError> {
     let environment_pointer = if definition.is_thunk() {
-        fmm::build::union_address(payload_pointer, 0)?.into()
+        fmm::build::union_address(compile_thunk_content_pointer(definition, types)?, 0)?.into()
     } else {
-        payload_pointer
+        compile_payload_pointer(definition, types)?
     };
+    let environment = definition
+        .environment()
+        .iter()
+        .enumerate()
+        .map(|(index, free_variable)| -> Result<_, CompileError> {
+            let value = instruction_builder.load(fmm::build::record_address(
+                environment_pointer.clone(),
+                index,
+            )?)?;
+
+            reference_count::clone_expression(
+                instruction_builder,
+                &value,
+                free_variable.type_(),
+                types,
+            )?;
+
+            Ok((free_variable.name().into(), value))
+        })
+        .collect::<Result<HashMap<String, _>, _>>()?;
+    let entry_functions = siblings
+        .iter()
+        .filter_map(|sibling| match sibling {
+            Sibling::Shared(sibling, entry_function) => {
+                Some((sibling.name().into(), entry_function.clone()))
+            }
+            Sibling::Unshared(..) => None,
+        })
+        .collect::<HashMap<String, _>>();
+    // Shared siblings are given clones of current closures as they have the
+    // same environments. Closures of unshared siblings are created on every
+    // call so that closures in a recursive group never refer to each other.
+    // Thunks are never siblings as type checking rejects them in groups.
+    let siblings = siblings
+        .iter()
+        .filter(|sibling| {
+            !matches!(sibling, Sibling::Shared(sibling, _) if sibling.name() == definition.name())
+        })
+        .map(|sibling| -> Result<_, CompileError> {
+            Ok(match sibling {
+                Sibling::Shared(sibling, _) => {
+                    let closure_pointer = compile_closure_pointer(sibling.type_(), types)?;
+
+                    reference_count::clone_function(instruction_builder, &closure_pointer)?;
+
+                    (sibling.name().into(), closure_pointer)
+                }
+                Sibling::Unshared(sibling, entry_function, drop_function) => (
+                    sibling.name().into(),
+                    closure::compile_closure(
+                        instruction_builder,
+                        sibling,
+                        entry_function.clone(),
+                        drop_function.clone(),
+                        sibling
+                            .environment()
+                            .iter()
+                            .map(|free_variable| -> Result<_, CompileError> {
+                                let value = environment[free_variable.name()].clone();
 
-    expression::compile(
+                                reference_count::clone_expression(
+                                    instruction_builder,
+                                    &value,
+                                    free_variable.type_(),
+                                    types,
+                                )?;
+
+                                Ok(value)
+                            })
+                            .collect::<Result<_, _>>()?,
+                        types,
+                    )?,
+                ),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    expression::compile_tail(
         module_builder,
         instruction_builder,
         definition.body(),
         &variables
             .clone()
             .into_iter()
-            .chain(
-                definition
-                    .environment()
-                    .iter()
-                    .enumerate()
-                    .map(|(index, free_variable)| -> Result<_, CompileError> {
-                        let value = instruction_builder.load(fmm::build::record_address(
-                            environment_pointer.clone(),
-                            index,
-                        )?)?;
-
-                        reference_count::clone_expression(
-                            instruction_builder,
-                            &value,
-                            free_variable.type_(),
-                            types,
-                        )?;
-
-                        Ok((free_variable.name().into(), value))
-                    })
-                    .collect::<Result<Vec<_>, _>>()?,
-            )
+            .chain(environment)
+            .chain(siblings)
             .chain(vec![(
                 definition.name().into(),
                 compile_closure_pointer(definition.type_(), types)?,
@@ -110,24 +210,30 @@ fn compile_body(
                 )
             }))
             .collect(),
+        &entry_functions,
         types,
+        sums,
     )
 }
 
 fn compile_initial_thunk_entry(
     module_builder: &fmm::build::ModuleBuilder,
+    entry_function_name: &str,
     definition: &eir::ir::Definition,
-    normal_entry_function: fmm::build::TypedExpression,
-    lock_entry_function: fmm::build::TypedExpression,
+    siblings: &[Sibling],
     variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
-    let entry_function_name = module_builder.generate_name();
+    let body_function =
+        compile_thunk_body(module_builder, definition, siblings, variables, types, sums)?;
+    let normal_entry_function = compile_normal_thunk_entry(module_builder, definition, types)?;
+    let lock_entry_function = compile_locked_thunk_entry(module_builder, definition, types)?;
     let entry_function_type = types::compile_entry_function(definition, types);
     let arguments = compile_arguments(definition, types);
 
     module_builder.define_function(
-        &entry_function_name,
+        entry_function_name,
         arguments.clone(),
         types::compile(definition.result_type(), types),
         |instruction_builder| {
@@ -136,18 +242,26 @@ fn compile_initial_thunk_entry(
             instruction_builder.if_(
                 instruction_builder.compare_and_swap(
                     entry_function_pointer.clone(),
-                    fmm::build::variable(&entry_function_name, entry_function_type.clone()),
+                    fmm::build::variable(entry_function_name, entry_function_type.clone()),
                     lock_entry_function.clone(),
                     fmm::ir::AtomicOrdering::Acquire,
                     fmm::ir::AtomicOrdering::Relaxed,
                 ),
                 |instruction_builder| -> Result<_, CompileError> {
-                    let value = compile_body(
-                        module_builder,
-                        &instruction_builder,
-                        definition,
-                        variables,
-                        types,
+                    instruction_builder.atomic_store(
+                        thunk_lock::compile_thread_id(&instruction_builder)?,
+                        compile_thunk_owner_pointer(definition, types)?,
+                        fmm::ir::AtomicOrdering::Relaxed,
+                    );
+
+                    let value = instruction_builder.call(
+                        body_function.clone(),
+                        arguments
+                            .iter()
+                            .map(|argument| {
+                                fmm::build::variable(argument.name(), argument.type_().clone())
+                            })
+                            .collect(),
                     )?;
 
                     reference_count::clone_expression(
@@ -205,6 +319,35 @@ fn compile_initial_thunk_entry(
     )
 }
 
+// Thunk bodies are compiled into separate functions so that values returned
+// early by try operations are also stored as thunk values.
+fn compile_thunk_body(
+    module_builder: &fmm::build::ModuleBuilder,
+    definition: &eir::ir::Definition,
+    siblings: &[Sibling],
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    module_builder.define_anonymous_function(
+        compile_arguments(definition, types),
+        types::compile(definition.result_type(), types),
+        |instruction_builder| {
+            compile_body(
+                module_builder,
+                &instruction_builder,
+                definition,
+                siblings,
+                variables,
+                types,
+                sums,
+            )
+        },
+        fmm::ir::FunctionDefinitionOptions::new()
+            .set_calling_convention(fmm::types::CallingConvention::Source),
+    )
+}
+
 fn compile_normal_thunk_entry(
     module_builder: &fmm::build::ModuleBuilder,
     definition: &eir::ir::Definition,
@@ -249,8 +392,14 @@ fn compile_locked_thunk_entry(
                         ),
                     ),
                 )?,
-                // TODO Return to handle thunk locks asynchronously.
-                |instruction_builder| Ok(instruction_builder.unreachable()),
+                |instruction_builder| {
+                    compile_locked_body(
+                        &instruction_builder,
+                        &entry_function_name,
+                        definition,
+                        types,
+                    )
+                },
                 |instruction_builder| compile_normal_body(&instruction_builder, definition, types),
             )?;
 
@@ -262,6 +411,52 @@ fn compile_locked_thunk_entry(
     )
 }
 
+// Threads other than ones evaluating thunks wait until the thunks are unlocked
+// and then read their values.
+fn compile_locked_body(
+    instruction_builder: &fmm::build::InstructionBuilder,
+    entry_function_name: &str,
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::ir::Block, CompileError> {
+    instruction_builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            instruction_builder.atomic_load(
+                compile_thunk_owner_pointer(definition, types)?,
+                fmm::ir::AtomicOrdering::Relaxed,
+            )?,
+            thunk_lock::compile_thread_id(instruction_builder)?,
+        )?,
+        |instruction_builder| -> Result<_, CompileError> {
+            thunk_lock::compile_reentrance_error(&instruction_builder)?;
+
+            Ok(instruction_builder.unreachable())
+        },
+        |instruction_builder| -> Result<_, CompileError> {
+            let entry_function_pointer = compile_entry_function_pointer(definition, types)?;
+
+            thunk_lock::compile_wait(
+                &instruction_builder,
+                entry_function_pointer.clone(),
+                fmm::build::variable(
+                    entry_function_name,
+                    types::compile_entry_function(definition, types),
+                ),
+            )?;
+
+            // This synchronizes with the release store of normal entry
+            // functions so that thunk values are visible.
+            instruction_builder
+                .atomic_load(entry_function_pointer, fmm::ir::AtomicOrdering::Acquire)?;
+
+            compile_normal_body(&instruction_builder, definition, types)
+        },
+    )?;
+
+    Ok(instruction_builder.unreachable())
+}
+
 fn compile_normal_body(
     instruction_builder: &fmm::build::InstructionBuilder,
     definition: &eir::ir::Definition,
@@ -319,7 +514,21 @@ fn compile_thunk_value_pointer(
     definition: &eir::ir::Definition,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
-    Ok(fmm::build::union_address(compile_payload_pointer(definition, types)?, 1)?.into())
+    Ok(fmm::build::union_address(compile_thunk_content_pointer(definition, types)?, 1)?.into())
+}
+
+fn compile_thunk_content_pointer(
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(fmm::build::record_address(compile_payload_pointer(definition, types)?, 0)?.into())
+}
+
+fn compile_thunk_owner_pointer(
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(fmm::build::record_address(compile_payload_pointer(definition, types)?, 1)?.into())
 }
 
 fn compile_payload_pointer(
@@ -6,7 +6,6 @@ use std::{
 #[derive(Clone, Debug, PartialEq)]
 pub enum CompileError {
     FmmBuild(fmm::build::BuildError),
-    NestedVariant,
     ReferenceCount(eir::analysis::ReferenceCountError),
     TypeCheck(eir::analysis::TypeCheckError),
 }
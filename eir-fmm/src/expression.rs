@@ -1,6 +1,7 @@
 use super::error::CompileError;
 use crate::{
-    closure, entry_function, function_application, records, reference_count, types, variant,
+    byte_strings, closure, entry_function, function_application, integers, numbers,
+    numeric_conversion, records, reference_count, sum, switch, type_information, types, variant,
 };
 use std::collections::HashMap;
 
@@ -13,7 +14,9 @@ pub fn compile(
     instruction_builder: &fmm::build::InstructionBuilder,
     expression: &eir::ir::Expression,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     let compile = |expression, variables| {
         compile(
@@ -21,7 +24,9 @@ pub fn compile(
             instruction_builder,
             expression,
             variables,
+            entry_functions,
             types,
+            sums,
         )
     };
 
@@ -31,22 +36,48 @@ pub fn compile(
             instruction_builder,
             operation,
             variables,
+            entry_functions,
             types,
-        )?
-        .into(),
+            sums,
+        )?,
         eir::ir::Expression::Boolean(boolean) => fmm::ir::Primitive::Boolean(*boolean).into(),
-        eir::ir::Expression::Case(case) => {
-            compile_case(module_builder, instruction_builder, case, variables, types)?
+        eir::ir::Expression::BooleanOperation(operation) => compile_boolean_operation(
+            module_builder,
+            instruction_builder,
+            operation,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )?,
+        eir::ir::Expression::ByteStringConcatenation(concatenation) => {
+            byte_strings::compile_concatenation(
+                instruction_builder,
+                compile(concatenation.lhs(), variables)?,
+                compile(concatenation.rhs(), variables)?,
+            )?
         }
+        eir::ir::Expression::ByteStringLength(length) => {
+            byte_strings::compile_length(instruction_builder, compile(length.string(), variables)?)?
+        }
+        eir::ir::Expression::ByteStringSlice(slice) => byte_strings::compile_slice(
+            instruction_builder,
+            compile(slice.string(), variables)?,
+            compile(slice.start(), variables)?,
+            compile(slice.end(), variables)?,
+        )?,
+        eir::ir::Expression::Case(case) => compile_case(
+            module_builder,
+            instruction_builder,
+            case,
+            variables,
+            entry_functions,
+            types,
+            sums,
+            false,
+        )?,
         eir::ir::Expression::CloneVariables(clone) => {
-            for (variable, type_) in clone.variables() {
-                reference_count::clone_expression(
-                    instruction_builder,
-                    &variables[variable],
-                    type_,
-                    types,
-                )?;
-            }
+            compile_clone_variables(instruction_builder, clone, variables, types)?;
 
             compile(clone.expression(), variables)?
         }
@@ -55,47 +86,139 @@ pub fn compile(
             instruction_builder,
             operation,
             variables,
+            entry_functions,
             types,
+            sums,
         )?
         .into(),
         eir::ir::Expression::DropVariables(drop) => {
-            for (variable, type_) in drop.variables() {
-                reference_count::drop_expression(
-                    instruction_builder,
-                    &variables[variable],
-                    type_,
-                    types,
-                )?;
-            }
+            compile_drop_variables(instruction_builder, drop, variables, types)?;
 
             compile(drop.expression(), variables)?
         }
-        eir::ir::Expression::FunctionApplication(application) => function_application::compile(
+        // Lazy values are forced by applying their thunks to empty tuples.
+        eir::ir::Expression::Force(force) => function_application::compile(
             module_builder,
             instruction_builder,
-            compile(application.first_function(), variables)?,
-            &application
+            compile(force.expression(), variables)?,
+            &[fmm::build::record(vec![]).into()],
+            &[&eir::types::Tuple::new(vec![]).into()],
+            types,
+        )?,
+        eir::ir::Expression::FunctionApplication(application) => {
+            let closure_pointer = compile(application.first_function(), variables)?;
+            let arguments = application
                 .arguments()
                 .into_iter()
                 .map(|argument| compile(argument, variables))
-                .collect::<Result<Vec<_>, CompileError>>()?,
-            &application.argument_types().into_iter().collect::<Vec<_>>(),
+                .collect::<Result<Vec<_>, CompileError>>()?;
+            let argument_types = application.argument_types().into_iter().collect::<Vec<_>>();
+
+            if let Some(entry_function) = find_entry_function(application, entry_functions) {
+                function_application::compile_entry_function_call(
+                    module_builder,
+                    instruction_builder,
+                    entry_function,
+                    closure_pointer,
+                    &arguments,
+                    &argument_types,
+                    types,
+                )?
+            } else {
+                function_application::compile(
+                    module_builder,
+                    instruction_builder,
+                    closure_pointer,
+                    &arguments,
+                    &argument_types,
+                    types,
+                )?
+            }
+        }
+        eir::ir::Expression::If(if_) => compile_if(
+            module_builder,
+            instruction_builder,
+            if_,
+            variables,
+            entry_functions,
             types,
+            sums,
+            false,
         )?,
-        eir::ir::Expression::If(if_) => {
-            compile_if(module_builder, instruction_builder, if_, variables, types)?
-        }
-        eir::ir::Expression::Let(let_) => {
-            compile_let(module_builder, instruction_builder, let_, variables, types)?
-        }
-        eir::ir::Expression::LetRecursive(let_recursive) => compile_let_recursive(
+        eir::ir::Expression::Integer(integer) => integers::compile(integer).into(),
+        eir::ir::Expression::Lazy(lazy) => compile_lazy(
+            module_builder,
+            instruction_builder,
+            lazy,
+            variables,
+            types,
+            sums,
+        )?,
+        eir::ir::Expression::Let(let_) => compile(
+            let_.expression(),
+            &compile_let_variables(
+                module_builder,
+                instruction_builder,
+                let_,
+                variables,
+                entry_functions,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LetRecord(let_) => compile(
+            let_.expression(),
+            &compile_let_record_variables(
+                module_builder,
+                instruction_builder,
+                let_,
+                variables,
+                entry_functions,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LetRecursive(let_recursive) => compile(
+            let_recursive.expression(),
+            &compile_let_recursive_variables(
+                module_builder,
+                instruction_builder,
+                let_recursive,
+                variables,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LiteralCase(case) => compile_literal_case(
             module_builder,
             instruction_builder,
-            let_recursive,
+            case,
             variables,
+            entry_functions,
             types,
+            sums,
+            false,
         )?,
+        eir::ir::Expression::Negation(negation) => {
+            let value = compile(negation.expression(), variables)?;
+
+            match negation.type_() {
+                eir::types::Type::Integer(integer) => integers::compile_negation(*integer, value)?,
+                _ => numbers::compile_negation(value)?,
+            }
+        }
+        eir::ir::Expression::Not(not) => fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            compile(not.expression(), variables)?,
+            fmm::ir::Primitive::Boolean(false),
+        )?
+        .into(),
         eir::ir::Expression::Number(number) => fmm::ir::Primitive::Float64(*number).into(),
+        eir::ir::Expression::NumericConversion(conversion) => numeric_conversion::compile(
+            instruction_builder,
+            conversion,
+            compile(conversion.argument(), variables)?,
+        )?,
         eir::ir::Expression::Record(record) => {
             let unboxed = fmm::build::record(
                 record
@@ -118,32 +241,43 @@ pub fn compile(
         }
         eir::ir::Expression::RecordElement(element) => {
             let record_type = element.type_().clone();
-            let element_index = element.index();
+            let element_index =
+                records::get_record_element_index(element.type_(), element.field(), types);
 
             let record = compile(element.record(), variables)?;
             let element = records::get_record_element(
                 instruction_builder,
                 &record,
                 element.type_(),
-                element.index(),
+                element_index,
                 types,
             )?;
 
             reference_count::clone_expression(
                 instruction_builder,
                 &element,
-                &types[record_type.name()].elements()[element_index],
-                types,
-            )?;
-            reference_count::drop_expression(
-                instruction_builder,
-                &record,
-                &record_type.into(),
+                &types::get_record_elements(&record_type, types)[element_index],
                 types,
             )?;
+            reference_count::drop_expression(instruction_builder, &record, &record_type, types)?;
 
             element
         }
+        eir::ir::Expression::RecordUpdate(update) => {
+            let record = compile(update.record(), variables)?;
+            let fields = update
+                .fields()
+                .iter()
+                .map(|field| {
+                    Ok((
+                        records::get_record_element_index(update.type_(), field.field(), types),
+                        compile(field.expression(), variables)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, CompileError>>()?;
+
+            records::update_record(instruction_builder, &record, update.type_(), &fields, types)?
+        }
         eir::ir::Expression::ByteString(string) => {
             if string.value().is_empty() {
                 fmm::ir::Undefined::new(types::compile_string()).into()
@@ -173,24 +307,239 @@ pub fn compile(
                 )?
             }
         }
+        eir::ir::Expression::Sum(sum) => sum::compile(
+            instruction_builder,
+            &compile(sum.payload(), variables)?,
+            sum.type_(),
+            sum.constructor(),
+            sums,
+        )?,
+        eir::ir::Expression::SumCase(case) => compile_sum_case(
+            module_builder,
+            instruction_builder,
+            case,
+            variables,
+            entry_functions,
+            types,
+            sums,
+            false,
+        )?,
+        eir::ir::Expression::TryOperation(operation) => compile_try_operation(
+            module_builder,
+            instruction_builder,
+            operation,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )?,
         eir::ir::Expression::Variable(variable) => variables[variable.name()].clone(),
         eir::ir::Expression::Variant(variant) => fmm::build::record(vec![
             variant::compile_tag(variant.type_()),
             variant::compile_boxed_payload(
                 instruction_builder,
                 &compile(variant.payload(), variables)?,
+                variant.type_(),
             )?,
         ])
         .into(),
     })
 }
 
+// Compiles expressions in tail positions returning their values from
+// functions. Function applications marked as tail calls are compiled into
+// calls followed immediately by returns even in branches. Tail calls are not
+// guaranteed to be eliminated though. F-- has no loop, jump or tail call
+// instruction to lower them into, so deep recursion can still grow stacks.
+pub fn compile_tail(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    expression: &eir::ir::Expression,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::ir::Block, CompileError> {
+    let compile = |expression, variables| {
+        compile(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )
+    };
+    let compile_tail = |expression, variables| {
+        compile_tail(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )
+    };
+
+    Ok(match expression {
+        eir::ir::Expression::Case(case) => {
+            compile_case(
+                module_builder,
+                instruction_builder,
+                case,
+                variables,
+                entry_functions,
+                types,
+                sums,
+                true,
+            )?;
+
+            instruction_builder.unreachable()
+        }
+        eir::ir::Expression::CloneVariables(clone) => {
+            compile_clone_variables(instruction_builder, clone, variables, types)?;
+
+            compile_tail(clone.expression(), variables)?
+        }
+        eir::ir::Expression::DropVariables(drop) => {
+            compile_drop_variables(instruction_builder, drop, variables, types)?;
+
+            compile_tail(drop.expression(), variables)?
+        }
+        eir::ir::Expression::FunctionApplication(application) if application.is_tail() => {
+            let closure_pointer = compile(application.first_function(), variables)?;
+            let arguments = application
+                .arguments()
+                .into_iter()
+                .map(|argument| compile(argument, variables))
+                .collect::<Result<Vec<_>, CompileError>>()?;
+            let argument_types = application.argument_types().into_iter().collect::<Vec<_>>();
+
+            if let Some(entry_function) = find_entry_function(application, entry_functions) {
+                function_application::compile_tail_entry_function_call(
+                    module_builder,
+                    instruction_builder,
+                    entry_function,
+                    closure_pointer,
+                    &arguments,
+                    &argument_types,
+                    types,
+                )?
+            } else {
+                function_application::compile_tail(
+                    module_builder,
+                    instruction_builder,
+                    closure_pointer,
+                    &arguments,
+                    &argument_types,
+                    types,
+                )?
+            }
+        }
+        eir::ir::Expression::If(if_) => {
+            compile_if(
+                module_builder,
+                instruction_builder,
+                if_,
+                variables,
+                entry_functions,
+                types,
+                sums,
+                true,
+            )?;
+
+            instruction_builder.unreachable()
+        }
+        eir::ir::Expression::Let(let_) => compile_tail(
+            let_.expression(),
+            &compile_let_variables(
+                module_builder,
+                instruction_builder,
+                let_,
+                variables,
+                entry_functions,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LetRecord(let_) => compile_tail(
+            let_.expression(),
+            &compile_let_record_variables(
+                module_builder,
+                instruction_builder,
+                let_,
+                variables,
+                entry_functions,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LetRecursive(let_) => compile_tail(
+            let_.expression(),
+            &compile_let_recursive_variables(
+                module_builder,
+                instruction_builder,
+                let_,
+                variables,
+                types,
+                sums,
+            )?,
+        )?,
+        eir::ir::Expression::LiteralCase(case) => {
+            compile_literal_case(
+                module_builder,
+                instruction_builder,
+                case,
+                variables,
+                entry_functions,
+                types,
+                sums,
+                true,
+            )?;
+
+            instruction_builder.unreachable()
+        }
+        eir::ir::Expression::SumCase(case) => {
+            compile_sum_case(
+                module_builder,
+                instruction_builder,
+                case,
+                variables,
+                entry_functions,
+                types,
+                sums,
+                true,
+            )?;
+
+            instruction_builder.unreachable()
+        }
+        _ => instruction_builder.return_(compile(expression, variables)?),
+    })
+}
+
+// Definitions in the same recursive groups as current functions are applied
+// by calling their entry functions directly.
+fn find_entry_function(
+    application: &eir::ir::FunctionApplication,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+) -> Option<fmm::build::TypedExpression> {
+    match application.first_function() {
+        eir::ir::Expression::Variable(variable) => entry_functions.get(variable.name()).cloned(),
+        _ => None,
+    }
+}
+
 fn compile_if(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
     if_: &eir::ir::If,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+    tail: bool,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     let compile = |instruction_builder: &fmm::build::InstructionBuilder, expression| {
         compile(
@@ -198,200 +547,802 @@ fn compile_if(
             instruction_builder,
             expression,
             variables,
+            entry_functions,
+            types,
+            sums,
+        )
+    };
+
+    let compile_branch = |instruction_builder: &fmm::build::InstructionBuilder, expression| {
+        compile_branch(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
             types,
+            sums,
+            tail,
         )
     };
 
     instruction_builder.if_(
         compile(instruction_builder, if_.condition())?,
-        |instruction_builder| {
-            Ok(instruction_builder.branch(compile(&instruction_builder, if_.then())?))
-        },
-        |instruction_builder| {
-            Ok(instruction_builder.branch(compile(&instruction_builder, if_.else_())?))
-        },
+        |instruction_builder| compile_branch(&instruction_builder, if_.then()),
+        |instruction_builder| compile_branch(&instruction_builder, if_.else_()),
     )
 }
 
+// Branches in tail positions return their values from functions directly so
+// that tail calls in them are compiled into tail calls.
+fn compile_branch(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    expression: &eir::ir::Expression,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+    tail: bool,
+) -> Result<fmm::ir::Block, CompileError> {
+    Ok(if tail {
+        compile_tail(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )?
+    } else {
+        instruction_builder.branch(compile(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )?)
+    })
+}
+
+fn compile_boolean_operation(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    operation: &eir::ir::BooleanOperation,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let compile = |instruction_builder: &fmm::build::InstructionBuilder, expression| {
+        compile(
+            module_builder,
+            instruction_builder,
+            expression,
+            variables,
+            entry_functions,
+            types,
+            sums,
+        )
+    };
+    let compile_rhs =
+        |instruction_builder: fmm::build::InstructionBuilder| -> Result<_, CompileError> {
+            Ok(instruction_builder.branch(compile(&instruction_builder, operation.rhs())?))
+        };
+    // Left-hand sides determine results when right-hand sides are short-circuited.
+    let compile_short_circuit =
+        |instruction_builder: fmm::build::InstructionBuilder| -> Result<_, CompileError> {
+            Ok(instruction_builder.branch(fmm::ir::Primitive::Boolean(
+                operation.operator() == eir::ir::BooleanOperator::Or,
+            )))
+        };
+
+    let lhs = compile(instruction_builder, operation.lhs())?;
+
+    match operation.operator() {
+        eir::ir::BooleanOperator::And => {
+            instruction_builder.if_(lhs, compile_rhs, compile_short_circuit)
+        }
+        eir::ir::BooleanOperator::Or => {
+            instruction_builder.if_(lhs, compile_short_circuit, compile_rhs)
+        }
+    }
+}
+
+// Case expressions with at most this number of alternatives compare tags of
+// variants directly instead of searching type IDs.
+const MAXIMUM_TAG_COMPARISON_COUNT: usize = 4;
+
+// Alternatives are numbered densely in case expressions. Their indices are
+// found by binary search on type IDs first and then dispatched so that each
+// alternative is compiled only once. Case expressions with a few alternatives
+// test tags in order instead.
 fn compile_case(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
     case: &eir::ir::Case,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+    tail: bool,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     let argument = compile(
         module_builder,
         instruction_builder,
         case.argument(),
         variables,
+        entry_functions,
         types,
+        sums,
     )?;
+    let alternatives = case.alternatives();
 
-    Ok(compile_alternatives(
-        module_builder,
-        instruction_builder,
-        argument,
-        case.alternatives(),
-        case.default_alternative(),
-        variables,
-        types,
+    let compile_alternative = |instruction_builder: &fmm::build::InstructionBuilder,
+                               index: usize| {
+        let (name, expression, value) = if let Some(alternative) = alternatives.get(index) {
+            (
+                alternative.name(),
+                alternative.expression(),
+                variant::compile_payload(
+                    instruction_builder,
+                    &instruction_builder.deconstruct_record(
+                        argument.clone(),
+                        variant::VARIANT_PAYLOAD_ELEMENT_INDEX,
+                    )?,
+                    alternative.type_(),
+                    types,
+                )?,
+            )
+        } else if let Some(alternative) = case.default_alternative() {
+            (
+                alternative.name(),
+                alternative.expression(),
+                argument.clone(),
+            )
+        } else {
+            return Ok(instruction_builder.unreachable());
+        };
+
+        compile_branch(
+            module_builder,
+            instruction_builder,
+            expression,
+            &variables
+                .clone()
+                .into_iter()
+                .chain(vec![(name.into(), value)])
+                .collect(),
+            entry_functions,
+            types,
+            sums,
+            tail,
+        )
+    };
+
+    if (1..=MAXIMUM_TAG_COMPARISON_COUNT).contains(&alternatives.len()) {
+        let tag = instruction_builder
+            .deconstruct_record(argument.clone(), variant::VARIANT_TAG_ELEMENT_INDEX)?;
+
+        switch::compile_chain(
+            instruction_builder,
+            0..alternatives.len() + 1,
+            &|_, index| compile_tag_comparison(&tag, alternatives[index].type_()),
+            &compile_alternative,
+        )
+    } else {
+        switch::compile(
+            instruction_builder,
+            &compile_alternative_index(instruction_builder, &argument, alternatives)?,
+            0..alternatives.len() + 1,
+            &compile_alternative,
+        )
+    }
+}
+
+// Variants not matched by any alternatives get the number of alternatives as
+// their indices.
+fn compile_alternative_index(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    alternatives: &[eir::ir::Alternative],
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let tag = builder.deconstruct_record(argument.clone(), variant::VARIANT_TAG_ELEMENT_INDEX)?;
+    let id = builder.deconstruct_record(
+        builder.load(tag.clone())?,
+        type_information::TYPE_INFORMATION_ID_ELEMENT_INDEX,
+    )?;
+    let mut ids = alternatives
+        .iter()
+        .enumerate()
+        .map(|(index, alternative)| (type_information::compile_id(alternative.type_()), index))
+        .collect::<Vec<_>>();
+
+    // Earlier alternatives of the same type come first.
+    ids.sort_unstable();
+
+    switch::search(
+        builder,
+        &ids,
+        &|key| {
+            Ok(fmm::build::comparison_operation(
+                fmm::ir::ComparisonOperator::LessThan(false),
+                id.clone(),
+                fmm::ir::Primitive::Integer64(key),
+            )?
+            .into())
+        },
+        // Type IDs can collide. So we compare tags themselves at last.
+        &|builder, indices| {
+            switch::chain(
+                builder,
+                indices,
+                &|_, index| compile_tag_comparison(&tag, alternatives[index].type_()),
+                alternatives.len(),
+            )
+        },
+    )
+}
+
+fn compile_tag_comparison(
+    tag: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(fmm::build::comparison_operation(
+        fmm::ir::ComparisonOperator::Equal,
+        fmm::build::bit_cast(fmm::types::Primitive::PointerInteger, tag.clone()),
+        fmm::build::bit_cast(
+            fmm::types::Primitive::PointerInteger,
+            variant::compile_tag(type_),
+        ),
     )?
-    .unwrap())
+    .into())
 }
 
-fn compile_alternatives(
+// Literal alternatives are numbered densely like variant ones. Their indices
+// are found by binary search on literal values or string lengths.
+fn compile_literal_case(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
-    argument: fmm::build::TypedExpression,
-    alternatives: &[eir::ir::Alternative],
-    default_alternative: Option<&eir::ir::DefaultAlternative>,
+    case: &eir::ir::LiteralCase,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
-) -> Result<Option<fmm::build::TypedExpression>, CompileError> {
-    Ok(match alternatives {
-        [] => default_alternative
-            .map(|alternative| {
-                compile(
-                    module_builder,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+    tail: bool,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let argument = compile(
+        module_builder,
+        instruction_builder,
+        case.argument(),
+        variables,
+        entry_functions,
+        types,
+        sums,
+    )?;
+    let alternatives = case.alternatives();
+
+    switch::compile(
+        instruction_builder,
+        &compile_literal_index(
+            module_builder,
+            instruction_builder,
+            &argument,
+            case.type_(),
+            alternatives,
+            types,
+            sums,
+        )?,
+        0..alternatives.len() + 1,
+        &|instruction_builder, index| {
+            let (expression, variables) = if let Some(alternative) = alternatives.get(index) {
+                reference_count::drop_expression(
                     instruction_builder,
+                    &argument,
+                    case.type_(),
+                    types,
+                )?;
+
+                (alternative.expression(), variables.clone())
+            } else if let Some(alternative) = case.default_alternative() {
+                (
                     alternative.expression(),
-                    &variables
+                    variables
                         .clone()
                         .into_iter()
-                        .chain(vec![(alternative.name().into(), argument)])
+                        .chain(vec![(alternative.name().into(), argument.clone())])
                         .collect(),
-                    types,
                 )
-            })
-            .transpose()?,
-        [alternative, ..] => Some(instruction_builder.if_(
-            fmm::build::comparison_operation(
-                fmm::ir::ComparisonOperator::Equal,
-                fmm::build::bit_cast(
-                    fmm::types::Primitive::PointerInteger,
-                    instruction_builder.deconstruct_record(argument.clone(), 0)?,
-                ),
-                fmm::build::bit_cast(
-                    fmm::types::Primitive::PointerInteger,
-                    variant::compile_tag(alternative.type_()),
-                ),
-            )?,
-            |instruction_builder| -> Result<_, CompileError> {
-                Ok(instruction_builder.branch(compile(
+            } else {
+                return Ok(instruction_builder.unreachable());
+            };
+
+            compile_branch(
+                module_builder,
+                instruction_builder,
+                expression,
+                &variables,
+                entry_functions,
+                types,
+                sums,
+                tail,
+            )
+        },
+    )
+}
+
+// Literals equal to earlier ones never match and neither do NaNs.
+fn compile_literal_index(
+    module_builder: &fmm::build::ModuleBuilder,
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+    alternatives: &[eir::ir::LiteralAlternative],
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let literals = alternatives
+        .iter()
+        .enumerate()
+        .filter(|(index, alternative)| {
+            !matches!(alternative.literal(), eir::ir::Literal::Number(number) if number.is_nan())
+                && alternatives[..*index]
+                    .iter()
+                    .all(|other| other.literal() != alternative.literal())
+        })
+        .map(|(index, alternative)| (alternative.literal(), index))
+        .collect::<Vec<_>>();
+
+    let compile_leaf = |builder: &fmm::build::InstructionBuilder, indices: &[usize]| {
+        switch::chain(
+            builder,
+            indices,
+            &|builder, index| {
+                let literal = alternatives[index].literal();
+                let value = compile(
                     module_builder,
-                    &instruction_builder,
-                    alternative.expression(),
-                    &variables
-                        .clone()
-                        .into_iter()
-                        .chain(vec![(
-                            alternative.name().into(),
-                            variant::compile_unboxed_payload(
-                                &instruction_builder,
-                                &instruction_builder.deconstruct_record(argument.clone(), 1)?,
-                                alternative.type_(),
-                                types,
-                            )?,
-                        )])
-                        .collect(),
+                    builder,
+                    &literal.clone().into(),
+                    &HashMap::new(),
+                    &HashMap::new(),
                     types,
-                )?))
-            },
-            |instruction_builder| {
-                Ok(
-                    if let Some(expression) = compile_alternatives(
-                        module_builder,
-                        &instruction_builder,
+                    sums,
+                )?;
+
+                Ok(match literal {
+                    eir::ir::Literal::ByteString(_) => {
+                        byte_strings::compile_equality(builder, argument, &value)?
+                    }
+                    _ => fmm::build::comparison_operation(
+                        fmm::ir::ComparisonOperator::Equal,
                         argument.clone(),
-                        &alternatives[1..],
-                        default_alternative,
-                        variables,
-                        types,
-                    )? {
-                        instruction_builder.branch(expression)
-                    } else {
-                        instruction_builder.unreachable()
-                    },
+                        value,
+                    )?
+                    .into(),
+                })
+            },
+            alternatives.len(),
+        )
+    };
+    let compile_less_than = |signed, value: fmm::build::TypedExpression| {
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::LessThan(signed),
+            argument.clone(),
+            value,
+        )
+        .map(fmm::build::TypedExpression::from)
+        .map_err(CompileError::from)
+    };
+
+    match type_ {
+        eir::types::Type::Boolean => switch::search(
+            builder,
+            &sort_literal_keys(&literals, |literal| {
+                matches!(literal, eir::ir::Literal::Boolean(true))
+            }),
+            &|key| compile_less_than(false, fmm::ir::Primitive::Boolean(key).into()),
+            &compile_leaf,
+        ),
+        eir::types::Type::ByteString => {
+            let length = byte_strings::compile_raw_length(builder, argument)?;
+
+            switch::search(
+                builder,
+                &sort_literal_keys(&literals, |literal| match literal {
+                    eir::ir::Literal::ByteString(string) => string.value().len(),
+                    _ => unreachable!("string literal expected"),
+                }),
+                &|key| {
+                    Ok(fmm::build::comparison_operation(
+                        fmm::ir::ComparisonOperator::LessThan(false),
+                        length.clone(),
+                        fmm::ir::Primitive::PointerInteger(key as i64),
+                    )?
+                    .into())
+                },
+                &compile_leaf,
+            )
+        }
+        eir::types::Type::Integer(integer_type) => switch::search(
+            builder,
+            &sort_literal_keys(&literals, |literal| match literal {
+                eir::ir::Literal::Integer(integer) => integers::get_ordered_value(integer),
+                _ => unreachable!("integer literal expected"),
+            }),
+            &|key| {
+                compile_less_than(
+                    integer_type.is_signed(),
+                    integers::compile(&eir::ir::Integer::new(*integer_type, key as u64)).into(),
                 )
             },
-        )?),
-    })
+            &compile_leaf,
+        ),
+        eir::types::Type::Number => switch::search(
+            builder,
+            &sort_literal_keys(&literals, |literal| match literal {
+                eir::ir::Literal::Number(number) => *number,
+                _ => unreachable!("number literal expected"),
+            }),
+            &|key| compile_less_than(true, fmm::ir::Primitive::Float64(key).into()),
+            &compile_leaf,
+        ),
+        _ => unreachable!("literal types must be comparable"),
+    }
 }
 
-fn compile_let(
+fn sort_literal_keys<K: Copy + PartialOrd>(
+    literals: &[(&eir::ir::Literal, usize)],
+    get_key: impl Fn(&eir::ir::Literal) -> K,
+) -> Vec<(K, usize)> {
+    let mut keys = literals
+        .iter()
+        .map(|(literal, index)| (get_key(literal), *index))
+        .collect::<Vec<_>>();
+
+    keys.sort_by(|one, other| one.partial_cmp(other).unwrap());
+
+    keys
+}
+
+fn compile_sum_case(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
-    let_: &eir::ir::Let,
+    case: &eir::ir::SumCase,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+    tail: bool,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
-    let compile = |expression, variables| {
-        compile(
+    let argument = compile(
+        module_builder,
+        instruction_builder,
+        case.argument(),
+        variables,
+        entry_functions,
+        types,
+        sums,
+    )?;
+    let constructors = &sums[case.type_().name()];
+
+    switch::compile(
+        instruction_builder,
+        &instruction_builder.deconstruct_record(argument.clone(), sum::SUM_TAG_ELEMENT_INDEX)?,
+        0..constructors.len(),
+        &|instruction_builder, tag| {
+            let constructor = &constructors[tag];
+            let (name, expression, value) = if let Some(alternative) = case
+                .alternatives()
+                .iter()
+                .find(|alternative| alternative.type_() == &constructor.clone().into())
+            {
+                (
+                    alternative.name(),
+                    alternative.expression(),
+                    sum::compile_payload(instruction_builder, &argument, constructor, types)?,
+                )
+            } else if let Some(alternative) = case.default_alternative() {
+                (
+                    alternative.name(),
+                    alternative.expression(),
+                    argument.clone(),
+                )
+            } else {
+                return Ok(instruction_builder.unreachable());
+            };
+
+            compile_branch(
+                module_builder,
+                instruction_builder,
+                expression,
+                &variables
+                    .clone()
+                    .into_iter()
+                    .chain(vec![(name.into(), value)])
+                    .collect(),
+                entry_functions,
+                types,
+                sums,
+                tail,
+            )
+        },
+    )
+}
+
+// Values of `then` expressions are returned from the enclosing functions
+// directly as they are in tail positions.
+fn compile_try_operation(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    operation: &eir::ir::TryOperation,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let operand = compile(
+        module_builder,
+        instruction_builder,
+        operation.operand(),
+        variables,
+        entry_functions,
+        types,
+        sums,
+    )?;
+
+    instruction_builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            fmm::build::bit_cast(
+                fmm::types::Primitive::PointerInteger,
+                instruction_builder
+                    .deconstruct_record(operand.clone(), variant::VARIANT_TAG_ELEMENT_INDEX)?,
+            ),
+            fmm::build::bit_cast(
+                fmm::types::Primitive::PointerInteger,
+                variant::compile_tag(operation.type_()),
+            ),
+        )?,
+        |instruction_builder| -> Result<_, CompileError> {
+            let payload = variant::compile_payload(
+                &instruction_builder,
+                &instruction_builder
+                    .deconstruct_record(operand.clone(), variant::VARIANT_PAYLOAD_ELEMENT_INDEX)?,
+                operation.type_(),
+                types,
+            )?;
+
+            compile_tail(
+                module_builder,
+                &instruction_builder,
+                operation.then(),
+                &variables
+                    .clone()
+                    .into_iter()
+                    .chain(vec![(operation.name().into(), payload)])
+                    .collect(),
+                entry_functions,
+                types,
+                sums,
+            )
+        },
+        |instruction_builder| Ok(instruction_builder.branch(operand.clone())),
+    )
+}
+
+fn compile_clone_variables(
+    instruction_builder: &fmm::build::InstructionBuilder,
+    clone: &eir::ir::CloneVariables,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<(), CompileError> {
+    for (variable, type_) in clone.variables() {
+        reference_count::clone_expression(instruction_builder, &variables[variable], type_, types)?;
+    }
+
+    Ok(())
+}
+
+fn compile_drop_variables(
+    instruction_builder: &fmm::build::InstructionBuilder,
+    drop: &eir::ir::DropVariables,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<(), CompileError> {
+    for (variable, type_) in drop.variables() {
+        reference_count::drop_expression(instruction_builder, &variables[variable], type_, types)?;
+    }
+
+    Ok(())
+}
+
+fn compile_let_variables(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    let_: &eir::ir::Let,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<HashMap<String, fmm::build::TypedExpression>, CompileError> {
+    Ok(variables
+        .clone()
+        .drain()
+        .chain(vec![(
+            let_.name().into(),
+            compile(
+                module_builder,
+                instruction_builder,
+                let_.bound_expression(),
+                variables,
+                entry_functions,
+                types,
+                sums,
+            )?,
+        )])
+        .collect())
+}
+
+fn compile_let_record_variables(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    let_: &eir::ir::LetRecord,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<HashMap<String, fmm::build::TypedExpression>, CompileError> {
+    let elements = records::deconstruct_record(
+        instruction_builder,
+        &compile(
             module_builder,
             instruction_builder,
-            expression,
+            let_.bound_expression(),
             variables,
+            entry_functions,
             types,
-        )
-    };
+            sums,
+        )?,
+        let_.type_(),
+        types,
+    )?;
 
-    compile(
-        let_.expression(),
-        &variables
-            .clone()
-            .drain()
-            .chain(vec![(
-                let_.name().into(),
-                compile(let_.bound_expression(), variables)?,
-            )])
-            .collect(),
-    )
+    Ok(variables
+        .clone()
+        .drain()
+        .chain(
+            let_.elements()
+                .iter()
+                .map(|element| element.name().into())
+                .zip(elements),
+        )
+        .collect())
 }
 
-fn compile_let_recursive(
+fn compile_let_recursive_variables(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
     let_: &eir::ir::LetRecursive,
     variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
-) -> Result<fmm::build::TypedExpression, CompileError> {
-    let closure_pointer = reference_count::allocate_heap(
-        instruction_builder,
-        types::compile_sized_closure(let_.definition(), types),
-    )?;
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<HashMap<String, fmm::build::TypedExpression>, CompileError> {
+    // Entry functions are named in advance so that definitions in a group can
+    // create closures of each other.
+    let entry_function_names = let_
+        .definitions()
+        .iter()
+        .map(|_| module_builder.generate_name())
+        .collect::<Vec<_>>();
+    let closure_functions = let_
+        .definitions()
+        .iter()
+        .zip(&entry_function_names)
+        .map(|(definition, name)| -> Result<_, CompileError> {
+            Ok((
+                definition.name(),
+                (
+                    fmm::build::variable(name, types::compile_entry_function(definition, types)),
+                    closure::compile_drop_function(module_builder, definition, types)?,
+                ),
+            ))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    // Closures of definitions created here are shared with their siblings if
+    // possible so that calls in the group never allocate closures.
+    let closure_shared = eir::analysis::can_share_closure(let_.definitions());
+
+    for (definition, name) in let_.definitions().iter().zip(&entry_function_names) {
+        let siblings = eir::analysis::find_sibling_references(definition, let_.definitions());
+
+        entry_function::compile_with_name(
+            module_builder,
+            name,
+            definition,
+            &if closure_shared {
+                siblings
+                    .into_iter()
+                    .chain(vec![definition])
+                    .map(|sibling| {
+                        entry_function::Sibling::Shared(
+                            sibling,
+                            closure_functions[sibling.name()].0.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                siblings
+                    .into_iter()
+                    .map(|sibling| {
+                        let (entry_function, drop_function) = &closure_functions[sibling.name()];
+
+                        entry_function::Sibling::Unshared(
+                            sibling,
+                            entry_function.clone(),
+                            drop_function.clone(),
+                        )
+                    })
+                    .collect()
+            },
+            variables,
+            types,
+            sums,
+        )?;
+    }
 
-    instruction_builder.store(
-        closure::compile_closure_content(
-            entry_function::compile(module_builder, let_.definition(), variables, types)?,
-            closure::compile_drop_function(module_builder, let_.definition(), types)?,
-            let_.definition()
-                .environment()
+    Ok(variables
+        .clone()
+        .into_iter()
+        .chain(
+            let_.definitions()
                 .iter()
-                .map(|free_variable| variables[free_variable.name()].clone())
-                .collect(),
-        ),
-        closure_pointer.clone(),
-    );
+                .map(|definition| -> Result<_, CompileError> {
+                    let (entry_function, drop_function) = &closure_functions[definition.name()];
 
-    compile(
-        module_builder,
+                    Ok((
+                        definition.name().into(),
+                        closure::compile_closure(
+                            instruction_builder,
+                            definition,
+                            entry_function.clone(),
+                            drop_function.clone(),
+                            definition
+                                .environment()
+                                .iter()
+                                .map(|free_variable| variables[free_variable.name()].clone())
+                                .collect(),
+                            types,
+                        )?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+        .collect())
+}
+
+fn compile_lazy(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    lazy: &eir::ir::Lazy,
+    variables: &HashMap<String, fmm::build::TypedExpression>,
+    types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let definition = lazy.definition();
+
+    closure::compile_closure(
         instruction_builder,
-        let_.expression(),
-        &variables
-            .clone()
-            .into_iter()
-            .chain(vec![(
-                let_.definition().name().into(),
-                fmm::build::bit_cast(
-                    fmm::types::Pointer::new(types::compile_unsized_closure(
-                        let_.definition().type_(),
-                        types,
-                    )),
-                    closure_pointer,
-                )
-                .into(),
-            )])
+        definition,
+        entry_function::compile(module_builder, definition, variables, types, sums)?,
+        closure::compile_drop_function(module_builder, definition, types)?,
+        definition
+            .environment()
+            .iter()
+            .map(|free_variable| variables[free_variable.name()].clone())
             .collect(),
         types,
     )
@@ -402,8 +1353,10 @@ fn compile_arithmetic_operation(
     instruction_builder: &fmm::build::InstructionBuilder,
     operation: &eir::ir::ArithmeticOperation,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
-) -> Result<fmm::ir::ArithmeticOperation, CompileError> {
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
     let compile = |expression| {
         compile(
             module_builder,
@@ -411,24 +1364,44 @@ fn compile_arithmetic_operation(
             expression,
             variables,
             types,
+            sums,
         )
     };
 
     let lhs = compile(operation.lhs())?;
     let rhs = compile(operation.rhs())?;
 
-    Ok(match operation.operator() {
-        eir::ir::ArithmeticOperator::Add => {
-            fmm::build::arithmetic_operation(fmm::ir::ArithmeticOperator::Add, lhs, rhs)?
+    let compile_operation = |operator, lhs, rhs| -> Result<_, CompileError> {
+        Ok(fmm::build::arithmetic_operation(operator, lhs, rhs)?.into())
+    };
+
+    Ok(match (operation.operator(), operation.type_()) {
+        (eir::ir::ArithmeticOperator::Add, _) => {
+            compile_operation(fmm::ir::ArithmeticOperator::Add, lhs, rhs)?
         }
-        eir::ir::ArithmeticOperator::Subtract => {
-            fmm::build::arithmetic_operation(fmm::ir::ArithmeticOperator::Subtract, lhs, rhs)?
+        (eir::ir::ArithmeticOperator::Subtract, _) => {
+            compile_operation(fmm::ir::ArithmeticOperator::Subtract, lhs, rhs)?
         }
-        eir::ir::ArithmeticOperator::Multiply => {
-            fmm::build::arithmetic_operation(fmm::ir::ArithmeticOperator::Multiply, lhs, rhs)?
+        (eir::ir::ArithmeticOperator::Multiply, _) => {
+            compile_operation(fmm::ir::ArithmeticOperator::Multiply, lhs, rhs)?
         }
-        eir::ir::ArithmeticOperator::Divide => {
-            fmm::build::arithmetic_operation(fmm::ir::ArithmeticOperator::Divide, lhs, rhs)?
+        (eir::ir::ArithmeticOperator::Divide, eir::types::Type::Integer(integer)) => {
+            integers::compile_division(instruction_builder, *integer, lhs, rhs)?
+        }
+        (eir::ir::ArithmeticOperator::Divide, _) => {
+            compile_operation(fmm::ir::ArithmeticOperator::Divide, lhs, rhs)?
+        }
+        (eir::ir::ArithmeticOperator::Remainder, eir::types::Type::Integer(integer)) => {
+            integers::compile_remainder(instruction_builder, *integer, lhs, rhs)?
+        }
+        (eir::ir::ArithmeticOperator::Remainder, _) => {
+            numbers::compile_remainder(instruction_builder, lhs, rhs)?
+        }
+        (eir::ir::ArithmeticOperator::Power, eir::types::Type::Integer(integer)) => {
+            integers::compile_power(instruction_builder, *integer, lhs, rhs)?
+        }
+        (eir::ir::ArithmeticOperator::Power, _) => {
+            numbers::compile_power(instruction_builder, lhs, rhs)?
         }
     })
 }
@@ -438,7 +1411,9 @@ fn compile_comparison_operation(
     instruction_builder: &fmm::build::InstructionBuilder,
     operation: &eir::ir::ComparisonOperation,
     variables: &HashMap<String, fmm::build::TypedExpression>,
+    entry_functions: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<fmm::ir::ComparisonOperation, CompileError> {
     let compile = |expression| {
         compile(
@@ -446,26 +1421,40 @@ fn compile_comparison_operation(
             instruction_builder,
             expression,
             variables,
+            entry_functions,
             types,
+            sums,
         )
     };
 
     let lhs = compile(operation.lhs())?;
     let rhs = compile(operation.rhs())?;
+    let (lhs, rhs, signed) = match operation.type_() {
+        // Booleans are compared as unsigned integers where false is less than
+        // true.
+        eir::types::Type::Boolean => (lhs, rhs, false),
+        eir::types::Type::ByteString => (
+            byte_strings::compile_comparison(instruction_builder, lhs, rhs)?,
+            byte_strings::compile_ordering(0),
+            true,
+        ),
+        eir::types::Type::Integer(integer) => (lhs, rhs, integer.is_signed()),
+        _ => (lhs, rhs, true),
+    };
 
     Ok(fmm::build::comparison_operation(
         match operation.operator() {
             eir::ir::ComparisonOperator::Equal => fmm::ir::ComparisonOperator::Equal,
             eir::ir::ComparisonOperator::NotEqual => fmm::ir::ComparisonOperator::NotEqual,
             eir::ir::ComparisonOperator::GreaterThan => {
-                fmm::ir::ComparisonOperator::GreaterThan(true)
+                fmm::ir::ComparisonOperator::GreaterThan(signed)
             }
             eir::ir::ComparisonOperator::GreaterThanOrEqual => {
-                fmm::ir::ComparisonOperator::GreaterThanOrEqual(true)
+                fmm::ir::ComparisonOperator::GreaterThanOrEqual(signed)
             }
-            eir::ir::ComparisonOperator::LessThan => fmm::ir::ComparisonOperator::LessThan(true),
+            eir::ir::ComparisonOperator::LessThan => fmm::ir::ComparisonOperator::LessThan(signed),
             eir::ir::ComparisonOperator::LessThanOrEqual => {
-                fmm::ir::ComparisonOperator::LessThanOrEqual(true)
+                fmm::ir::ComparisonOperator::LessThanOrEqual(signed)
             }
         },
         lhs,
@@ -24,6 +24,84 @@ pub fn compile(
     )
 }
 
+// Tail calls return their results from callers directly in every branch of
+// arity checks. They are emitted as plain calls followed immediately by
+// returns, and nothing here guarantees that they reuse stack frames. That is
+// up to the CPS transformation and back ends of fmm.
+pub fn compile_tail(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    closure_pointer: fmm::build::TypedExpression,
+    arguments: &[fmm::build::TypedExpression],
+    argument_types: &[&eir::types::Type],
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::ir::Block, CompileError> {
+    compile_tail_with_min_arity(
+        module_builder,
+        instruction_builder,
+        closure_pointer,
+        arguments,
+        1,
+        argument_types,
+        types,
+    )
+}
+
+// Entry functions known statically are called directly with closures without
+// arity checks. So applications need at least as many arguments as the entry
+// functions take.
+pub fn compile_entry_function_call(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    entry_function: fmm::build::TypedExpression,
+    closure_pointer: fmm::build::TypedExpression,
+    arguments: &[fmm::build::TypedExpression],
+    argument_types: &[&eir::types::Type],
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let arity = types::get_arity(entry_function.type_().to_function().unwrap());
+
+    compile(
+        module_builder,
+        instruction_builder,
+        call_entry_function(
+            instruction_builder,
+            entry_function,
+            closure_pointer,
+            &arguments[..arity],
+        )?,
+        &arguments[arity..],
+        &argument_types[arity..],
+        types,
+    )
+}
+
+pub fn compile_tail_entry_function_call(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    entry_function: fmm::build::TypedExpression,
+    closure_pointer: fmm::build::TypedExpression,
+    arguments: &[fmm::build::TypedExpression],
+    argument_types: &[&eir::types::Type],
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::ir::Block, CompileError> {
+    let arity = types::get_arity(entry_function.type_().to_function().unwrap());
+
+    compile_tail(
+        module_builder,
+        instruction_builder,
+        call_entry_function(
+            instruction_builder,
+            entry_function,
+            closure_pointer,
+            &arguments[..arity],
+        )?,
+        &arguments[arity..],
+        &argument_types[arity..],
+        types,
+    )
+}
+
 fn compile_with_min_arity(
     module_builder: &fmm::build::ModuleBuilder,
     instruction_builder: &fmm::build::InstructionBuilder,
@@ -82,19 +160,95 @@ fn compile_with_min_arity(
     })
 }
 
+fn compile_tail_with_min_arity(
+    module_builder: &fmm::build::ModuleBuilder,
+    instruction_builder: &fmm::build::InstructionBuilder,
+    closure_pointer: fmm::build::TypedExpression,
+    arguments: &[fmm::build::TypedExpression],
+    min_arity: usize,
+    argument_types: &[&eir::types::Type],
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::ir::Block, CompileError> {
+    Ok(
+        if arguments.len() < min_arity
+            || types::get_arity(get_entry_function_type(&closure_pointer)) == min_arity
+        {
+            instruction_builder.return_(compile_with_min_arity(
+                module_builder,
+                instruction_builder,
+                closure_pointer,
+                arguments,
+                min_arity,
+                argument_types,
+                types,
+            )?)
+        } else {
+            instruction_builder.if_(
+                fmm::build::comparison_operation(
+                    fmm::ir::ComparisonOperator::Equal,
+                    closure::compile_load_arity(instruction_builder, closure_pointer.clone())?,
+                    expression::compile_arity(min_arity),
+                )?,
+                |instruction_builder| {
+                    compile_tail(
+                        module_builder,
+                        &instruction_builder,
+                        compile_direct_call(
+                            &instruction_builder,
+                            closure_pointer.clone(),
+                            &arguments[..min_arity],
+                        )?,
+                        &arguments[min_arity..],
+                        &argument_types[min_arity..],
+                        types,
+                    )
+                },
+                |instruction_builder| {
+                    compile_tail_with_min_arity(
+                        module_builder,
+                        &instruction_builder,
+                        closure_pointer.clone(),
+                        arguments,
+                        min_arity + 1,
+                        argument_types,
+                        types,
+                    )
+                },
+            )?;
+
+            instruction_builder.unreachable()
+        },
+    )
+}
+
 fn compile_direct_call(
     instruction_builder: &fmm::build::InstructionBuilder,
     closure_pointer: fmm::build::TypedExpression,
     arguments: &[fmm::build::TypedExpression],
 ) -> Result<fmm::build::TypedExpression, CompileError> {
-    Ok(instruction_builder.call(
+    call_entry_function(
+        instruction_builder,
         fmm::build::bit_cast(
             types::compile_curried_entry_function(
                 get_entry_function_type(&closure_pointer),
                 arguments.len(),
             ),
             closure::compile_load_entry_function(instruction_builder, closure_pointer.clone())?,
-        ),
+        )
+        .into(),
+        closure_pointer,
+        arguments,
+    )
+}
+
+fn call_entry_function(
+    instruction_builder: &fmm::build::InstructionBuilder,
+    entry_function: fmm::build::TypedExpression,
+    closure_pointer: fmm::build::TypedExpression,
+    arguments: &[fmm::build::TypedExpression],
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(instruction_builder.call(
+        entry_function,
         vec![
             fmm::build::bit_cast(types::compile_untyped_closure_pointer(), closure_pointer).into(),
         ]
@@ -1,5 +1,5 @@
 use super::error::CompileError;
-use crate::{closure, entry_function, expression, types};
+use crate::{closure, entry_function, expression, thunk_lock, types};
 use std::collections::HashMap;
 
 pub fn compile(
@@ -7,14 +7,15 @@ pub fn compile(
     definition: &eir::ir::Definition,
     global_variables: &HashMap<String, fmm::build::TypedExpression>,
     types: &HashMap<String, eir::types::RecordBody>,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
 ) -> Result<(), CompileError> {
     module_builder.define_variable(
         definition.name(),
         fmm::build::record(vec![
-            entry_function::compile(module_builder, definition, global_variables, types)?,
+            entry_function::compile(module_builder, definition, global_variables, types, sums)?,
             closure::compile_drop_function(module_builder, definition, types)?,
             expression::compile_arity(definition.arguments().iter().count()).into(),
-            fmm::ir::Undefined::new(types::compile_closure_payload(definition, types)).into(),
+            compile_payload(definition, types),
         ]),
         fmm::ir::VariableDefinitionOptions::new()
             .set_linkage(fmm::ir::Linkage::External)
@@ -23,3 +24,18 @@ pub fn compile(
 
     Ok(())
 }
+
+fn compile_payload(
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> fmm::build::TypedExpression {
+    if definition.is_thunk() {
+        fmm::build::record(vec![
+            fmm::ir::Undefined::new(types::compile_thunk_content(definition, types)).into(),
+            fmm::ir::Primitive::PointerInteger(thunk_lock::NO_OWNER).into(),
+        ])
+        .into()
+    } else {
+        fmm::ir::Undefined::new(types::compile_closure_payload(definition, types)).into()
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{error::CompileError, types};
+
+const POWER_FUNCTION_TYPES: [eir::types::Integer; 3] = [
+    eir::types::Integer::Unsigned8,
+    eir::types::Integer::Unsigned32,
+    eir::types::Integer::Unsigned64,
+];
+
+pub fn compile(integer: &eir::ir::Integer) -> fmm::ir::Primitive {
+    match integer.type_().bits() {
+        8 => fmm::ir::Primitive::Integer8(integer.value() as u8),
+        32 => fmm::ir::Primitive::Integer32(integer.value() as u32),
+        _ => fmm::ir::Primitive::Integer64(integer.value()),
+    }
+}
+
+// Integers are ordered as signed or unsigned ones depending on their types.
+pub fn get_ordered_value(integer: &eir::ir::Integer) -> i128 {
+    let type_ = integer.type_();
+
+    if type_.is_signed() {
+        let shift = 64 - type_.bits();
+
+        (((integer.value() << shift) as i64) >> shift) as i128
+    } else {
+        integer.value() as i128
+    }
+}
+
+pub fn compile_negation(
+    type_: eir::types::Integer,
+    value: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::arithmetic_operation(
+        fmm::ir::ArithmeticOperator::Subtract,
+        compile_constant(type_, 0),
+        value,
+    )?
+    .into())
+}
+
+// Division by zero results in zero and remainders by zero are dividends so
+// that `x == x / y * y + x % y` holds for any integers. Division of minimum
+// signed integers by minus one wraps around to the minimum integers.
+pub fn compile_division(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    compile_with_divisor(
+        builder,
+        type_,
+        &rhs,
+        compile_constant(type_, 0),
+        |builder| {
+            if type_.is_signed() {
+                compile_signed_division(builder, type_, lhs.clone(), rhs.clone())
+            } else {
+                Ok(fmm::build::arithmetic_operation(
+                    fmm::ir::ArithmeticOperator::Divide,
+                    lhs.clone(),
+                    rhs.clone(),
+                )?
+                .into())
+            }
+        },
+    )
+}
+
+// fmm divides integers as unsigned ones. So we divide absolute values of
+// signed integers and then negate their quotients if signs of their operands
+// are different.
+fn compile_signed_division(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let lhs_negative = compile_is_negative(type_, &lhs)?;
+    let rhs_negative = compile_is_negative(type_, &rhs)?;
+
+    compile_conditional_negation(
+        builder,
+        type_,
+        fmm::build::arithmetic_operation(
+            fmm::ir::ArithmeticOperator::Divide,
+            compile_conditional_negation(builder, type_, lhs, lhs_negative.clone())?,
+            compile_conditional_negation(builder, type_, rhs, rhs_negative.clone())?,
+        )?
+        .into(),
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::NotEqual,
+            lhs_negative,
+            rhs_negative,
+        )?
+        .into(),
+    )
+}
+
+pub fn compile_remainder(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    compile_with_divisor(builder, type_, &rhs, lhs.clone(), |builder| {
+        compile_non_zero_remainder(builder, type_, lhs.clone(), rhs.clone())
+    })
+}
+
+// Remainders of signed integers are computed from their absolute values and
+// have the same signs as dividends.
+fn compile_non_zero_remainder(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    if !type_.is_signed() {
+        return Ok(compile_unsigned_remainder(lhs, rhs)?);
+    }
+
+    let lhs_negative = compile_is_negative(type_, &lhs)?;
+    let rhs_negative = compile_is_negative(type_, &rhs)?;
+
+    compile_conditional_negation(
+        builder,
+        type_,
+        compile_unsigned_remainder(
+            compile_conditional_negation(builder, type_, lhs, lhs_negative.clone())?,
+            compile_conditional_negation(builder, type_, rhs, rhs_negative)?,
+        )?,
+        lhs_negative,
+    )
+}
+
+pub fn compile_power(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    base: fmm::build::TypedExpression,
+    exponent: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let compile_call = |builder: &fmm::build::InstructionBuilder, exponent| {
+        builder.call(
+            fmm::build::variable(
+                compile_power_function_name(type_),
+                compile_power_function_type(type_),
+            ),
+            vec![base.clone(), exponent],
+        )
+    };
+
+    if !type_.is_signed() {
+        return Ok(compile_call(builder, exponent)?);
+    }
+
+    // Powers with negative exponents are truncated reciprocals which are zero
+    // unless bases are one or minus one.
+    builder.if_(
+        compile_is_negative(type_, &exponent)?,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(builder.if_(
+                fmm::build::comparison_operation(
+                    fmm::ir::ComparisonOperator::Equal,
+                    base.clone(),
+                    compile_constant(type_, 1),
+                )?,
+                |builder| -> Result<_, CompileError> {
+                    Ok(builder.branch(compile_constant(type_, 1)))
+                },
+                |builder| {
+                    Ok(builder.branch(builder.if_(
+                        fmm::build::comparison_operation(
+                            fmm::ir::ComparisonOperator::Equal,
+                            base.clone(),
+                            compile_constant(type_, u64::MAX),
+                        )?,
+                        |builder| -> Result<_, CompileError> {
+                            Ok(builder.branch(compile_call(
+                                &builder,
+                                compile_negation(type_, exponent.clone())?,
+                            )?))
+                        },
+                        |builder| Ok(builder.branch(compile_constant(type_, 0))),
+                    )?))
+                },
+            )?))
+        },
+        |builder| Ok(builder.branch(compile_call(&builder, exponent.clone())?)),
+    )
+}
+
+pub fn compile_power_functions(
+    module_builder: &fmm::build::ModuleBuilder,
+) -> Result<(), CompileError> {
+    for type_ in POWER_FUNCTION_TYPES {
+        compile_power_function(module_builder, type_)?;
+    }
+
+    Ok(())
+}
+
+// Powers are computed by exponentiation by squaring with exponents regarded
+// as unsigned integers. Signed integers share the functions of unsigned ones
+// as their multiplication wraps around in the same way.
+fn compile_power_function(
+    module_builder: &fmm::build::ModuleBuilder,
+    type_: eir::types::Integer,
+) -> Result<(), CompileError> {
+    let base = fmm::build::variable("base", types::compile_integer(type_));
+    let exponent = fmm::build::variable("exponent", types::compile_integer(type_));
+
+    module_builder.define_function(
+        compile_power_function_name(type_),
+        vec![
+            fmm::ir::Argument::new("base", types::compile_integer(type_)),
+            fmm::ir::Argument::new("exponent", types::compile_integer(type_)),
+        ],
+        types::compile_integer(type_),
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.return_(builder.if_(
+                fmm::build::comparison_operation(
+                    fmm::ir::ComparisonOperator::Equal,
+                    exponent.clone(),
+                    compile_constant(type_, 0),
+                )?,
+                |builder| -> Result<_, CompileError> {
+                    Ok(builder.branch(compile_constant(type_, 1)))
+                },
+                |builder| {
+                    let power = builder.call(
+                        fmm::build::variable(
+                            compile_power_function_name(type_),
+                            compile_power_function_type(type_),
+                        ),
+                        vec![
+                            fmm::build::arithmetic_operation(
+                                fmm::ir::ArithmeticOperator::Multiply,
+                                base.clone(),
+                                base.clone(),
+                            )?
+                            .into(),
+                            fmm::build::arithmetic_operation(
+                                fmm::ir::ArithmeticOperator::Divide,
+                                exponent.clone(),
+                                compile_constant(type_, 2),
+                            )?
+                            .into(),
+                        ],
+                    )?;
+
+                    Ok(builder.branch(builder.if_(
+                        fmm::build::comparison_operation(
+                            fmm::ir::ComparisonOperator::Equal,
+                            fmm::build::bitwise_operation(
+                                fmm::ir::BitwiseOperator::And,
+                                exponent.clone(),
+                                compile_constant(type_, 1),
+                            )?,
+                            compile_constant(type_, 0),
+                        )?,
+                        |builder| -> Result<_, CompileError> { Ok(builder.branch(power.clone())) },
+                        |builder| {
+                            Ok(builder.branch(fmm::build::arithmetic_operation(
+                                fmm::ir::ArithmeticOperator::Multiply,
+                                power.clone(),
+                                base.clone(),
+                            )?))
+                        },
+                    )?))
+                },
+            )?))
+        },
+        fmm::ir::FunctionDefinitionOptions::new()
+            .set_calling_convention(fmm::types::CallingConvention::Target)
+            .set_linkage(fmm::ir::Linkage::Internal),
+    )?;
+
+    Ok(())
+}
+
+fn compile_power_function_name(type_: eir::types::Integer) -> String {
+    format!("integer_power_{}", type_.bits())
+}
+
+fn compile_power_function_type(type_: eir::types::Integer) -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![
+            types::compile_integer(type_).into(),
+            types::compile_integer(type_).into(),
+        ],
+        types::compile_integer(type_),
+        fmm::types::CallingConvention::Target,
+    )
+}
+
+fn compile_with_divisor(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    divisor: &fmm::build::TypedExpression,
+    zero_division_value: fmm::build::TypedExpression,
+    compile_division: impl Fn(
+        &fmm::build::InstructionBuilder,
+    ) -> Result<fmm::build::TypedExpression, CompileError>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::Equal,
+            divisor.clone(),
+            compile_constant(type_, 0),
+        )?,
+        |builder| -> Result<_, CompileError> { Ok(builder.branch(zero_division_value.clone())) },
+        |builder| Ok(builder.branch(compile_division(&builder)?)),
+    )
+}
+
+fn compile_unsigned_remainder(
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::arithmetic_operation(
+        fmm::ir::ArithmeticOperator::Subtract,
+        lhs.clone(),
+        fmm::build::arithmetic_operation(
+            fmm::ir::ArithmeticOperator::Multiply,
+            fmm::build::arithmetic_operation(
+                fmm::ir::ArithmeticOperator::Divide,
+                lhs,
+                rhs.clone(),
+            )?,
+            rhs,
+        )?,
+    )?
+    .into())
+}
+
+fn compile_is_negative(
+    type_: eir::types::Integer,
+    value: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::comparison_operation(
+        fmm::ir::ComparisonOperator::LessThan(true),
+        value.clone(),
+        compile_constant(type_, 0),
+    )?
+    .into())
+}
+
+fn compile_conditional_negation(
+    builder: &fmm::build::InstructionBuilder,
+    type_: eir::types::Integer,
+    value: fmm::build::TypedExpression,
+    condition: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        condition,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(compile_negation(type_, value.clone())?))
+        },
+        |builder| Ok(builder.branch(value.clone())),
+    )
+}
+
+fn compile_constant(type_: eir::types::Integer, value: u64) -> fmm::build::TypedExpression {
+    compile(&eir::ir::Integer::new(type_, value)).into()
+}
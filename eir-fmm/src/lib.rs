@@ -1,4 +1,6 @@
+mod byte_strings;
 mod closure;
+mod configuration;
 mod entry_function;
 mod error;
 mod expression;
@@ -7,29 +9,57 @@ mod foreign_definition;
 mod function_application;
 mod function_declaration;
 mod function_definition;
+mod integers;
+mod numbers;
+mod numeric_conversion;
 mod records;
 mod reference_count;
+mod sum;
+mod switch;
+mod thunk_lock;
 mod type_information;
 mod types;
 mod variant;
 
+pub use configuration::Configuration;
 pub use error::CompileError;
 use std::collections::HashMap;
 
 pub fn compile(module: &eir::ir::Module) -> Result<fmm::ir::Module, CompileError> {
+    compile_with_configuration(module, &Configuration::default())
+}
+
+pub fn compile_with_configuration(
+    module: &eir::ir::Module,
+    configuration: &Configuration,
+) -> Result<fmm::ir::Module, CompileError> {
     eir::analysis::check_types(module)?;
 
-    let module = eir::analysis::infer_environment(module);
+    let module = eir::analysis::convert_constants(module);
+    let module = eir::analysis::monomorphize(&module);
+    let module = eir::analysis::infer_environment(&module);
     let module = eir::analysis::count_references(&module)?;
 
     eir::analysis::check_types(&module)?;
 
+    let module = eir::analysis::mark_tail_calls(&module);
+
     let module_builder = fmm::build::ModuleBuilder::new();
     let types = module
         .type_definitions()
         .iter()
         .map(|definition| (definition.name().into(), definition.type_().clone()))
         .collect();
+    let sums = module
+        .sum_definitions()
+        .iter()
+        .map(|definition| (definition.name().into(), definition.constructors().to_vec()))
+        .collect();
+
+    numbers::compile_function_declarations(&module_builder);
+    byte_strings::compile_function_declarations(&module_builder);
+    integers::compile_power_functions(&module_builder)?;
+    thunk_lock::compile_runtime_functions(&module_builder, &module, configuration)?;
 
     for type_ in &eir::analysis::collect_variant_types(&module) {
         type_information::compile(&module_builder, type_, &types)?;
@@ -40,6 +70,11 @@ pub fn compile(module: &eir::ir::Module) -> Result<fmm::ir::Module, CompileError
         reference_count::compile_record_drop_function(&module_builder, definition, &types)?;
     }
 
+    for definition in module.sum_definitions() {
+        reference_count::compile_sum_clone_function(&module_builder, definition, &types)?;
+        reference_count::compile_sum_drop_function(&module_builder, definition, &types)?;
+    }
+
     for declaration in module.foreign_declarations() {
         foreign_declaration::compile_foreign_declaration(&module_builder, declaration, &types)?;
     }
@@ -51,7 +86,13 @@ pub fn compile(module: &eir::ir::Module) -> Result<fmm::ir::Module, CompileError
     let global_variables = compile_global_variables(&module, &types)?;
 
     for definition in module.definitions() {
-        function_definition::compile(&module_builder, definition, &global_variables, &types)?;
+        function_definition::compile(
+            &module_builder,
+            definition,
+            &global_variables,
+            &types,
+            &sums,
+        )?;
     }
 
     let function_types = module
@@ -141,7 +182,11 @@ mod tests {
     use super::*;
 
     fn compile_module(module: &eir::ir::Module) {
-        let mut module = compile(module).unwrap();
+        let mut module = compile_with_configuration(
+            module,
+            &Configuration::new("wait", "thread_id", "thunk_reentrance_error"),
+        )
+        .unwrap();
 
         compile_final_module(&module);
         fmm::analysis::cps::transform(&mut module, fmm::types::Record::new(vec![])).unwrap();
@@ -357,6 +402,7 @@ mod tests {
                         eir::ir::Argument::new("y", eir::types::Type::Number),
                     ],
                     eir::ir::ArithmeticOperation::new(
+                        eir::types::Type::Number,
                         eir::ir::ArithmeticOperator::Add,
                         eir::ir::Variable::new("x"),
                         eir::ir::Variable::new("y"),
@@ -390,6 +436,169 @@ mod tests {
                 ),
             ]));
         }
+
+        #[test]
+        fn compile_recursive_thunk() {
+            compile_module(&create_module_with_definitions(vec![
+                eir::ir::Definition::thunk(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::FunctionApplication::new(
+                        eir::types::Function::new(
+                            eir::types::Type::Number,
+                            eir::types::Type::Number,
+                        ),
+                        eir::ir::Variable::new("f"),
+                        eir::ir::Variable::new("x"),
+                    ),
+                    eir::types::Type::Number,
+                ),
+            ]));
+        }
+
+        #[test]
+        fn declare_runtime_functions_with_thunk() {
+            let module = compile(&create_module_with_definitions(vec![
+                eir::ir::Definition::thunk(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::Variable::new("x"),
+                    eir::types::Type::Number,
+                ),
+            ]))
+            .unwrap();
+
+            assert!(module
+                .function_declarations()
+                .iter()
+                .any(|declaration| declaration.name() == "eir_thunk_wait"));
+        }
+
+        #[test]
+        fn declare_no_runtime_functions_without_thunk() {
+            let module = compile(&create_module_with_definitions(vec![
+                eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::Variable::new("x"),
+                    eir::types::Type::Number,
+                ),
+            ]))
+            .unwrap();
+
+            assert!(!module
+                .function_declarations()
+                .iter()
+                .any(|declaration| declaration.name() == "eir_thunk_wait"));
+        }
+
+        #[test]
+        fn compile_constant() {
+            compile_module(&create_module_with_definitions(vec![
+                eir::ir::Definition::new("c", vec![], 42.0, eir::types::Type::Number),
+                eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::ArithmeticOperation::new(
+                        eir::types::Type::Number,
+                        eir::ir::ArithmeticOperator::Add,
+                        eir::ir::Variable::new("c"),
+                        eir::ir::Variable::new("x"),
+                    ),
+                    eir::types::Type::Number,
+                ),
+            ]));
+        }
+
+        #[test]
+        fn compile_constant_of_string() {
+            compile_module(&create_module_with_definitions(vec![
+                eir::ir::Definition::new(
+                    "c",
+                    vec![],
+                    eir::ir::ByteString::new("foo"),
+                    eir::types::Type::ByteString,
+                ),
+                eir::ir::Definition::new(
+                    "d",
+                    vec![],
+                    eir::ir::ByteStringConcatenation::new(
+                        eir::ir::Variable::new("c"),
+                        eir::ir::Variable::new("c"),
+                    ),
+                    eir::types::Type::ByteString,
+                ),
+            ]));
+        }
+
+        #[test]
+        fn compile_constant_of_function() {
+            let function_type =
+                eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number);
+
+            compile_module(&create_module_with_definitions(vec![
+                eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::Variable::new("x"),
+                    eir::types::Type::Number,
+                ),
+                eir::ir::Definition::new(
+                    "c",
+                    vec![],
+                    eir::ir::Variable::new("f"),
+                    function_type.clone(),
+                ),
+                eir::ir::Definition::new(
+                    "g",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::FunctionApplication::new(
+                        function_type,
+                        eir::ir::Variable::new("c"),
+                        eir::ir::Variable::new("x"),
+                    ),
+                    eir::types::Type::Number,
+                ),
+            ]));
+        }
+
+        #[test]
+        fn compile_polymorphic_definitions() {
+            let type_variable = eir::types::Type::Variable("a".into());
+            let record_type =
+                eir::types::Record::with_arguments("box", vec![eir::types::Type::Number]);
+
+            compile_module(&create_module_with_type_definitions(
+                vec![eir::ir::TypeDefinition::with_parameters(
+                    "box",
+                    vec!["a".into()],
+                    eir::types::RecordBody::new(vec![type_variable.clone()]),
+                )],
+                vec![
+                    eir::ir::Definition::with_type_parameters(
+                        "id",
+                        vec!["a".into()],
+                        vec![eir::ir::Argument::new("x", type_variable.clone())],
+                        eir::ir::Variable::new("x"),
+                        type_variable,
+                    ),
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::with_type_arguments(
+                            eir::types::Function::new(record_type.clone(), record_type.clone()),
+                            eir::ir::Variable::new("id"),
+                            vec![record_type.clone().into()],
+                            eir::ir::Record::new(
+                                record_type.clone(),
+                                vec![eir::ir::Variable::new("x").into()],
+                            ),
+                        ),
+                        record_type,
+                    ),
+                ],
+            ));
+        }
     }
 
     mod expressions {
@@ -423,6 +632,7 @@ mod tests {
                             "g",
                             vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
                             eir::ir::ArithmeticOperation::new(
+                                eir::types::Type::Number,
                                 eir::ir::ArithmeticOperator::Add,
                                 eir::ir::Variable::new("x"),
                                 eir::ir::Variable::new("y"),
@@ -454,6 +664,7 @@ mod tests {
                             "g",
                             vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
                             eir::ir::ArithmeticOperation::new(
+                                eir::types::Type::Number,
                                 eir::ir::ArithmeticOperator::Add,
                                 eir::ir::Variable::new("x"),
                                 eir::ir::Variable::new("y"),
@@ -489,6 +700,166 @@ mod tests {
             ]));
         }
 
+        #[test]
+        fn compile_mutually_recursive_definitions() {
+            let function_type =
+                eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number);
+
+            compile_module(&create_module_with_definitions(vec![
+                eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::LetRecursive::with_definitions(
+                        vec![
+                            eir::ir::Definition::new(
+                                "g",
+                                vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                                eir::ir::FunctionApplication::new(
+                                    function_type.clone(),
+                                    eir::ir::Variable::new("h"),
+                                    eir::ir::Variable::new("x"),
+                                ),
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::Definition::new(
+                                "h",
+                                vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                                eir::ir::FunctionApplication::new(
+                                    function_type.clone(),
+                                    eir::ir::Variable::new("g"),
+                                    eir::ir::Variable::new("y"),
+                                ),
+                                eir::types::Type::Number,
+                            ),
+                        ],
+                        eir::ir::FunctionApplication::new(
+                            function_type,
+                            eir::ir::Variable::new("g"),
+                            42.0,
+                        ),
+                    ),
+                    eir::types::Type::Number,
+                ),
+            ]));
+        }
+
+        #[test]
+        fn share_closures_in_mutually_recursive_definitions() {
+            fn count_heap_allocations(block: &fmm::ir::Block) -> usize {
+                block
+                    .instructions()
+                    .iter()
+                    .map(|instruction| match instruction {
+                        fmm::ir::Instruction::AllocateHeap(_) => 1,
+                        fmm::ir::Instruction::If(if_) => {
+                            count_heap_allocations(if_.then()) + count_heap_allocations(if_.else_())
+                        }
+                        _ => 0,
+                    })
+                    .sum()
+            }
+
+            let function_type =
+                eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number);
+            let create_definition = |name: &str, other: &str| {
+                eir::ir::Definition::new(
+                    name,
+                    vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                    eir::ir::If::new(
+                        eir::ir::ComparisonOperation::new(
+                            eir::types::Type::Number,
+                            eir::ir::ComparisonOperator::Equal,
+                            eir::ir::Variable::new("y"),
+                            0.0,
+                        ),
+                        eir::ir::Variable::new("x"),
+                        eir::ir::FunctionApplication::new(
+                            function_type.clone(),
+                            eir::ir::Variable::new(other),
+                            eir::ir::ArithmeticOperation::new(
+                                eir::types::Type::Number,
+                                eir::ir::ArithmeticOperator::Subtract,
+                                eir::ir::Variable::new("y"),
+                                1.0,
+                            ),
+                        ),
+                    ),
+                    eir::types::Type::Number,
+                )
+            };
+            let module = create_module_with_definitions(vec![eir::ir::Definition::new(
+                "f",
+                vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                eir::ir::LetRecursive::with_definitions(
+                    vec![create_definition("g", "h"), create_definition("h", "g")],
+                    eir::ir::FunctionApplication::new(
+                        function_type,
+                        eir::ir::Variable::new("g"),
+                        42.0,
+                    ),
+                ),
+                eir::types::Type::Number,
+            )]);
+
+            compile_module(&module);
+
+            // Only closures for the body of the let-recursive expression are
+            // allocated. Calls in the group reuse them.
+            assert_eq!(
+                compile(&module)
+                    .unwrap()
+                    .function_definitions()
+                    .iter()
+                    .map(|definition| count_heap_allocations(definition.body()))
+                    .sum::<usize>(),
+                2
+            );
+        }
+
+        #[test]
+        fn fail_to_compile_thunk_in_mutually_recursive_definitions() {
+            let function_type =
+                eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number);
+
+            assert!(matches!(
+                compile(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::LetRecursive::with_definitions(
+                            vec![
+                                eir::ir::Definition::new(
+                                    "g",
+                                    vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                                    eir::ir::FunctionApplication::new(
+                                        function_type.clone(),
+                                        eir::ir::Variable::new("h"),
+                                        eir::ir::Variable::new("x"),
+                                    ),
+                                    eir::types::Type::Number,
+                                ),
+                                eir::ir::Definition::thunk(
+                                    "h",
+                                    vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                                    eir::ir::Variable::new("x"),
+                                    eir::types::Type::Number,
+                                ),
+                            ],
+                            eir::ir::FunctionApplication::new(
+                                function_type,
+                                eir::ir::Variable::new("g"),
+                                42.0,
+                            ),
+                        ),
+                        eir::types::Type::Number,
+                    )
+                ]),),
+                Err(CompileError::TypeCheck(
+                    eir::analysis::TypeCheckError::ThunkInRecursiveGroup(_)
+                ))
+            ));
+        }
+
         #[test]
         fn compile_let_recursive_with_curried_function() {
             compile_module(&create_module_with_definitions(vec![
@@ -504,8 +875,10 @@ mod tests {
                                     "h",
                                     vec![eir::ir::Argument::new("z", eir::types::Type::Number)],
                                     eir::ir::ArithmeticOperation::new(
+                                        eir::types::Type::Number,
                                         eir::ir::ArithmeticOperator::Add,
                                         eir::ir::ArithmeticOperation::new(
+                                            eir::types::Type::Number,
                                             eir::ir::ArithmeticOperator::Add,
                                             eir::ir::Variable::new("x"),
                                             eir::ir::Variable::new("y"),
@@ -548,6 +921,26 @@ mod tests {
         mod cases {
             use super::*;
 
+            #[test]
+            fn compile_with_variant() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::Case::new(
+                            eir::ir::Variable::new("x"),
+                            vec![eir::ir::Alternative::new(
+                                eir::types::Type::Variant,
+                                "y",
+                                eir::ir::Variable::new("y"),
+                            )],
+                            None,
+                        ),
+                        eir::types::Type::Variant,
+                    ),
+                ]));
+            }
+
             #[test]
             fn compile_with_float_64() {
                 compile_module(&create_module_with_definitions(vec![
@@ -639,147 +1032,546 @@ mod tests {
                     ),
                 ]));
             }
-        }
-
-        mod records {
-            use super::*;
 
             #[test]
-            fn compile_with_no_element() {
-                let record_type = eir::types::Record::new("foo");
+            fn compile_with_many_alternatives() {
+                let record_types = (0..40)
+                    .map(|index| eir::types::Record::new(format!("foo{}", index)))
+                    .collect::<Vec<_>>();
 
                 compile_module(&create_module_with_type_definitions(
-                    vec![eir::ir::TypeDefinition::new(
-                        "foo",
-                        eir::types::RecordBody::new(vec![]),
-                    )],
+                    record_types
+                        .iter()
+                        .map(|record_type| {
+                            eir::ir::TypeDefinition::new(
+                                record_type.name(),
+                                eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                            )
+                        })
+                        .collect(),
                     vec![eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Record::new(record_type.clone(), vec![]),
-                        record_type,
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::Case::new(
+                            eir::ir::Variable::new("x"),
+                            record_types
+                                .iter()
+                                .map(|record_type| {
+                                    eir::ir::Alternative::new(
+                                        record_type.clone(),
+                                        "y",
+                                        eir::ir::RecordElement::new(
+                                            record_type.clone(),
+                                            0,
+                                            eir::ir::Variable::new("y"),
+                                        ),
+                                    )
+                                })
+                                .collect(),
+                            Some(eir::ir::DefaultAlternative::new("y", 42.0)),
+                        ),
+                        eir::types::Type::Number,
                     )],
                 ));
             }
 
             #[test]
-            fn compile_with_1_element() {
-                let record_type = eir::types::Record::new("foo");
-
-                compile_module(&create_module_with_type_definitions(
-                    vec![eir::ir::TypeDefinition::new(
-                        "foo",
-                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
-                    )],
-                    vec![eir::ir::Definition::new(
-                        "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Record::new(record_type.clone(), vec![42.0.into()]),
-                        record_type,
-                    )],
-                ));
+            fn compile_with_few_alternatives_and_default_alternative() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::Case::new(
+                            eir::ir::Variable::new("x"),
+                            vec![
+                                eir::ir::Alternative::new(
+                                    eir::types::Type::Number,
+                                    "y",
+                                    eir::ir::Variable::new("y"),
+                                ),
+                                eir::ir::Alternative::new(eir::types::Type::Boolean, "y", 13.0),
+                            ],
+                            Some(eir::ir::DefaultAlternative::new("y", 42.0)),
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
             }
 
             #[test]
-            fn compile_with_2_elements() {
-                let record_type = eir::types::Record::new("foo");
+            fn compile_with_duplicate_alternatives() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::Case::new(
+                            eir::ir::Variable::new("x"),
+                            vec![
+                                eir::ir::Alternative::new(
+                                    eir::types::Type::Number,
+                                    "y",
+                                    eir::ir::Variable::new("y"),
+                                ),
+                                eir::ir::Alternative::new(eir::types::Type::Boolean, "y", 13.0),
+                                eir::ir::Alternative::new(eir::types::Type::Number, "y", 42.0),
+                            ],
+                            None,
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+        }
 
-                compile_module(&create_module_with_type_definitions(
-                    vec![eir::ir::TypeDefinition::new(
-                        "foo",
-                        eir::types::RecordBody::new(vec![
-                            eir::types::Type::Number,
-                            eir::types::Type::Boolean,
-                        ]),
-                    )],
-                    vec![eir::ir::Definition::new(
+        mod literal_cases {
+            use super::*;
+
+            fn compile_literal_case(
+                type_: impl Into<eir::types::Type>,
+                alternatives: Vec<eir::ir::LiteralAlternative>,
+                default_alternative: Option<eir::ir::DefaultAlternative>,
+            ) {
+                let type_ = type_.into();
+
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Record::new(record_type.clone(), vec![42.0.into(), true.into()]),
-                        record_type,
-                    )],
-                ));
+                        vec![eir::ir::Argument::new("x", type_.clone())],
+                        eir::ir::LiteralCase::new(
+                            type_,
+                            eir::ir::Variable::new("x"),
+                            alternatives,
+                            default_alternative,
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
             }
 
             #[test]
-            fn compile_boxed() {
-                let record_type = eir::types::Record::new("foo");
+            fn compile_with_booleans() {
+                compile_literal_case(
+                    eir::types::Type::Boolean,
+                    vec![
+                        eir::ir::LiteralAlternative::new(false, 1.0),
+                        eir::ir::LiteralAlternative::new(true, 2.0),
+                    ],
+                    None,
+                );
+            }
 
-                compile_module(&create_module_with_type_definitions(
-                    vec![eir::ir::TypeDefinition::new(
-                        "foo",
-                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
-                    )],
-                    vec![eir::ir::Definition::new(
-                        "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Record::new(record_type.clone(), vec![42.0.into()]),
-                        record_type,
-                    )],
-                ));
+            #[test]
+            fn compile_with_numbers() {
+                compile_literal_case(
+                    eir::types::Type::Number,
+                    (0..10)
+                        .map(|index| eir::ir::LiteralAlternative::new(index as f64, 1.0))
+                        .chain([
+                            eir::ir::LiteralAlternative::new(-0.0, 2.0),
+                            eir::ir::LiteralAlternative::new(f64::NAN, 3.0),
+                        ])
+                        .collect(),
+                    Some(eir::ir::DefaultAlternative::new(
+                        "y",
+                        eir::ir::Variable::new("y"),
+                    )),
+                );
+            }
+
+            #[test]
+            fn compile_with_signed_integers() {
+                let integer_type = eir::types::Integer::Signed32;
+
+                compile_literal_case(
+                    integer_type,
+                    [-2i64, 42, -1, 0, 7]
+                        .iter()
+                        .map(|&value| {
+                            eir::ir::LiteralAlternative::new(
+                                eir::ir::Integer::new(integer_type, value as u64),
+                                1.0,
+                            )
+                        })
+                        .collect(),
+                    Some(eir::ir::DefaultAlternative::new("y", 2.0)),
+                );
+            }
+
+            #[test]
+            fn compile_with_strings() {
+                compile_literal_case(
+                    eir::types::Type::ByteString,
+                    ["foo", "", "bar", "hello", "foo"]
+                        .iter()
+                        .map(|&string| {
+                            eir::ir::LiteralAlternative::new(eir::ir::ByteString::new(string), 1.0)
+                        })
+                        .collect(),
+                    Some(eir::ir::DefaultAlternative::new("y", 2.0)),
+                );
             }
         }
 
-        mod record_elements {
+        mod boolean_operations {
             use super::*;
 
             #[test]
-            fn compile_with_1_element_record() {
+            fn compile_with_operators() {
+                for operator in [eir::ir::BooleanOperator::And, eir::ir::BooleanOperator::Or] {
+                    compile_module(&create_module_with_definitions(vec![
+                        eir::ir::Definition::new(
+                            "f",
+                            vec![
+                                eir::ir::Argument::new("x", eir::types::Type::Boolean),
+                                eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            ],
+                            eir::ir::BooleanOperation::new(
+                                operator,
+                                eir::ir::Not::new(eir::ir::Variable::new("x")),
+                                eir::ir::Variable::new("y"),
+                            ),
+                            eir::types::Type::Boolean,
+                        ),
+                    ]));
+                }
+            }
+
+            #[test]
+            fn compile_with_dropped_variable() {
                 let record_type = eir::types::Record::new("foo");
 
                 compile_module(&create_module_with_type_definitions(
                     vec![eir::ir::TypeDefinition::new(
                         "foo",
-                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                        eir::types::RecordBody::new(vec![eir::types::Type::Boolean]),
                     )],
                     vec![eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", record_type.clone())],
-                        eir::ir::RecordElement::new(record_type, 0, eir::ir::Variable::new("x")),
-                        eir::types::Type::Number,
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("y", record_type.clone()),
+                        ],
+                        eir::ir::BooleanOperation::new(
+                            eir::ir::BooleanOperator::And,
+                            eir::ir::Variable::new("x"),
+                            eir::ir::RecordElement::new(
+                                record_type,
+                                0,
+                                eir::ir::Variable::new("y"),
+                            ),
+                        ),
+                        eir::types::Type::Boolean,
                     )],
                 ));
             }
+        }
+
+        mod comparison_operations {
+            use super::*;
 
             #[test]
-            fn compile_with_2_element_record() {
-                let record_type = eir::types::Record::new("foo");
+            fn compile_with_booleans() {
+                for operator in [
+                    eir::ir::ComparisonOperator::Equal,
+                    eir::ir::ComparisonOperator::NotEqual,
+                    eir::ir::ComparisonOperator::LessThan,
+                    eir::ir::ComparisonOperator::LessThanOrEqual,
+                    eir::ir::ComparisonOperator::GreaterThan,
+                    eir::ir::ComparisonOperator::GreaterThanOrEqual,
+                ] {
+                    compile_module(&create_module_with_definitions(vec![
+                        eir::ir::Definition::new(
+                            "f",
+                            vec![
+                                eir::ir::Argument::new("x", eir::types::Type::Boolean),
+                                eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            ],
+                            eir::ir::ComparisonOperation::new(
+                                eir::types::Type::Boolean,
+                                operator,
+                                eir::ir::Variable::new("x"),
+                                eir::ir::Variable::new("y"),
+                            ),
+                            eir::types::Type::Boolean,
+                        ),
+                    ]));
+                }
+            }
+        }
 
-                compile_module(&create_module_with_type_definitions(
-                    vec![eir::ir::TypeDefinition::new(
-                        "foo",
-                        eir::types::RecordBody::new(vec![
+        mod arithmetic_operations {
+            use super::*;
+
+            fn compile_operations(type_: eir::types::Type, constant: eir::ir::Expression) {
+                compile_module(&create_module_with_definitions(
+                    [
+                        eir::ir::ArithmeticOperator::Add,
+                        eir::ir::ArithmeticOperator::Subtract,
+                        eir::ir::ArithmeticOperator::Multiply,
+                        eir::ir::ArithmeticOperator::Divide,
+                        eir::ir::ArithmeticOperator::Remainder,
+                        eir::ir::ArithmeticOperator::Power,
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, operator)| {
+                        eir::ir::Definition::new(
+                            format!("f{}", index),
+                            vec![
+                                eir::ir::Argument::new("x", type_.clone()),
+                                eir::ir::Argument::new("y", type_.clone()),
+                            ],
+                            eir::ir::ArithmeticOperation::new(
+                                type_.clone(),
+                                operator,
+                                eir::ir::Variable::new("x"),
+                                eir::ir::Variable::new("y"),
+                            ),
+                            type_.clone(),
+                        )
+                    })
+                    .chain([
+                        eir::ir::Definition::new(
+                            "g",
+                            vec![eir::ir::Argument::new("x", type_.clone())],
+                            eir::ir::ComparisonOperation::new(
+                                type_.clone(),
+                                eir::ir::ComparisonOperator::LessThan,
+                                eir::ir::Variable::new("x"),
+                                constant,
+                            ),
                             eir::types::Type::Boolean,
-                            eir::types::Type::Number,
-                        ]),
-                    )],
-                    vec![eir::ir::Definition::new(
-                        "f",
-                        vec![eir::ir::Argument::new("x", record_type.clone())],
-                        eir::ir::RecordElement::new(record_type, 1, eir::ir::Variable::new("x")),
-                        eir::types::Type::Number,
-                    )],
+                        ),
+                        eir::ir::Definition::new(
+                            "h",
+                            vec![eir::ir::Argument::new("x", type_.clone())],
+                            eir::ir::Negation::new(type_.clone(), eir::ir::Variable::new("x")),
+                            type_,
+                        ),
+                    ])
+                    .collect(),
+                ));
+            }
+
+            #[test]
+            fn compile_with_number() {
+                compile_operations(eir::types::Type::Number, 42.0.into());
+            }
+
+            #[test]
+            fn compile_with_signed_integers() {
+                for type_ in [
+                    eir::types::Integer::Signed8,
+                    eir::types::Integer::Signed32,
+                    eir::types::Integer::Signed64,
+                ] {
+                    compile_operations(type_.into(), eir::ir::Integer::new(type_, 42).into());
+                }
+            }
+
+            #[test]
+            fn compile_with_unsigned_integers() {
+                for type_ in [
+                    eir::types::Integer::Unsigned8,
+                    eir::types::Integer::Unsigned32,
+                    eir::types::Integer::Unsigned64,
+                ] {
+                    compile_operations(type_.into(), eir::ir::Integer::new(type_, 42).into());
+                }
+            }
+
+            fn compile_divisions(type_: eir::types::Integer, lhs: u64, rhs: u64) {
+                compile_module(&create_module_with_definitions(
+                    [
+                        eir::ir::ArithmeticOperator::Divide,
+                        eir::ir::ArithmeticOperator::Remainder,
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, operator)| {
+                        eir::ir::Definition::new(
+                            format!("f{}", index),
+                            vec![eir::ir::Argument::new("x", type_)],
+                            eir::ir::ArithmeticOperation::new(
+                                type_,
+                                operator,
+                                eir::ir::Integer::new(type_, lhs),
+                                eir::ir::Integer::new(type_, rhs),
+                            ),
+                            type_,
+                        )
+                    })
+                    .collect(),
                 ));
             }
+
+            #[test]
+            fn compile_division_by_zero() {
+                for type_ in [
+                    eir::types::Integer::Signed8,
+                    eir::types::Integer::Signed64,
+                    eir::types::Integer::Unsigned8,
+                    eir::types::Integer::Unsigned64,
+                ] {
+                    compile_divisions(type_, 42, 0);
+                }
+            }
+
+            #[test]
+            fn compile_division_of_minimum_integer_by_minus_one() {
+                for type_ in [
+                    eir::types::Integer::Signed8,
+                    eir::types::Integer::Signed32,
+                    eir::types::Integer::Signed64,
+                ] {
+                    compile_divisions(type_, 1 << (type_.bits() - 1), u64::MAX);
+                }
+            }
         }
 
-        mod variants {
+        mod byte_strings {
             use super::*;
 
             #[test]
-            fn compile_with_float_64() {
+            fn compile_length() {
                 compile_module(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Variant::new(eir::types::Type::Number, 42.0),
-                        eir::types::Type::Variant,
+                        vec![eir::ir::Argument::new("x", eir::types::Type::ByteString)],
+                        eir::ir::ByteStringLength::new(eir::ir::Variable::new("x")),
+                        eir::types::Integer::Unsigned64,
                     ),
                 ]));
             }
 
             #[test]
-            fn compile_with_empty_unboxed_record() {
+            fn compile_concatenation() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::ByteString)],
+                        eir::ir::ByteStringConcatenation::new(
+                            eir::ir::Variable::new("x"),
+                            eir::ir::ByteStringConcatenation::new(
+                                eir::ir::Variable::new("x"),
+                                eir::ir::ByteString::new("foo"),
+                            ),
+                        ),
+                        eir::types::Type::ByteString,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_slice() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::ByteString),
+                            eir::ir::Argument::new("y", eir::types::Integer::Unsigned64),
+                        ],
+                        eir::ir::ByteStringSlice::new(
+                            eir::ir::Variable::new("x"),
+                            eir::ir::Variable::new("y"),
+                            eir::ir::Integer::new(eir::types::Integer::Unsigned64, 42),
+                        ),
+                        eir::types::Type::ByteString,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_comparisons() {
+                for operator in [
+                    eir::ir::ComparisonOperator::Equal,
+                    eir::ir::ComparisonOperator::NotEqual,
+                    eir::ir::ComparisonOperator::LessThan,
+                    eir::ir::ComparisonOperator::LessThanOrEqual,
+                    eir::ir::ComparisonOperator::GreaterThan,
+                    eir::ir::ComparisonOperator::GreaterThanOrEqual,
+                ] {
+                    compile_module(&create_module_with_definitions(vec![
+                        eir::ir::Definition::new(
+                            "f",
+                            vec![eir::ir::Argument::new("x", eir::types::Type::ByteString)],
+                            eir::ir::ComparisonOperation::new(
+                                eir::types::Type::ByteString,
+                                operator,
+                                eir::ir::Variable::new("x"),
+                                eir::ir::ByteString::new("foo"),
+                            ),
+                            eir::types::Type::Boolean,
+                        ),
+                    ]));
+                }
+            }
+        }
+
+        mod numeric_conversions {
+            use super::*;
+
+            #[test]
+            fn compile_between_all_types() {
+                let types = [
+                    eir::types::Type::Number,
+                    eir::types::Integer::Signed8.into(),
+                    eir::types::Integer::Signed32.into(),
+                    eir::types::Integer::Signed64.into(),
+                    eir::types::Integer::Unsigned8.into(),
+                    eir::types::Integer::Unsigned32.into(),
+                    eir::types::Integer::Unsigned64.into(),
+                ];
+
+                for from in &types {
+                    for to in &types {
+                        compile_module(&create_module_with_definitions(vec![
+                            eir::ir::Definition::new(
+                                "f",
+                                vec![eir::ir::Argument::new("x", from.clone())],
+                                eir::ir::NumericConversion::new(
+                                    from.clone(),
+                                    to.clone(),
+                                    eir::ir::Rounding::Truncate,
+                                    eir::ir::Variable::new("x"),
+                                ),
+                                to.clone(),
+                            ),
+                        ]));
+                    }
+                }
+            }
+
+            #[test]
+            fn compile_with_rounding_modes() {
+                for rounding in [
+                    eir::ir::Rounding::Ceiling,
+                    eir::ir::Rounding::Floor,
+                    eir::ir::Rounding::Round,
+                    eir::ir::Rounding::Truncate,
+                ] {
+                    compile_module(&create_module_with_definitions(vec![
+                        eir::ir::Definition::new(
+                            "f",
+                            vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                            eir::ir::NumericConversion::new(
+                                eir::types::Type::Number,
+                                eir::types::Type::Number,
+                                rounding,
+                                eir::ir::Variable::new("x"),
+                            ),
+                            eir::types::Type::Number,
+                        ),
+                    ]));
+                }
+            }
+        }
+
+        mod records {
+            use super::*;
+
+            #[test]
+            fn compile_with_no_element() {
                 let record_type = eir::types::Record::new("foo");
 
                 compile_module(&create_module_with_type_definitions(
@@ -789,18 +1581,15 @@ mod tests {
                     )],
                     vec![eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", record_type.clone())],
-                        eir::ir::Variant::new(
-                            record_type.clone(),
-                            eir::ir::Record::new(record_type, vec![]),
-                        ),
-                        eir::types::Type::Variant,
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Record::new(record_type.clone(), vec![]),
+                        record_type,
                     )],
                 ));
             }
 
             #[test]
-            fn compile_with_unboxed_record() {
+            fn compile_with_1_element() {
                 let record_type = eir::types::Record::new("foo");
 
                 compile_module(&create_module_with_type_definitions(
@@ -810,322 +1599,1128 @@ mod tests {
                     )],
                     vec![eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", record_type.clone())],
-                        eir::ir::Variant::new(
-                            record_type.clone(),
-                            eir::ir::Record::new(record_type, vec![42.0.into()]),
-                        ),
-                        eir::types::Type::Variant,
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Record::new(record_type.clone(), vec![42.0.into()]),
+                        record_type,
                     )],
                 ));
             }
 
             #[test]
-            fn compile_with_string() {
+            fn compile_with_2_elements() {
+                let record_type = eir::types::Record::new("foo");
+
                 compile_module(&create_module_with_type_definitions(
-                    vec![],
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![
+                            eir::types::Type::Number,
+                            eir::types::Type::Boolean,
+                        ]),
+                    )],
                     vec![eir::ir::Definition::new(
                         "f",
                         vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Variant::new(
-                            eir::types::Type::ByteString,
-                            eir::ir::ByteString::new("foo"),
-                        ),
-                        eir::types::Type::Variant,
+                        eir::ir::Record::new(record_type.clone(), vec![42.0.into(), true.into()]),
+                        record_type,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_boxed() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Record::new(record_type.clone(), vec![42.0.into()]),
+                        record_type,
                     )],
                 ));
             }
+
+            #[test]
+            fn compile_tuple() {
+                let tuple_type = eir::types::Tuple::new(vec![
+                    eir::types::Type::Number,
+                    eir::types::Tuple::new(vec![]).into(),
+                ]);
+
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Record::new(
+                            tuple_type.clone(),
+                            vec![
+                                eir::ir::Variable::new("x").into(),
+                                eir::ir::Record::new(eir::types::Tuple::new(vec![]), vec![]).into(),
+                            ],
+                        ),
+                        tuple_type,
+                    ),
+                ]));
+            }
         }
 
-        mod function_applications {
+        mod record_elements {
             use super::*;
 
             #[test]
-            fn compile_1_argument() {
+            fn compile_with_1_element_record() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::RecordElement::new(record_type, 0, eir::ir::Variable::new("x")),
+                        eir::types::Type::Number,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_2_element_record() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![
+                            eir::types::Type::Boolean,
+                            eir::types::Type::Number,
+                        ]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::RecordElement::new(record_type, 1, eir::ir::Variable::new("x")),
+                        eir::types::Type::Number,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_field_name() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::with_fields(vec![
+                            ("x".into(), eir::types::Type::Boolean),
+                            ("y".into(), eir::types::Type::Number),
+                        ]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::RecordElement::with_field_name(
+                            record_type,
+                            "y",
+                            eir::ir::Variable::new("x"),
+                        ),
+                        eir::types::Type::Number,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_tuple() {
+                let tuple_type = eir::types::Tuple::new(vec![
+                    eir::types::Type::ByteString,
+                    eir::types::Type::Number,
+                ]);
+
                 compile_module(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::Variable::new("x"),
+                        vec![eir::ir::Argument::new("x", tuple_type.clone())],
+                        eir::ir::RecordElement::new(tuple_type, 1, eir::ir::Variable::new("x")),
                         eir::types::Type::Number,
                     ),
+                ]));
+            }
+
+            #[test]
+            fn compile_with_anonymous_record_field_name() {
+                let record_type = eir::types::Tuple::with_fields(vec![
+                    ("x".into(), eir::types::Type::ByteString),
+                    ("y".into(), eir::types::Type::Number),
+                ]);
+
+                compile_module(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
-                        "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::Number,
-                                eir::types::Type::Number,
-                            ),
-                            eir::ir::Variable::new("f"),
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::RecordElement::with_field_name(
+                            record_type,
+                            "y",
+                            eir::ir::Variable::new("x"),
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+        }
+
+        mod record_updates {
+            use super::*;
+
+            #[test]
+            fn compile() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::with_fields(vec![
+                            ("x".into(), eir::types::Type::ByteString),
+                            ("y".into(), eir::types::Type::Number),
+                        ]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::RecordUpdate::new(
+                            record_type.clone(),
+                            eir::ir::Variable::new("x"),
+                            vec![eir::ir::RecordUpdateField::with_field_name(
+                                "x",
+                                eir::ir::ByteString::new("foo"),
+                            )],
+                        ),
+                        record_type,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_tuple() {
+                let tuple_type = eir::types::Tuple::new(vec![
+                    eir::types::Type::Number,
+                    eir::types::Type::ByteString,
+                ]);
+
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", tuple_type.clone())],
+                        eir::ir::RecordUpdate::new(
+                            tuple_type.clone(),
+                            eir::ir::Variable::new("x"),
+                            vec![eir::ir::RecordUpdateField::new(0, 42.0)],
+                        ),
+                        tuple_type,
+                    ),
+                ]));
+            }
+        }
+
+        mod let_records {
+            use super::*;
+
+            #[test]
+            fn compile() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::with_fields(vec![
+                            ("x".into(), eir::types::Type::ByteString),
+                            ("y".into(), eir::types::Type::Number),
+                        ]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::LetRecord::new(
+                            record_type,
+                            vec![
+                                eir::ir::Argument::new("y", eir::types::Type::ByteString),
+                                eir::ir::Argument::new("z", eir::types::Type::Number),
+                            ],
+                            eir::ir::Variable::new("x"),
+                            eir::ir::Variable::new("y"),
+                        ),
+                        eir::types::Type::ByteString,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_unboxed_tuple() {
+                let tuple_type = eir::types::Tuple::new(vec![]);
+
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", tuple_type.clone())],
+                        eir::ir::LetRecord::new(
+                            tuple_type,
+                            vec![],
+                            eir::ir::Variable::new("x"),
                             42.0,
                         ),
                         eir::types::Type::Number,
                     ),
                 ]));
             }
+        }
+
+        mod variants {
+            use super::*;
+
+            #[test]
+            fn compile_with_variant() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::Variant::new(
+                            eir::types::Type::Variant,
+                            eir::ir::Variable::new("x"),
+                        ),
+                        eir::types::Type::Variant,
+                    ),
+                ]));
+            }
 
             #[test]
-            fn compile_2_arguments() {
+            fn compile_with_float_64() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Variant::new(eir::types::Type::Number, 42.0),
+                        eir::types::Type::Variant,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_with_empty_unboxed_record() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::Variant::new(
+                            record_type.clone(),
+                            eir::ir::Record::new(record_type, vec![]),
+                        ),
+                        eir::types::Type::Variant,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_unboxed_record() {
+                let record_type = eir::types::Record::new("foo");
+
+                compile_module(&create_module_with_type_definitions(
+                    vec![eir::ir::TypeDefinition::new(
+                        "foo",
+                        eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                    )],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", record_type.clone())],
+                        eir::ir::Variant::new(
+                            record_type.clone(),
+                            eir::ir::Record::new(record_type, vec![42.0.into()]),
+                        ),
+                        eir::types::Type::Variant,
+                    )],
+                ));
+            }
+
+            #[test]
+            fn compile_with_string() {
+                compile_module(&create_module_with_type_definitions(
+                    vec![],
+                    vec![eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Variant::new(
+                            eir::types::Type::ByteString,
+                            eir::ir::ByteString::new("foo"),
+                        ),
+                        eir::types::Type::Variant,
+                    )],
+                ));
+            }
+        }
+
+        mod function_applications {
+            use super::*;
+
+            #[test]
+            fn compile_1_argument() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Number,
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::Variable::new("f"),
+                            42.0,
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_2_arguments() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Boolean,
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::FunctionApplication::new(
+                                eir::types::Function::new(
+                                    eir::types::Type::Number,
+                                    eir::types::Function::new(
+                                        eir::types::Type::Boolean,
+                                        eir::types::Type::Number,
+                                    ),
+                                ),
+                                eir::ir::Variable::new("f"),
+                                42.0,
+                            ),
+                            true,
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_3_arguments() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::ByteString,
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::FunctionApplication::new(
+                                eir::types::Function::new(
+                                    eir::types::Type::Boolean,
+                                    eir::types::Function::new(
+                                        eir::types::Type::ByteString,
+                                        eir::types::Type::Number,
+                                    ),
+                                ),
+                                eir::ir::FunctionApplication::new(
+                                    eir::types::Function::new(
+                                        eir::types::Type::Number,
+                                        eir::types::Function::new(
+                                            eir::types::Type::Boolean,
+                                            eir::types::Function::new(
+                                                eir::types::Type::ByteString,
+                                                eir::types::Type::Number,
+                                            ),
+                                        ),
+                                    ),
+                                    eir::ir::Variable::new("f"),
+                                    42.0,
+                                ),
+                                true,
+                            ),
+                            eir::ir::ByteString::new("foo"),
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_1_argument_with_arity_of_2() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Number,
+                                eir::types::Function::new(
+                                    eir::types::Type::Boolean,
+                                    eir::types::Type::Number,
+                                ),
+                            ),
+                            eir::ir::Variable::new("f"),
+                            42.0,
+                        ),
+                        eir::types::Function::new(
+                            eir::types::Type::Boolean,
+                            eir::types::Type::Number,
+                        ),
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_1_argument_with_arity_of_3() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Number,
+                                eir::types::Function::new(
+                                    eir::types::Type::Boolean,
+                                    eir::types::Function::new(
+                                        eir::types::Type::ByteString,
+                                        eir::types::Type::Number,
+                                    ),
+                                ),
+                            ),
+                            eir::ir::Variable::new("f"),
+                            42.0,
+                        ),
+                        eir::types::Function::new(
+                            eir::types::Type::Boolean,
+                            eir::types::Function::new(
+                                eir::types::Type::ByteString,
+                                eir::types::Type::Number,
+                            ),
+                        ),
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_2_arguments_with_arity_of_3() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Boolean,
+                                eir::types::Function::new(
+                                    eir::types::Type::ByteString,
+                                    eir::types::Type::Number,
+                                ),
+                            ),
+                            eir::ir::FunctionApplication::new(
+                                eir::types::Function::new(
+                                    eir::types::Type::Number,
+                                    eir::types::Function::new(
+                                        eir::types::Type::Boolean,
+                                        eir::types::Function::new(
+                                            eir::types::Type::ByteString,
+                                            eir::types::Type::Number,
+                                        ),
+                                    ),
+                                ),
+                                eir::ir::Variable::new("f"),
+                                42.0,
+                            ),
+                            true,
+                        ),
+                        eir::types::Function::new(
+                            eir::types::Type::ByteString,
+                            eir::types::Type::Number,
+                        ),
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_with_curried_function() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::LetRecursive::new(
+                            eir::ir::Definition::new(
+                                "g",
+                                vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
+                                eir::ir::ArithmeticOperation::new(
+                                    eir::types::Type::Number,
+                                    eir::ir::ArithmeticOperator::Add,
+                                    eir::ir::Variable::new("x"),
+                                    eir::ir::Variable::new("y"),
+                                ),
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::Variable::new("g"),
+                        ),
+                        eir::types::Function::new(
+                            eir::types::Type::Number,
+                            eir::types::Type::Number,
+                        ),
+                    ),
+                    eir::ir::Definition::new(
+                        "g",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::FunctionApplication::new(
+                            eir::types::Function::new(
+                                eir::types::Type::Number,
+                                eir::types::Type::Number,
+                            ),
+                            eir::ir::FunctionApplication::new(
+                                eir::types::Function::new(
+                                    eir::types::Type::Number,
+                                    eir::types::Function::new(
+                                        eir::types::Type::Number,
+                                        eir::types::Type::Number,
+                                    ),
+                                ),
+                                eir::ir::Variable::new("f"),
+                                111.0,
+                            ),
+                            222.0,
+                        ),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+        }
+
+        mod sums {
+            use super::*;
+
+            fn create_module_with_sum(definitions: Vec<eir::ir::Definition>) -> eir::ir::Module {
+                eir::ir::Module::with_sum_definitions(
+                    vec![
+                        eir::ir::TypeDefinition::new("foo", eir::types::RecordBody::new(vec![])),
+                        eir::ir::TypeDefinition::new(
+                            "bar",
+                            eir::types::RecordBody::new(vec![eir::types::Type::Number]),
+                        ),
+                    ],
+                    vec![eir::ir::SumDefinition::new(
+                        "baz",
+                        vec![
+                            eir::types::Record::new("foo"),
+                            eir::types::Record::new("bar"),
+                        ],
+                    )],
+                    vec![],
+                    vec![],
+                    vec![],
+                    definitions,
+                )
+            }
+
+            #[test]
+            fn compile_sum() {
+                let record_type = eir::types::Record::new("bar");
+
+                compile_module(&create_module_with_sum(vec![eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                    eir::ir::Sum::new(
+                        eir::types::Sum::new("baz"),
+                        record_type.clone(),
+                        eir::ir::Record::new(record_type, vec![eir::ir::Variable::new("x").into()]),
+                    ),
+                    eir::types::Sum::new("baz"),
+                )]));
+            }
+
+            #[test]
+            fn compile_sum_case() {
+                compile_module(&create_module_with_sum(vec![eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Sum::new("baz"))],
+                    eir::ir::SumCase::new(
+                        eir::types::Sum::new("baz"),
+                        eir::ir::Variable::new("x"),
+                        vec![
+                            eir::ir::Alternative::new(eir::types::Record::new("foo"), "y", 42.0),
+                            eir::ir::Alternative::new(
+                                eir::types::Record::new("bar"),
+                                "y",
+                                eir::ir::RecordElement::new(
+                                    eir::types::Record::new("bar"),
+                                    0,
+                                    eir::ir::Variable::new("y"),
+                                ),
+                            ),
+                        ],
+                        None,
+                    ),
+                    eir::types::Type::Number,
+                )]));
+            }
+
+            #[test]
+            fn compile_sum_case_with_default_alternative() {
+                compile_module(&create_module_with_sum(vec![eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Sum::new("baz"))],
+                    eir::ir::SumCase::new(
+                        eir::types::Sum::new("baz"),
+                        eir::ir::Variable::new("x"),
+                        vec![eir::ir::Alternative::new(
+                            eir::types::Record::new("foo"),
+                            "y",
+                            eir::ir::Variable::new("x"),
+                        )],
+                        Some(eir::ir::DefaultAlternative::new(
+                            "y",
+                            eir::ir::Variable::new("y"),
+                        )),
+                    ),
+                    eir::types::Sum::new("baz"),
+                )]));
+            }
+
+            #[test]
+            fn compile_sum_in_variant() {
+                compile_module(&create_module_with_sum(vec![eir::ir::Definition::new(
+                    "f",
+                    vec![eir::ir::Argument::new("x", eir::types::Sum::new("baz"))],
+                    eir::ir::Variant::new(eir::types::Sum::new("baz"), eir::ir::Variable::new("x")),
+                    eir::types::Type::Variant,
+                )]));
+            }
+        }
+
+        mod lazy_values {
+            use super::*;
+
+            #[test]
+            fn compile() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
+                        eir::ir::Lazy::new(eir::types::Type::Number, eir::ir::Variable::new("x")),
+                        eir::types::Lazy::new(eir::types::Type::Number),
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_force() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new(
+                            "x",
+                            eir::types::Lazy::new(eir::types::Type::Number),
+                        )],
+                        eir::ir::Force::new(eir::ir::Variable::new("x")),
+                        eir::types::Type::Number,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_with_environment() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::ByteString)],
+                        eir::ir::Let::new(
+                            "y",
+                            eir::types::Lazy::new(eir::types::Type::ByteString),
+                            eir::ir::Lazy::new(
+                                eir::types::Type::ByteString,
+                                eir::ir::ByteStringConcatenation::new(
+                                    eir::ir::Variable::new("x"),
+                                    eir::ir::Variable::new("x"),
+                                ),
+                            ),
+                            eir::ir::ByteStringConcatenation::new(
+                                eir::ir::Force::new(eir::ir::Variable::new("y")),
+                                eir::ir::Variable::new("x"),
+                            ),
+                        ),
+                        eir::types::Type::ByteString,
+                    ),
+                ]));
+            }
+        }
+
+        mod try_operations {
+            use super::*;
+
+            #[test]
+            fn compile() {
+                compile_module(&create_module_with_definitions(vec![
+                    eir::ir::Definition::new(
+                        "f",
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::TryOperation::new(
+                            eir::ir::Variable::new("x"),
+                            "e",
+                            eir::types::Type::ByteString,
+                            eir::ir::Variant::new(
+                                eir::types::Type::ByteString,
+                                eir::ir::Variable::new("e"),
+                            ),
+                        ),
+                        eir::types::Type::Variant,
+                    ),
+                ]));
+            }
+
+            #[test]
+            fn compile_with_dropped_variables() {
                 compile_module(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
                         vec![
-                            eir::ir::Argument::new("x", eir::types::Type::Number),
-                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("x", eir::types::Type::Variant),
+                            eir::ir::Argument::new("y", eir::types::Type::ByteString),
                         ],
-                        eir::ir::Variable::new("x"),
-                        eir::types::Type::Number,
-                    ),
-                    eir::ir::Definition::new(
-                        "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::Boolean,
+                        eir::ir::Let::new(
+                            "z",
+                            eir::types::Type::Variant,
+                            eir::ir::TryOperation::new(
+                                eir::ir::Variable::new("x"),
+                                "e",
                                 eir::types::Type::Number,
+                                eir::ir::Variable::new("y"),
                             ),
-                            eir::ir::FunctionApplication::new(
-                                eir::types::Function::new(
-                                    eir::types::Type::Number,
-                                    eir::types::Function::new(
-                                        eir::types::Type::Boolean,
-                                        eir::types::Type::Number,
-                                    ),
-                                ),
-                                eir::ir::Variable::new("f"),
-                                42.0,
-                            ),
-                            true,
+                            eir::ir::Variable::new("y"),
                         ),
-                        eir::types::Type::Number,
+                        eir::types::Type::ByteString,
                     ),
                 ]));
             }
 
             #[test]
-            fn compile_3_arguments() {
+            fn compile_in_thunk() {
                 compile_module(&create_module_with_definitions(vec![
-                    eir::ir::Definition::new(
+                    eir::ir::Definition::thunk(
                         "f",
-                        vec![
-                            eir::ir::Argument::new("x", eir::types::Type::Number),
-                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
-                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
-                        ],
-                        eir::ir::Variable::new("x"),
-                        eir::types::Type::Number,
-                    ),
-                    eir::ir::Definition::new(
-                        "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::ByteString,
+                        vec![eir::ir::Argument::new("x", eir::types::Type::Variant)],
+                        eir::ir::TryOperation::new(
+                            eir::ir::Variable::new("x"),
+                            "e",
+                            eir::types::Type::Number,
+                            eir::ir::Variant::new(
                                 eir::types::Type::Number,
+                                eir::ir::Variable::new("e"),
                             ),
-                            eir::ir::FunctionApplication::new(
-                                eir::types::Function::new(
-                                    eir::types::Type::Boolean,
-                                    eir::types::Function::new(
-                                        eir::types::Type::ByteString,
-                                        eir::types::Type::Number,
-                                    ),
-                                ),
-                                eir::ir::FunctionApplication::new(
-                                    eir::types::Function::new(
-                                        eir::types::Type::Number,
-                                        eir::types::Function::new(
-                                            eir::types::Type::Boolean,
-                                            eir::types::Function::new(
-                                                eir::types::Type::ByteString,
-                                                eir::types::Type::Number,
-                                            ),
-                                        ),
-                                    ),
-                                    eir::ir::Variable::new("f"),
-                                    42.0,
-                                ),
-                                true,
-                            ),
-                            eir::ir::ByteString::new("foo"),
                         ),
-                        eir::types::Type::Number,
+                        eir::types::Type::Variant,
                     ),
                 ]));
             }
+        }
+
+        mod tail_calls {
+            use super::*;
+
+            fn create_function_type() -> eir::types::Function {
+                eir::types::Function::new(
+                    eir::types::Type::Number,
+                    eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number),
+                )
+            }
+
+            fn create_call(
+                function: &str,
+                x: impl Into<eir::ir::Expression>,
+                y: impl Into<eir::ir::Expression>,
+            ) -> eir::ir::FunctionApplication {
+                eir::ir::FunctionApplication::new(
+                    eir::types::Function::new(eir::types::Type::Number, eir::types::Type::Number),
+                    eir::ir::FunctionApplication::new(
+                        create_function_type(),
+                        eir::ir::Variable::new(function),
+                        x,
+                    ),
+                    y,
+                )
+            }
+
+            fn compile_module_with_tail_call(module: &eir::ir::Module) {
+                compile_module(module);
+
+                assert!(has_tail_call_in_branch(&compile(module).unwrap()));
+            }
+
+            // Calls at the ends of functions are always followed by returns.
+            // So only the ones in branches tell if tail positions are kept.
+            fn has_tail_call_in_branch(module: &fmm::ir::Module) -> bool {
+                module
+                    .function_definitions()
+                    .iter()
+                    .any(|definition| has_tail_call_in_block(definition.body(), false))
+            }
+
+            // Tail calls are calls whose results are returned immediately.
+            fn has_tail_call_in_block(block: &fmm::ir::Block, branch: bool) -> bool {
+                let is_tail_call = match (block.instructions().last(), block.terminal_instruction())
+                {
+                    (
+                        Some(fmm::ir::Instruction::Call(call)),
+                        fmm::ir::TerminalInstruction::Return(return_),
+                    ) => matches!(
+                        return_.expression(),
+                        fmm::ir::Expression::Variable(variable) if variable.name() == call.name()
+                    ),
+                    _ => false,
+                };
+
+                (branch && is_tail_call)
+                    || block
+                        .instructions()
+                        .iter()
+                        .any(|instruction| match instruction {
+                            fmm::ir::Instruction::If(if_) => {
+                                has_tail_call_in_block(if_.then(), true)
+                                    || has_tail_call_in_block(if_.else_(), true)
+                            }
+                            _ => false,
+                        })
+            }
 
             #[test]
-            fn compile_1_argument_with_arity_of_2() {
-                compile_module(&create_module_with_definitions(vec![
+            fn compile_self_recursion() {
+                compile_module_with_tail_call(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
                         vec![
                             eir::ir::Argument::new("x", eir::types::Type::Number),
-                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
+                            eir::ir::Argument::new("y", eir::types::Type::Number),
                         ],
-                        eir::ir::Variable::new("x"),
-                        eir::types::Type::Number,
-                    ),
-                    eir::ir::Definition::new(
-                        "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
+                        eir::ir::If::new(
+                            eir::ir::ComparisonOperation::new(
                                 eir::types::Type::Number,
-                                eir::types::Function::new(
-                                    eir::types::Type::Boolean,
+                                eir::ir::ComparisonOperator::Equal,
+                                eir::ir::Variable::new("x"),
+                                0.0,
+                            ),
+                            eir::ir::Variable::new("y"),
+                            create_call(
+                                "f",
+                                eir::ir::ArithmeticOperation::new(
                                     eir::types::Type::Number,
+                                    eir::ir::ArithmeticOperator::Subtract,
+                                    eir::ir::Variable::new("x"),
+                                    1.0,
                                 ),
+                                eir::ir::Variable::new("y"),
                             ),
-                            eir::ir::Variable::new("f"),
-                            42.0,
-                        ),
-                        eir::types::Function::new(
-                            eir::types::Type::Boolean,
-                            eir::types::Type::Number,
                         ),
+                        eir::types::Type::Number,
                     ),
                 ]));
             }
 
             #[test]
-            fn compile_1_argument_with_arity_of_3() {
-                compile_module(&create_module_with_definitions(vec![
+            fn compile_mutual_recursion() {
+                compile_module_with_tail_call(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
                         vec![
                             eir::ir::Argument::new("x", eir::types::Type::Number),
-                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
-                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
+                            eir::ir::Argument::new("y", eir::types::Type::Number),
                         ],
-                        eir::ir::Variable::new("x"),
+                        create_call(
+                            "g",
+                            eir::ir::Variable::new("x"),
+                            eir::ir::Variable::new("y"),
+                        ),
                         eir::types::Type::Number,
                     ),
                     eir::ir::Definition::new(
                         "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::Number,
-                                eir::types::Function::new(
-                                    eir::types::Type::Boolean,
-                                    eir::types::Function::new(
-                                        eir::types::Type::ByteString,
-                                        eir::types::Type::Number,
-                                    ),
-                                ),
-                            ),
-                            eir::ir::Variable::new("f"),
-                            42.0,
-                        ),
-                        eir::types::Function::new(
-                            eir::types::Type::Boolean,
-                            eir::types::Function::new(
-                                eir::types::Type::ByteString,
-                                eir::types::Type::Number,
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Number),
+                        ],
+                        eir::ir::If::new(
+                            true,
+                            eir::ir::Variable::new("x"),
+                            create_call(
+                                "f",
+                                eir::ir::Variable::new("x"),
+                                eir::ir::Variable::new("y"),
                             ),
                         ),
+                        eir::types::Type::Number,
                     ),
                 ]));
             }
 
             #[test]
-            fn compile_2_arguments_with_arity_of_3() {
-                compile_module(&create_module_with_definitions(vec![
+            fn compile_in_case() {
+                compile_module_with_tail_call(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
                         vec![
-                            eir::ir::Argument::new("x", eir::types::Type::Number),
-                            eir::ir::Argument::new("y", eir::types::Type::Boolean),
-                            eir::ir::Argument::new("z", eir::types::Type::ByteString),
+                            eir::ir::Argument::new("x", eir::types::Type::Variant),
+                            eir::ir::Argument::new("y", eir::types::Type::Number),
                         ],
-                        eir::ir::Variable::new("x"),
+                        eir::ir::Case::new(
+                            eir::ir::Variable::new("x"),
+                            vec![eir::ir::Alternative::new(
+                                eir::types::Type::Number,
+                                "z",
+                                create_call(
+                                    "g",
+                                    eir::ir::Variable::new("z"),
+                                    eir::ir::Variable::new("y"),
+                                ),
+                            )],
+                            Some(eir::ir::DefaultAlternative::new("_", 42.0)),
+                        ),
                         eir::types::Type::Number,
                     ),
                     eir::ir::Definition::new(
                         "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::Boolean,
-                                eir::types::Function::new(
-                                    eir::types::Type::ByteString,
-                                    eir::types::Type::Number,
-                                ),
-                            ),
-                            eir::ir::FunctionApplication::new(
-                                eir::types::Function::new(
-                                    eir::types::Type::Number,
-                                    eir::types::Function::new(
-                                        eir::types::Type::Boolean,
-                                        eir::types::Function::new(
-                                            eir::types::Type::ByteString,
-                                            eir::types::Type::Number,
-                                        ),
-                                    ),
-                                ),
-                                eir::ir::Variable::new("f"),
-                                42.0,
-                            ),
-                            true,
-                        ),
-                        eir::types::Function::new(
-                            eir::types::Type::ByteString,
-                            eir::types::Type::Number,
-                        ),
+                        vec![
+                            eir::ir::Argument::new("x", eir::types::Type::Number),
+                            eir::ir::Argument::new("y", eir::types::Type::Number),
+                        ],
+                        eir::ir::Variable::new("x"),
+                        eir::types::Type::Number,
                     ),
                 ]));
             }
 
             #[test]
-            fn compile_with_curried_function() {
-                compile_module(&create_module_with_definitions(vec![
+            fn compile_in_local_definition() {
+                compile_module_with_tail_call(&create_module_with_definitions(vec![
                     eir::ir::Definition::new(
                         "f",
                         vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
                         eir::ir::LetRecursive::new(
                             eir::ir::Definition::new(
                                 "g",
-                                vec![eir::ir::Argument::new("y", eir::types::Type::Number)],
-                                eir::ir::ArithmeticOperation::new(
-                                    eir::ir::ArithmeticOperator::Add,
+                                vec![
+                                    eir::ir::Argument::new("y", eir::types::Type::Number),
+                                    eir::ir::Argument::new("z", eir::types::Type::Number),
+                                ],
+                                eir::ir::If::new(
+                                    true,
                                     eir::ir::Variable::new("x"),
-                                    eir::ir::Variable::new("y"),
+                                    create_call(
+                                        "g",
+                                        eir::ir::Variable::new("y"),
+                                        eir::ir::Variable::new("z"),
+                                    ),
                                 ),
                                 eir::types::Type::Number,
                             ),
-                            eir::ir::Variable::new("g"),
-                        ),
-                        eir::types::Function::new(
-                            eir::types::Type::Number,
-                            eir::types::Type::Number,
+                            create_call("g", eir::ir::Variable::new("x"), 42.0),
                         ),
+                        eir::types::Type::Number,
                     ),
-                    eir::ir::Definition::new(
-                        "g",
-                        vec![eir::ir::Argument::new("x", eir::types::Type::Number)],
-                        eir::ir::FunctionApplication::new(
-                            eir::types::Function::new(
-                                eir::types::Type::Number,
-                                eir::types::Type::Number,
-                            ),
-                            eir::ir::FunctionApplication::new(
-                                eir::types::Function::new(
-                                    eir::types::Type::Number,
-                                    eir::types::Function::new(
-                                        eir::types::Type::Number,
-                                        eir::types::Type::Number,
-                                    ),
-                                ),
-                                eir::ir::Variable::new("f"),
-                                111.0,
+                ]));
+            }
+
+            #[test]
+            fn compile_non_tail_call_in_branch() {
+                let module = create_module_with_definitions(vec![eir::ir::Definition::new(
+                    "f",
+                    vec![
+                        eir::ir::Argument::new("x", eir::types::Type::Number),
+                        eir::ir::Argument::new("y", eir::types::Type::Number),
+                    ],
+                    eir::ir::If::new(
+                        true,
+                        eir::ir::Variable::new("y"),
+                        eir::ir::ArithmeticOperation::new(
+                            eir::types::Type::Number,
+                            eir::ir::ArithmeticOperator::Add,
+                            create_call(
+                                "f",
+                                eir::ir::Variable::new("x"),
+                                eir::ir::Variable::new("y"),
                             ),
-                            222.0,
+                            1.0,
                         ),
-                        eir::types::Type::Number,
                     ),
-                ]));
+                    eir::types::Type::Number,
+                )]);
+
+                compile_module(&module);
+
+                assert!(!has_tail_call_in_branch(&compile(&module).unwrap()));
             }
         }
 
@@ -0,0 +1,61 @@
+// Some operations on numbers are compiled into calls of functions in the C
+// standard library.
+const REMAINDER_FUNCTION_NAME: &str = "fmod";
+const POWER_FUNCTION_NAME: &str = "pow";
+
+pub fn compile_function_declarations(module_builder: &fmm::build::ModuleBuilder) {
+    for name in [REMAINDER_FUNCTION_NAME, POWER_FUNCTION_NAME] {
+        module_builder.declare_function(name, compile_binary_function_type());
+    }
+}
+
+pub fn compile_negation(
+    value: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    // Multiplication flips signs of zeros unlike subtraction from zero.
+    Ok(fmm::build::arithmetic_operation(
+        fmm::ir::ArithmeticOperator::Multiply,
+        value,
+        fmm::ir::Primitive::Float64(-1.0),
+    )?
+    .into())
+}
+
+pub fn compile_remainder(
+    builder: &fmm::build::InstructionBuilder,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    compile_binary_function_call(builder, REMAINDER_FUNCTION_NAME, lhs, rhs)
+}
+
+pub fn compile_power(
+    builder: &fmm::build::InstructionBuilder,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    compile_binary_function_call(builder, POWER_FUNCTION_NAME, lhs, rhs)
+}
+
+fn compile_binary_function_call(
+    builder: &fmm::build::InstructionBuilder,
+    name: &str,
+    lhs: fmm::build::TypedExpression,
+    rhs: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    builder.call(
+        fmm::build::variable(name, compile_binary_function_type()),
+        vec![lhs, rhs],
+    )
+}
+
+fn compile_binary_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![
+            fmm::types::Primitive::Float64.into(),
+            fmm::types::Primitive::Float64.into(),
+        ],
+        fmm::types::Primitive::Float64,
+        fmm::types::CallingConvention::Target,
+    )
+}
@@ -0,0 +1,498 @@
+use crate::{error::CompileError, variant};
+
+// Numbers in [0, 2^52) added to this number have mantissa bits equal to
+// themselves. Conversions between numbers and integers are compiled with
+// bit casts of such numbers as fmm does not have instructions for them.
+const MANTISSA_OFFSET: f64 = 4503599627370496.0;
+const WORD_SIZE: f64 = 4294967296.0;
+
+pub fn compile(
+    builder: &fmm::build::InstructionBuilder,
+    conversion: &eir::ir::NumericConversion,
+    argument: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    match (
+        integer_type(conversion.from()),
+        integer_type(conversion.to()),
+    ) {
+        (None, None) => compile_rounding(builder, &argument, conversion.rounding()),
+        (None, Some(to)) => compile_number_to_integer(
+            builder,
+            &compile_rounding(builder, &argument, conversion.rounding())?,
+            to,
+        ),
+        (Some(from), None) => compile_integer_to_number(builder, &argument, from),
+        (Some(from), Some(to)) => Ok(compile_truncation(
+            builder,
+            compile_extension(builder, &argument, from)?,
+            to,
+        )?),
+    }
+}
+
+fn integer_type(type_: &eir::types::Type) -> Option<eir::types::Integer> {
+    match type_ {
+        eir::types::Type::Integer(integer) => Some(*integer),
+        _ => None,
+    }
+}
+
+fn compile_rounding(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    rounding: eir::ir::Rounding,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let absolute = compile_absolute_number(builder, argument)?;
+    let rounded = compile_integral_number(builder, argument, rounding)?;
+
+    // Restore a sign of zero.
+    let rounded = fmm::build::bit_cast(
+        fmm::types::Primitive::Float64,
+        bitwise(
+            fmm::ir::BitwiseOperator::Or,
+            fmm::build::bit_cast(fmm::types::Primitive::Integer64, rounded),
+            bitwise(
+                fmm::ir::BitwiseOperator::And,
+                fmm::build::bit_cast(fmm::types::Primitive::Integer64, argument.clone()),
+                integer64(1 << 63),
+            )?,
+        )?,
+    )
+    .into();
+
+    // Numbers whose absolute values are not less than 2^52 are integral
+    // already. The condition is false for NaN too.
+    compile_if(
+        builder,
+        comparison(
+            fmm::ir::ComparisonOperator::LessThan(true),
+            absolute,
+            number(MANTISSA_OFFSET),
+        )?,
+        rounded,
+        argument.clone(),
+    )
+}
+
+// Rounds a number whose absolute value is less than 2^52.
+fn compile_integral_number(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    rounding: eir::ir::Rounding,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    // Rounds a number to the nearest integer with ties to even.
+    let nearest = compile_if(
+        builder,
+        comparison(
+            fmm::ir::ComparisonOperator::LessThan(true),
+            argument.clone(),
+            number(0.0),
+        )?,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Add,
+            arithmetic(
+                fmm::ir::ArithmeticOperator::Subtract,
+                argument.clone(),
+                number(MANTISSA_OFFSET),
+            )?,
+            number(MANTISSA_OFFSET),
+        )?,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            arithmetic(
+                fmm::ir::ArithmeticOperator::Add,
+                argument.clone(),
+                number(MANTISSA_OFFSET),
+            )?,
+            number(MANTISSA_OFFSET),
+        )?,
+    )?;
+
+    match rounding {
+        eir::ir::Rounding::Ceiling => compile_if(
+            builder,
+            comparison(
+                fmm::ir::ComparisonOperator::LessThan(true),
+                nearest.clone(),
+                argument.clone(),
+            )?,
+            arithmetic(
+                fmm::ir::ArithmeticOperator::Add,
+                nearest.clone(),
+                number(1.0),
+            )?,
+            nearest,
+        ),
+        eir::ir::Rounding::Floor => compile_if(
+            builder,
+            comparison(
+                fmm::ir::ComparisonOperator::GreaterThan(true),
+                nearest.clone(),
+                argument.clone(),
+            )?,
+            arithmetic(
+                fmm::ir::ArithmeticOperator::Subtract,
+                nearest.clone(),
+                number(1.0),
+            )?,
+            nearest,
+        ),
+        eir::ir::Rounding::Round => {
+            let truncated =
+                compile_integral_number(builder, argument, eir::ir::Rounding::Truncate)?;
+            let fraction = arithmetic(
+                fmm::ir::ArithmeticOperator::Subtract,
+                argument.clone(),
+                truncated.clone(),
+            )?;
+
+            compile_if(
+                builder,
+                comparison(
+                    fmm::ir::ComparisonOperator::GreaterThanOrEqual(true),
+                    fraction.clone(),
+                    number(0.5),
+                )?,
+                arithmetic(
+                    fmm::ir::ArithmeticOperator::Add,
+                    truncated.clone(),
+                    number(1.0),
+                )?,
+                compile_if(
+                    builder,
+                    comparison(
+                        fmm::ir::ComparisonOperator::LessThanOrEqual(true),
+                        fraction,
+                        number(-0.5),
+                    )?,
+                    arithmetic(
+                        fmm::ir::ArithmeticOperator::Subtract,
+                        truncated.clone(),
+                        number(1.0),
+                    )?,
+                    truncated,
+                )?,
+            )
+        }
+        eir::ir::Rounding::Truncate => compile_if(
+            builder,
+            comparison(
+                fmm::ir::ComparisonOperator::LessThan(true),
+                argument.clone(),
+                number(0.0),
+            )?,
+            compile_integral_number(builder, argument, eir::ir::Rounding::Ceiling)?,
+            compile_integral_number(builder, argument, eir::ir::Rounding::Floor)?,
+        ),
+    }
+}
+
+// Converts an integral number into an integer saturating it.
+fn compile_number_to_integer(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    to: eir::types::Integer,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let (minimum, maximum) = integer_range(to);
+    let magnitude =
+        compile_number_to_unsigned_integer(builder, &compile_absolute_number(builder, argument)?)?;
+
+    let integer = compile_if(
+        builder,
+        comparison(
+            fmm::ir::ComparisonOperator::GreaterThanOrEqual(true),
+            argument.clone(),
+            number(minimum as f64),
+        )?,
+        compile_if(
+            builder,
+            comparison(
+                fmm::ir::ComparisonOperator::LessThan(true),
+                argument.clone(),
+                // The maximum plus one is a power of two and exact.
+                number(maximum as f64 + 1.0),
+            )?,
+            compile_if(
+                builder,
+                comparison(
+                    fmm::ir::ComparisonOperator::LessThan(true),
+                    argument.clone(),
+                    number(0.0),
+                )?,
+                arithmetic(
+                    fmm::ir::ArithmeticOperator::Subtract,
+                    integer64(0),
+                    magnitude.clone(),
+                )?,
+                magnitude,
+            )?,
+            integer64(maximum as u64),
+        )?,
+        // The condition is false for NaN.
+        compile_if(
+            builder,
+            comparison(
+                fmm::ir::ComparisonOperator::LessThan(true),
+                argument.clone(),
+                number(minimum as f64),
+            )?,
+            integer64(minimum as u64),
+            integer64(0),
+        )?,
+    )?;
+
+    Ok(compile_truncation(builder, integer, to)?)
+}
+
+// Converts an integral number in [0, 2^64) into a 64-bit integer.
+fn compile_number_to_unsigned_integer(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let high = compile_integral_number(
+        builder,
+        &arithmetic(
+            fmm::ir::ArithmeticOperator::Divide,
+            argument.clone(),
+            number(WORD_SIZE),
+        )?,
+        eir::ir::Rounding::Floor,
+    )?;
+    let low = arithmetic(
+        fmm::ir::ArithmeticOperator::Subtract,
+        argument.clone(),
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Multiply,
+            high.clone(),
+            number(WORD_SIZE),
+        )?,
+    )?;
+    let convert = |value: fmm::build::TypedExpression| {
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            fmm::build::bit_cast(
+                fmm::types::Primitive::Integer64,
+                arithmetic(
+                    fmm::ir::ArithmeticOperator::Add,
+                    value,
+                    number(MANTISSA_OFFSET),
+                )?,
+            ),
+            integer64(MANTISSA_OFFSET.to_bits()),
+        )
+    };
+
+    Ok(arithmetic(
+        fmm::ir::ArithmeticOperator::Add,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Multiply,
+            convert(high)?,
+            integer64(WORD_SIZE as u64),
+        )?,
+        convert(low)?,
+    )?)
+}
+
+fn compile_integer_to_number(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    from: eir::types::Integer,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let integer = compile_extension(builder, argument, from)?;
+
+    if !from.is_signed() {
+        return Ok(compile_unsigned_integer_to_number(&integer)?);
+    }
+
+    let negative = comparison(
+        fmm::ir::ComparisonOperator::LessThan(true),
+        integer.clone(),
+        integer64(0),
+    )?;
+    let number = compile_unsigned_integer_to_number(&compile_if(
+        builder,
+        negative.clone(),
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            integer64(0),
+            integer.clone(),
+        )?,
+        integer,
+    )?)?;
+
+    compile_if(
+        builder,
+        negative,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            self::number(0.0),
+            number.clone(),
+        )?,
+        number,
+    )
+}
+
+// Converts a 64-bit unsigned integer into a number. Its upper and lower words
+// are converted exactly and then rounded only once on their addition.
+fn compile_unsigned_integer_to_number(
+    argument: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    let convert = |value: fmm::build::TypedExpression| {
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            fmm::build::bit_cast(
+                fmm::types::Primitive::Float64,
+                bitwise(
+                    fmm::ir::BitwiseOperator::Or,
+                    value,
+                    integer64(MANTISSA_OFFSET.to_bits()),
+                )?,
+            ),
+            number(MANTISSA_OFFSET),
+        )
+    };
+
+    arithmetic(
+        fmm::ir::ArithmeticOperator::Add,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Multiply,
+            convert(arithmetic(
+                fmm::ir::ArithmeticOperator::Divide,
+                argument.clone(),
+                integer64(WORD_SIZE as u64),
+            )?)?,
+            number(WORD_SIZE),
+        )?,
+        convert(bitwise(
+            fmm::ir::BitwiseOperator::And,
+            argument.clone(),
+            integer64(WORD_SIZE as u64 - 1),
+        )?)?,
+    )
+}
+
+// Extends an integer into a 64-bit one. Upper bits of integers cast from
+// smaller ones are masked as they are undefined.
+fn compile_extension(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+    from: eir::types::Integer,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    if from.bits() == 64 {
+        return Ok(argument.clone());
+    }
+
+    let mask = (1u64 << from.bits()) - 1;
+    let integer = bitwise(
+        fmm::ir::BitwiseOperator::And,
+        variant::compile_union_bit_cast(
+            builder,
+            fmm::types::Primitive::Integer64,
+            argument.clone(),
+        )?,
+        integer64(mask),
+    )?;
+
+    if !from.is_signed() {
+        return Ok(integer);
+    }
+
+    compile_if(
+        builder,
+        comparison(
+            fmm::ir::ComparisonOperator::GreaterThan(false),
+            integer.clone(),
+            integer64(mask >> 1),
+        )?,
+        bitwise(
+            fmm::ir::BitwiseOperator::Or,
+            integer.clone(),
+            integer64(!mask),
+        )?,
+        integer,
+    )
+}
+
+// Truncates a 64-bit integer into a smaller one assuming that targets are
+// little-endian.
+fn compile_truncation(
+    builder: &fmm::build::InstructionBuilder,
+    argument: fmm::build::TypedExpression,
+    to: eir::types::Integer,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    variant::compile_union_bit_cast(builder, crate::types::compile_integer(to), argument)
+}
+
+// Returns the minimum and maximum values of an integer type.
+fn integer_range(type_: eir::types::Integer) -> (i128, i128) {
+    if type_.is_signed() {
+        (-(1 << (type_.bits() - 1)), (1 << (type_.bits() - 1)) - 1)
+    } else {
+        (0, (1 << type_.bits()) - 1)
+    }
+}
+
+fn compile_absolute_number(
+    builder: &fmm::build::InstructionBuilder,
+    argument: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    compile_if(
+        builder,
+        comparison(
+            fmm::ir::ComparisonOperator::LessThan(true),
+            argument.clone(),
+            number(0.0),
+        )?,
+        arithmetic(
+            fmm::ir::ArithmeticOperator::Subtract,
+            number(0.0),
+            argument.clone(),
+        )?,
+        argument.clone(),
+    )
+}
+
+fn compile_if(
+    builder: &fmm::build::InstructionBuilder,
+    condition: fmm::build::TypedExpression,
+    then: fmm::build::TypedExpression,
+    else_: fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        condition,
+        |builder| -> Result<_, CompileError> { Ok(builder.branch(then.clone())) },
+        |builder| Ok(builder.branch(else_.clone())),
+    )
+}
+
+fn arithmetic(
+    operator: fmm::ir::ArithmeticOperator,
+    lhs: impl Into<fmm::build::TypedExpression>,
+    rhs: impl Into<fmm::build::TypedExpression>,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::arithmetic_operation(operator, lhs, rhs)?.into())
+}
+
+fn comparison(
+    operator: fmm::ir::ComparisonOperator,
+    lhs: impl Into<fmm::build::TypedExpression>,
+    rhs: impl Into<fmm::build::TypedExpression>,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::comparison_operation(operator, lhs, rhs)?.into())
+}
+
+fn bitwise(
+    operator: fmm::ir::BitwiseOperator,
+    lhs: impl Into<fmm::build::TypedExpression>,
+    rhs: impl Into<fmm::build::TypedExpression>,
+) -> Result<fmm::build::TypedExpression, fmm::build::BuildError> {
+    Ok(fmm::build::bitwise_operation(operator, lhs, rhs)?.into())
+}
+
+fn number(value: f64) -> fmm::build::TypedExpression {
+    fmm::ir::Primitive::Float64(value).into()
+}
+
+fn integer64(value: u64) -> fmm::build::TypedExpression {
+    fmm::ir::Primitive::Integer64(value).into()
+}
@@ -1,10 +1,10 @@
-use crate::{error::CompileError, types};
+use crate::{error::CompileError, reference_count, types};
 use std::collections::HashMap;
 
 pub fn get_record_element(
     builder: &fmm::build::InstructionBuilder,
     record: &fmm::build::TypedExpression,
-    type_: &eir::types::Record,
+    type_: &eir::types::Type,
     element_index: usize,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
@@ -20,3 +20,140 @@ pub fn get_record_element(
         element_index,
     )?)
 }
+
+pub fn get_record_element_index(
+    type_: &eir::types::Type,
+    field: &eir::ir::RecordField,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> usize {
+    match (type_, field) {
+        (_, eir::ir::RecordField::Index(index)) => *index,
+        (eir::types::Type::Record(record), eir::ir::RecordField::Name(name)) => {
+            types[record.name()].field_index(name).unwrap()
+        }
+        (eir::types::Type::Tuple(tuple), eir::ir::RecordField::Name(name)) => {
+            tuple.field_index(name).unwrap()
+        }
+        _ => unreachable!("field names must belong to record or tuple types"),
+    }
+}
+
+// Records are updated in place if they are not shared. Otherwise, they are
+// copied with the rest of their elements cloned.
+pub fn update_record(
+    builder: &fmm::build::InstructionBuilder,
+    record: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+    fields: &[(usize, fmm::build::TypedExpression)],
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    if !types::is_record_boxed(type_, types) {
+        return Ok(record.clone());
+    }
+
+    let unboxed_type = types::compile_unboxed_record(type_, types);
+    let element_types = types::get_record_elements(type_, types);
+    let pointer = fmm::build::bit_cast(
+        fmm::types::Pointer::new(unboxed_type.clone()),
+        record.clone(),
+    );
+
+    Ok(builder.if_(
+        reference_count::is_owned_pointer(builder, record)?,
+        |builder| -> Result<_, CompileError> {
+            for (index, value) in fields {
+                let address = fmm::build::record_address(pointer.clone(), *index)?;
+
+                reference_count::drop_expression(
+                    &builder,
+                    &builder.load(address.clone())?,
+                    &element_types[*index],
+                    types,
+                )?;
+                builder.store(value.clone(), address);
+            }
+
+            Ok(builder.branch(record.clone()))
+        },
+        |builder| -> Result<_, CompileError> {
+            let unboxed = builder.load(pointer.clone())?;
+            let elements = element_types
+                .iter()
+                .enumerate()
+                .map(|(index, type_)| {
+                    Ok(
+                        if let Some((_, value)) = fields.iter().find(|(field, _)| *field == index) {
+                            value.clone()
+                        } else {
+                            let element = builder.deconstruct_record(unboxed.clone(), index)?;
+
+                            reference_count::clone_expression(&builder, &element, type_, types)?;
+
+                            element
+                        },
+                    )
+                })
+                .collect::<Result<Vec<_>, CompileError>>()?;
+
+            let updated = reference_count::allocate_heap(&builder, unboxed_type.clone())?;
+            builder.store(fmm::build::record(elements), updated.clone());
+            reference_count::drop_expression(&builder, record, type_, types)?;
+
+            Ok(builder.branch(fmm::build::bit_cast(
+                types::compile_record(type_, types),
+                updated,
+            )))
+        },
+    )?)
+}
+
+// Elements are moved out of records if they are not shared. Otherwise, they
+// are cloned and the records are dropped.
+pub fn deconstruct_record(
+    builder: &fmm::build::InstructionBuilder,
+    record: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<Vec<fmm::build::TypedExpression>, CompileError> {
+    let element_types = types::get_record_elements(type_, types);
+
+    let unboxed = if types::is_record_boxed(type_, types) {
+        let pointer = fmm::build::bit_cast(
+            fmm::types::Pointer::new(types::compile_unboxed_record(type_, types)),
+            record.clone(),
+        );
+
+        builder.if_(
+            reference_count::is_owned_pointer(builder, record)?,
+            |builder| -> Result<_, CompileError> {
+                let unboxed = builder.load(pointer.clone())?;
+
+                reference_count::free_heap(&builder, record.clone())?;
+
+                Ok(builder.branch(unboxed))
+            },
+            |builder| -> Result<_, CompileError> {
+                let unboxed = builder.load(pointer.clone())?;
+
+                for (index, element_type) in element_types.iter().enumerate() {
+                    reference_count::clone_expression(
+                        &builder,
+                        &builder.deconstruct_record(unboxed.clone(), index)?,
+                        element_type,
+                        types,
+                    )?;
+                }
+
+                reference_count::drop_expression(&builder, record, type_, types)?;
+
+                Ok(builder.branch(unboxed))
+            },
+        )?
+    } else {
+        record.clone()
+    };
+
+    (0..element_types.len())
+        .map(|index| Ok(builder.deconstruct_record(unboxed.clone(), index)?))
+        .collect()
+}
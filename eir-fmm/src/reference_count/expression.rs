@@ -1,8 +1,9 @@
-use super::{super::error::CompileError, function, pointer, record_utilities};
+use super::{super::error::CompileError, function, pointer, record, record_utilities, sum};
 use crate::{
     type_information::{
         TYPE_INFORMATION_CLONE_FUNCTION_ELEMENT_INDEX, TYPE_INFORMATION_DROP_FUNCTION_ELEMENT_INDEX,
     },
+    types,
     variant::{VARIANT_PAYLOAD_ELEMENT_INDEX, VARIANT_TAG_ELEMENT_INDEX},
 };
 use std::collections::HashMap;
@@ -15,7 +16,9 @@ pub fn clone_expression(
 ) -> Result<(), CompileError> {
     match type_ {
         eir::types::Type::ByteString => pointer::clone_pointer(builder, expression)?,
-        eir::types::Type::Function(_) => function::clone_function(builder, expression)?,
+        eir::types::Type::Function(_) | eir::types::Type::Lazy(_) => {
+            function::clone_function(builder, expression)?
+        }
         eir::types::Type::Record(record) => {
             builder.call(
                 fmm::build::variable(
@@ -25,6 +28,20 @@ pub fn clone_expression(
                 vec![expression.clone()],
             )?;
         }
+        eir::types::Type::Sum(sum) => {
+            builder.call(
+                fmm::build::variable(
+                    sum::get_sum_clone_function_name(sum.name()),
+                    sum::compile_sum_rc_function_type(),
+                ),
+                vec![expression.clone()],
+            )?;
+        }
+        eir::types::Type::Tuple(_) => {
+            if types::is_record_boxed(type_, types) {
+                pointer::clone_pointer(builder, expression)?;
+            }
+        }
         eir::types::Type::Variant => {
             builder.call(
                 builder.deconstruct_record(
@@ -38,7 +55,8 @@ pub fn clone_expression(
                     .deconstruct_record(expression.clone(), VARIANT_PAYLOAD_ELEMENT_INDEX)?],
             )?;
         }
-        eir::types::Type::Boolean | eir::types::Type::Number => {}
+        eir::types::Type::Boolean | eir::types::Type::Integer(_) | eir::types::Type::Number => {}
+        eir::types::Type::Variable(_) => unreachable!("type variables must be monomorphized"),
     }
 
     Ok(())
@@ -52,7 +70,9 @@ pub fn drop_expression(
 ) -> Result<(), CompileError> {
     match type_ {
         eir::types::Type::ByteString => pointer::drop_pointer(builder, expression, |_| Ok(()))?,
-        eir::types::Type::Function(_) => function::drop_function(builder, expression)?,
+        eir::types::Type::Function(_) | eir::types::Type::Lazy(_) => {
+            function::drop_function(builder, expression)?
+        }
         eir::types::Type::Record(record) => {
             builder.call(
                 fmm::build::variable(
@@ -62,6 +82,23 @@ pub fn drop_expression(
                 vec![expression.clone()],
             )?;
         }
+        eir::types::Type::Sum(sum) => {
+            builder.call(
+                fmm::build::variable(
+                    sum::get_sum_drop_function_name(sum.name()),
+                    sum::compile_sum_rc_function_type(),
+                ),
+                vec![expression.clone()],
+            )?;
+        }
+        // Tuples have no type definitions and are dropped inline.
+        eir::types::Type::Tuple(_) => {
+            if types::is_record_boxed(type_, types) {
+                pointer::drop_pointer(builder, expression, |builder| {
+                    record::drop_record_elements(builder, expression, type_, types)
+                })?;
+            }
+        }
         eir::types::Type::Variant => {
             builder.call(
                 builder.deconstruct_record(
@@ -75,7 +112,8 @@ pub fn drop_expression(
                     .deconstruct_record(expression.clone(), VARIANT_PAYLOAD_ELEMENT_INDEX)?],
             )?;
         }
-        eir::types::Type::Boolean | eir::types::Type::Number => {}
+        eir::types::Type::Boolean | eir::types::Type::Integer(_) | eir::types::Type::Number => {}
+        eir::types::Type::Variable(_) => unreachable!("type variables must be monomorphized"),
     }
 
     Ok(())
@@ -8,15 +8,42 @@ pub fn allocate_heap(
     type_: impl Into<fmm::types::Type>,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     let type_ = type_.into();
+
+    allocate(
+        builder,
+        type_.clone(),
+        fmm::build::size_of(fmm::types::Record::new(vec![COUNT_TYPE.into(), type_])),
+    )
+}
+
+// Allocates heap blocks with extra bytes after values for data of dynamic
+// sizes, such as bytes of strings.
+pub fn allocate_variable_sized_heap(
+    builder: &fmm::build::InstructionBuilder,
+    type_: impl Into<fmm::types::Type>,
+    extra_size: impl Into<fmm::build::TypedExpression>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let type_ = type_.into();
+
+    allocate(
+        builder,
+        type_.clone(),
+        fmm::build::arithmetic_operation(
+            fmm::ir::ArithmeticOperator::Add,
+            fmm::build::size_of(fmm::types::Record::new(vec![COUNT_TYPE.into(), type_])),
+            extra_size,
+        )?,
+    )
+}
+
+fn allocate(
+    builder: &fmm::build::InstructionBuilder,
+    type_: fmm::types::Type,
+    size: impl Into<fmm::build::TypedExpression>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
     let pointer = fmm::build::bit_cast(
-        fmm::types::Pointer::new(fmm::types::Record::new(vec![
-            COUNT_TYPE.into(),
-            type_.clone(),
-        ])),
-        builder.allocate_heap(fmm::build::size_of(fmm::types::Record::new(vec![
-            COUNT_TYPE.into(),
-            type_,
-        ]))),
+        fmm::types::Pointer::new(fmm::types::Record::new(vec![COUNT_TYPE.into(), type_])),
+        builder.allocate_heap(size),
     );
 
     builder.store(
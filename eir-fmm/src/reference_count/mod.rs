@@ -4,13 +4,17 @@ mod heap;
 mod pointer;
 mod record;
 mod record_utilities;
+mod sum;
 mod variant;
 
 pub use expression::*;
 pub use function::*;
 pub use heap::*;
-pub use pointer::{compile_tagged_pointer, compile_untagged_pointer, drop_pointer};
+pub use pointer::{
+    compile_tagged_pointer, compile_untagged_pointer, drop_pointer, is_owned_pointer,
+};
 pub use record::*;
+pub use sum::{compile_sum_clone_function, compile_sum_drop_function};
 pub use variant::*;
 
 pub(self) fn reference_count_function_definition_options() -> fmm::ir::FunctionDefinitionOptions {
@@ -59,6 +59,27 @@ pub fn drop_pointer(
     Ok(())
 }
 
+// Checks if a pointer is on heap and referenced by nothing else.
+pub fn is_owned_pointer(
+    builder: &fmm::build::InstructionBuilder,
+    pointer: &fmm::build::TypedExpression,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(builder.if_(
+        is_heap_pointer(pointer)?,
+        |builder| -> Result<_, CompileError> {
+            Ok(builder.branch(fmm::build::comparison_operation(
+                fmm::ir::ComparisonOperator::Equal,
+                builder.atomic_load(
+                    get_counter_pointer(pointer)?,
+                    fmm::ir::AtomicOrdering::Acquire,
+                )?,
+                fmm::ir::Primitive::PointerInteger(heap::INITIAL_COUNT as i64),
+            )?))
+        },
+        |builder| Ok(builder.branch(fmm::ir::Primitive::Boolean(false))),
+    )?)
+}
+
 pub fn compile_tagged_pointer(
     pointer: &fmm::build::TypedExpression,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
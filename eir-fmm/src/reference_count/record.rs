@@ -11,7 +11,7 @@ pub fn compile_record_clone_function(
     definition: &eir::ir::TypeDefinition,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> Result<(), CompileError> {
-    let record_type = eir::types::Record::new(definition.name());
+    let record_type = eir::types::Type::from(eir::types::Record::new(definition.name()));
     let fmm_record_type = types::compile_record(&record_type, types);
 
     module_builder.define_function(
@@ -56,7 +56,7 @@ pub fn compile_record_drop_function(
     definition: &eir::ir::TypeDefinition,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> Result<(), CompileError> {
-    let record_type = eir::types::Record::new(definition.name());
+    let record_type = eir::types::Type::from(eir::types::Record::new(definition.name()));
     let fmm_record_type = types::compile_record(&record_type, types);
 
     module_builder.define_function(
@@ -71,18 +71,12 @@ pub fn compile_record_drop_function(
 
             if types::is_record_boxed(&record_type, types) {
                 pointer::drop_pointer(&builder, &record, |builder| {
-                    drop_record_elements(
-                        builder,
-                        &record,
-                        &record_type,
-                        definition.type_(),
-                        types,
-                    )?;
+                    drop_record_elements(builder, &record, &record_type, types)?;
 
                     Ok(())
                 })?;
             } else {
-                drop_record_elements(&builder, &record, &record_type, definition.type_(), types)?;
+                drop_record_elements(&builder, &record, &record_type, types)?;
             }
 
             Ok(builder.return_(fmm::ir::void_value()))
@@ -93,14 +87,16 @@ pub fn compile_record_drop_function(
     Ok(())
 }
 
-fn drop_record_elements(
+pub(super) fn drop_record_elements(
     builder: &fmm::build::InstructionBuilder,
     record: &fmm::build::TypedExpression,
-    record_type: &eir::types::Record,
-    record_body_type: &eir::types::RecordBody,
+    record_type: &eir::types::Type,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> Result<(), CompileError> {
-    for (index, type_) in record_body_type.elements().iter().enumerate() {
+    for (index, type_) in types::get_record_elements(record_type, types)
+        .iter()
+        .enumerate()
+    {
         expression::drop_expression(
             builder,
             &crate::records::get_record_element(builder, record, record_type, index, types)?,
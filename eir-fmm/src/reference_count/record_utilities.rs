@@ -14,7 +14,7 @@ pub fn compile_record_rc_function_type(
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> fmm::types::Function {
     fmm::types::Function::new(
-        vec![types::compile_record(record, types)],
+        vec![types::compile_record(&record.clone().into(), types)],
         fmm::types::void_type(),
         fmm::types::CallingConvention::Target,
     )
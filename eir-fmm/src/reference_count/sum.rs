@@ -0,0 +1,100 @@
+use super::{
+    super::{error::CompileError, types},
+    expression, reference_count_function_definition_options,
+};
+use crate::{sum::SUM_TAG_ELEMENT_INDEX, switch};
+use std::collections::HashMap;
+
+const ARGUMENT_NAME: &str = "_sum";
+
+pub fn get_sum_clone_function_name(name: &str) -> String {
+    format!("eir_clone_sum_{}", name)
+}
+
+pub fn get_sum_drop_function_name(name: &str) -> String {
+    format!("eir_drop_sum_{}", name)
+}
+
+pub fn compile_sum_rc_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![types::compile_sum().into()],
+        fmm::types::void_type(),
+        fmm::types::CallingConvention::Target,
+    )
+}
+
+pub fn compile_sum_clone_function(
+    module_builder: &fmm::build::ModuleBuilder,
+    definition: &eir::ir::SumDefinition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<(), CompileError> {
+    compile_sum_rc_function(
+        module_builder,
+        &get_sum_clone_function_name(definition.name()),
+        definition,
+        types,
+        expression::clone_expression,
+    )
+}
+
+pub fn compile_sum_drop_function(
+    module_builder: &fmm::build::ModuleBuilder,
+    definition: &eir::ir::SumDefinition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<(), CompileError> {
+    compile_sum_rc_function(
+        module_builder,
+        &get_sum_drop_function_name(definition.name()),
+        definition,
+        types,
+        expression::drop_expression,
+    )
+}
+
+fn compile_sum_rc_function(
+    module_builder: &fmm::build::ModuleBuilder,
+    name: &str,
+    definition: &eir::ir::SumDefinition,
+    types: &HashMap<String, eir::types::RecordBody>,
+    compile_payload_rc: impl Fn(
+        &fmm::build::InstructionBuilder,
+        &fmm::build::TypedExpression,
+        &eir::types::Type,
+        &HashMap<String, eir::types::RecordBody>,
+    ) -> Result<(), CompileError>,
+) -> Result<(), CompileError> {
+    module_builder.define_function(
+        name,
+        vec![fmm::ir::Argument::new(ARGUMENT_NAME, types::compile_sum())],
+        fmm::types::void_type(),
+        |builder| -> Result<_, CompileError> {
+            let sum = fmm::build::variable(ARGUMENT_NAME, types::compile_sum());
+
+            // Values of sums without constructors never exist.
+            if !definition.constructors().is_empty() {
+                switch::compile(
+                    &builder,
+                    &builder.deconstruct_record(sum.clone(), SUM_TAG_ELEMENT_INDEX)?,
+                    0..definition.constructors().len(),
+                    &|builder, tag| {
+                        let constructor = &definition.constructors()[tag];
+
+                        compile_payload_rc(
+                            builder,
+                            &crate::sum::compile_payload(builder, &sum, constructor, types)?,
+                            &constructor.clone().into(),
+                            types,
+                        )?;
+
+                        Ok(builder.branch(fmm::ir::void_value()))
+                    },
+                )?;
+            }
+
+            Ok(builder.return_(fmm::ir::void_value()))
+        },
+        reference_count_function_definition_options(),
+    )?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use super::{super::error::CompileError, expression};
+use super::{super::error::CompileError, expression, pointer};
 use crate::types;
 use std::collections::HashMap;
 
@@ -17,12 +17,14 @@ pub fn compile_variant_clone_function(
         |builder| -> Result<_, CompileError> {
             let payload = fmm::build::variable("_payload", types::compile_variant_payload());
 
-            expression::clone_expression(
-                &builder,
-                &crate::variant::compile_unboxed_payload(&builder, &payload, type_, types)?,
-                type_,
-                types,
-            )?;
+            let payload =
+                crate::variant::compile_unboxed_payload(&builder, &payload, type_, types)?;
+
+            if crate::variant::is_payload_boxed(type_) {
+                pointer::clone_pointer(&builder, &payload)?;
+            } else {
+                expression::clone_expression(&builder, &payload, type_, types)?;
+            }
 
             Ok(builder.return_(fmm::ir::void_value()))
         },
@@ -45,12 +47,14 @@ pub fn compile_variant_drop_function(
         |builder| -> Result<_, CompileError> {
             let payload = fmm::build::variable("_payload", types::compile_variant_payload());
 
-            expression::drop_expression(
-                &builder,
-                &crate::variant::compile_unboxed_payload(&builder, &payload, type_, types)?,
-                type_,
-                types,
-            )?;
+            let payload =
+                crate::variant::compile_unboxed_payload(&builder, &payload, type_, types)?;
+
+            if crate::variant::is_payload_boxed(type_) {
+                drop_boxed_payload(&builder, &payload, type_, types)?;
+            } else {
+                expression::drop_expression(&builder, &payload, type_, types)?;
+            }
 
             Ok(builder.return_(fmm::ir::void_value()))
         },
@@ -58,6 +62,17 @@ pub fn compile_variant_drop_function(
     )
 }
 
+pub fn drop_boxed_payload(
+    builder: &fmm::build::InstructionBuilder,
+    pointer: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<(), CompileError> {
+    pointer::drop_pointer(builder, pointer, |builder| {
+        expression::drop_expression(builder, &builder.load(pointer.clone())?, type_, types)
+    })
+}
+
 fn function_definition_options() -> fmm::ir::FunctionDefinitionOptions {
     fmm::ir::FunctionDefinitionOptions::new()
         .set_calling_convention(fmm::types::CallingConvention::Target)
@@ -0,0 +1,43 @@
+use crate::{switch, types, variant, CompileError};
+use std::collections::HashMap;
+
+pub const SUM_TAG_ELEMENT_INDEX: usize = 0;
+pub const SUM_PAYLOAD_ELEMENT_INDEX: usize = 1;
+
+pub fn compile(
+    builder: &fmm::build::InstructionBuilder,
+    payload: &fmm::build::TypedExpression,
+    type_: &eir::types::Sum,
+    constructor: &eir::types::Record,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(fmm::build::record(vec![
+        switch::compile_tag(get_tag(type_, constructor, sums)).into(),
+        variant::compile_union_bit_cast(builder, types::compile_sum_payload(), payload.clone())?,
+    ])
+    .into())
+}
+
+pub fn compile_payload(
+    builder: &fmm::build::InstructionBuilder,
+    sum: &fmm::build::TypedExpression,
+    constructor: &eir::types::Record,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(variant::compile_union_bit_cast(
+        builder,
+        types::compile_record(&constructor.clone().into(), types),
+        builder.deconstruct_record(sum.clone(), SUM_PAYLOAD_ELEMENT_INDEX)?,
+    )?)
+}
+
+fn get_tag(
+    type_: &eir::types::Sum,
+    constructor: &eir::types::Record,
+    sums: &HashMap<String, Vec<eir::types::Record>>,
+) -> usize {
+    sums[type_.name()]
+        .iter()
+        .position(|record| record == constructor)
+        .unwrap()
+}
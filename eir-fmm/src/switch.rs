@@ -0,0 +1,151 @@
+use crate::CompileError;
+use std::ops::Range;
+
+// Dispatches on dense integer tags by binary search as F-- has no switch
+// instruction.
+pub fn compile(
+    builder: &fmm::build::InstructionBuilder,
+    tag: &fmm::build::TypedExpression,
+    tags: Range<usize>,
+    compile_branch: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        usize,
+    ) -> Result<fmm::ir::Block, CompileError>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let middle = tags.start + (tags.len() + 1) / 2;
+
+    builder.if_(
+        fmm::build::comparison_operation(
+            fmm::ir::ComparisonOperator::LessThan(false),
+            tag.clone(),
+            compile_tag(middle),
+        )?,
+        |builder| compile_block(&builder, tag, tags.start..middle, compile_branch),
+        |builder| compile_block(&builder, tag, middle..tags.end, compile_branch),
+    )
+}
+
+pub fn compile_tag(tag: usize) -> fmm::ir::Primitive {
+    fmm::ir::Primitive::Integer32(tag as u32)
+}
+
+fn compile_block(
+    builder: &fmm::build::InstructionBuilder,
+    tag: &fmm::build::TypedExpression,
+    tags: Range<usize>,
+    compile_branch: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        usize,
+    ) -> Result<fmm::ir::Block, CompileError>,
+) -> Result<fmm::ir::Block, CompileError> {
+    Ok(match tags.len() {
+        0 => builder.unreachable(),
+        1 => compile_branch(builder, tags.start)?,
+        _ => builder.branch(compile(builder, tag, tags, compile_branch)?),
+    })
+}
+
+// Searches indices of sorted keys by binary search. Indices of keys equal to
+// each other are left to leaves.
+pub fn search<K: Copy + PartialOrd>(
+    builder: &fmm::build::InstructionBuilder,
+    keys: &[(K, usize)],
+    compile_less_than: &impl Fn(K) -> Result<fmm::build::TypedExpression, CompileError>,
+    compile_leaf: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        &[usize],
+    ) -> Result<fmm::build::TypedExpression, CompileError>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    match keys {
+        [(first, _), ..] if keys.iter().any(|(key, _)| key != first) => {
+            let pivot = keys[keys.len() / 2].0;
+            let middle = match keys.partition_point(|(key, _)| *key < pivot) {
+                0 => keys.partition_point(|(key, _)| *key <= pivot),
+                middle => middle,
+            };
+
+            builder.if_(
+                compile_less_than(keys[middle].0)?,
+                |builder| {
+                    Ok(builder.branch(search(
+                        &builder,
+                        &keys[..middle],
+                        compile_less_than,
+                        compile_leaf,
+                    )?))
+                },
+                |builder| {
+                    Ok(builder.branch(search(
+                        &builder,
+                        &keys[middle..],
+                        compile_less_than,
+                        compile_leaf,
+                    )?))
+                },
+            )
+        }
+        _ => compile_leaf(
+            builder,
+            &keys.iter().map(|(_, index)| *index).collect::<Vec<_>>(),
+        ),
+    }
+}
+
+// Tests conditions of indices in order and falls back to a default index.
+pub fn chain(
+    builder: &fmm::build::InstructionBuilder,
+    indices: &[usize],
+    compile_condition: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        usize,
+    ) -> Result<fmm::build::TypedExpression, CompileError>,
+    default_index: usize,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    match indices {
+        [] => Ok(compile_tag(default_index).into()),
+        [index, ..] => builder.if_(
+            compile_condition(builder, *index)?,
+            |builder| Ok(builder.branch(compile_tag(*index))),
+            |builder| {
+                Ok(builder.branch(chain(
+                    &builder,
+                    &indices[1..],
+                    compile_condition,
+                    default_index,
+                )?))
+            },
+        ),
+    }
+}
+
+// Tests conditions of tags in order and compiles branches of the first tags
+// whose conditions hold. The last tag needs no condition.
+pub fn compile_chain(
+    builder: &fmm::build::InstructionBuilder,
+    tags: Range<usize>,
+    compile_condition: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        usize,
+    ) -> Result<fmm::build::TypedExpression, CompileError>,
+    compile_branch: &impl Fn(
+        &fmm::build::InstructionBuilder,
+        usize,
+    ) -> Result<fmm::ir::Block, CompileError>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    builder.if_(
+        compile_condition(builder, tags.start)?,
+        |builder| compile_branch(&builder, tags.start),
+        |builder| {
+            if tags.len() <= 2 {
+                compile_branch(&builder, tags.start + 1)
+            } else {
+                Ok(builder.branch(compile_chain(
+                    &builder,
+                    tags.start + 1..tags.end,
+                    compile_condition,
+                    compile_branch,
+                )?))
+            }
+        },
+    )
+}
@@ -0,0 +1,162 @@
+use crate::{configuration::Configuration, error::CompileError, types};
+use eir::ir::Visitor;
+
+// Runtime functions in configurations are wrapped by internal functions of
+// fixed names so that entry functions do not depend on configurations.
+const WAIT_FUNCTION_NAME: &str = "_eir_thunk_wait";
+const THREAD_ID_FUNCTION_NAME: &str = "_eir_thunk_thread_id";
+const REENTRANCE_ERROR_FUNCTION_NAME: &str = "_eir_thunk_reentrance_error";
+
+// Thread IDs of zero mean that thunks are not owned by any threads.
+pub const NO_OWNER: i64 = 0;
+
+// Runtime functions are compiled only for modules with thunks so that the
+// others do not need to link them.
+pub fn compile_runtime_functions(
+    module_builder: &fmm::build::ModuleBuilder,
+    module: &eir::ir::Module,
+    configuration: &Configuration,
+) -> Result<(), CompileError> {
+    if !has_thunks(module) {
+        return Ok(());
+    }
+
+    for (name, runtime_name, type_) in [
+        (
+            WAIT_FUNCTION_NAME,
+            configuration.wait_function_name(),
+            compile_wait_function_type(),
+        ),
+        (
+            THREAD_ID_FUNCTION_NAME,
+            configuration.thread_id_function_name(),
+            compile_thread_id_function_type(),
+        ),
+        (
+            REENTRANCE_ERROR_FUNCTION_NAME,
+            configuration.thunk_reentrance_error_function_name(),
+            compile_void_function_type(),
+        ),
+    ] {
+        let runtime_function = module_builder.declare_function(runtime_name, type_.clone());
+        let arguments = type_
+            .arguments()
+            .iter()
+            .enumerate()
+            .map(|(index, type_)| fmm::ir::Argument::new(format!("x{}", index), type_.clone()))
+            .collect::<Vec<_>>();
+
+        module_builder.define_function(
+            name,
+            arguments.clone(),
+            type_.result().clone(),
+            |builder| -> Result<_, CompileError> {
+                Ok(builder.return_(
+                    builder.call(
+                        runtime_function.clone(),
+                        arguments
+                            .iter()
+                            .map(|argument| {
+                                fmm::build::variable(argument.name(), argument.type_().clone())
+                            })
+                            .collect(),
+                    )?,
+                ))
+            },
+            fmm::ir::FunctionDefinitionOptions::new()
+                .set_calling_convention(fmm::types::CallingConvention::Target)
+                .set_linkage(fmm::ir::Linkage::Internal),
+        )?;
+    }
+
+    Ok(())
+}
+
+// Waits until a word at a pointer changes from a given value. The runtime
+// function loops instead of compiled programs as F-- has no loops.
+pub fn compile_wait(
+    builder: &fmm::build::InstructionBuilder,
+    pointer: impl Into<fmm::build::TypedExpression>,
+    value: impl Into<fmm::build::TypedExpression>,
+) -> Result<(), CompileError> {
+    builder.call(
+        fmm::build::variable(WAIT_FUNCTION_NAME, compile_wait_function_type()),
+        vec![
+            fmm::build::bit_cast(compile_word_pointer(), pointer).into(),
+            fmm::build::bit_cast(fmm::types::Primitive::PointerInteger, value).into(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn compile_thread_id(
+    builder: &fmm::build::InstructionBuilder,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    Ok(builder.call(
+        fmm::build::variable(THREAD_ID_FUNCTION_NAME, compile_thread_id_function_type()),
+        vec![],
+    )?)
+}
+
+pub fn compile_reentrance_error(
+    builder: &fmm::build::InstructionBuilder,
+) -> Result<(), CompileError> {
+    builder.call(
+        fmm::build::variable(REENTRANCE_ERROR_FUNCTION_NAME, compile_void_function_type()),
+        vec![],
+    )?;
+
+    Ok(())
+}
+
+fn has_thunks(module: &eir::ir::Module) -> bool {
+    let mut finder = ThunkFinder { found: false };
+
+    finder.visit_module(module);
+
+    finder.found
+}
+
+struct ThunkFinder {
+    found: bool,
+}
+
+impl Visitor for ThunkFinder {
+    fn visit_definition(&mut self, definition: &eir::ir::Definition) {
+        self.found |= definition.is_thunk();
+
+        eir::ir::walk_definition(self, definition);
+    }
+}
+
+fn compile_word_pointer() -> fmm::types::Pointer {
+    fmm::types::Pointer::new(fmm::types::Primitive::PointerInteger)
+}
+
+fn compile_wait_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![
+            compile_word_pointer().into(),
+            fmm::types::Primitive::PointerInteger.into(),
+        ],
+        fmm::types::void_type(),
+        fmm::types::CallingConvention::Target,
+    )
+}
+
+fn compile_void_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![],
+        fmm::types::void_type(),
+        fmm::types::CallingConvention::Target,
+    )
+}
+
+fn compile_thread_id_function_type() -> fmm::types::Function {
+    fmm::types::Function::new(
+        vec![],
+        types::compile_thread_id(),
+        fmm::types::CallingConvention::Target,
+    )
+}
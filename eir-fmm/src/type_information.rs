@@ -3,6 +3,10 @@ use std::collections::HashMap;
 
 pub const TYPE_INFORMATION_CLONE_FUNCTION_ELEMENT_INDEX: usize = 0;
 pub const TYPE_INFORMATION_DROP_FUNCTION_ELEMENT_INDEX: usize = 1;
+pub const TYPE_INFORMATION_ID_ELEMENT_INDEX: usize = 2;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
 
 pub fn compile(
     module_builder: &fmm::build::ModuleBuilder,
@@ -14,9 +18,20 @@ pub fn compile(
         fmm::build::record(vec![
             reference_count::compile_variant_clone_function(module_builder, type_, types)?,
             reference_count::compile_variant_drop_function(module_builder, type_, types)?,
+            fmm::ir::Primitive::Integer64(compile_id(type_)).into(),
         ]),
         fmm::ir::VariableDefinitionOptions::new().set_linkage(fmm::ir::Linkage::Weak),
     );
 
     Ok(())
 }
+
+// Type IDs are FNV-1a hashes of type names so that they are consistent
+// across modules sharing type information.
+pub fn compile_id(type_: &eir::types::Type) -> u64 {
+    types::compile_type_id(type_)
+        .bytes()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
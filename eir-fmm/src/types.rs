@@ -15,19 +15,44 @@ pub fn compile(
         eir::types::Type::Function(function) => {
             fmm::types::Pointer::new(compile_unsized_closure(function, types)).into()
         }
+        eir::types::Type::Integer(integer) => compile_integer(*integer).into(),
+        eir::types::Type::Lazy(lazy) => fmm::types::Pointer::new(compile_unsized_closure(
+            &get_lazy_function_type(lazy),
+            types,
+        ))
+        .into(),
         eir::types::Type::Number => fmm::types::Primitive::Float64.into(),
-        eir::types::Type::Record(record) => compile_record(record, types),
+        eir::types::Type::Record(_) | eir::types::Type::Tuple(_) => compile_record(type_, types),
         eir::types::Type::ByteString => compile_string().into(),
+        eir::types::Type::Sum(_) => compile_sum().into(),
         eir::types::Type::Variant => compile_variant().into(),
+        eir::types::Type::Variable(_) => unreachable!("type variables must be monomorphized"),
+    }
+}
+
+// Lazy values are thunks which take empty tuples.
+pub fn get_lazy_function_type(lazy: &eir::types::Lazy) -> eir::types::Function {
+    eir::types::Function::new(eir::types::Tuple::new(vec![]), lazy.type_().clone())
+}
+
+pub fn compile_integer(integer: eir::types::Integer) -> fmm::types::Primitive {
+    match integer.bits() {
+        8 => fmm::types::Primitive::Integer8,
+        32 => fmm::types::Primitive::Integer32,
+        _ => fmm::types::Primitive::Integer64,
     }
 }
 
 pub fn compile_string() -> fmm::types::Pointer {
-    fmm::types::Pointer::new(fmm::types::Record::new(vec![
+    fmm::types::Pointer::new(compile_string_body())
+}
+
+pub fn compile_string_body() -> fmm::types::Record {
+    fmm::types::Record::new(vec![
         fmm::types::Primitive::PointerInteger.into(),
         // The first byte of a string
         fmm::types::Primitive::Integer8.into(),
-    ]))
+    ])
 }
 
 pub fn compile_variant() -> fmm::types::Record {
@@ -53,6 +78,8 @@ pub fn compile_variant_tag() -> fmm::types::Pointer {
             fmm::types::CallingConvention::Target,
         )
         .into(),
+        // type ID
+        fmm::types::Primitive::Integer64.into(),
     ]))
 }
 
@@ -60,42 +87,68 @@ pub fn compile_variant_payload() -> fmm::types::Primitive {
     fmm::types::Primitive::Integer64
 }
 
+pub fn compile_sum() -> fmm::types::Record {
+    fmm::types::Record::new(vec![compile_sum_tag().into(), compile_sum_payload().into()])
+}
+
+// Tags of sums are indices of their constructors.
+pub fn compile_sum_tag() -> fmm::types::Primitive {
+    fmm::types::Primitive::Integer32
+}
+
+// Constructors of sums are records which are either pointers or empty.
+pub fn compile_sum_payload() -> fmm::types::Primitive {
+    fmm::types::Primitive::Integer64
+}
+
 pub fn compile_type_id(type_: &eir::types::Type) -> String {
     format!("{:?}", type_)
 }
 
+// Records are either named records or anonymous tuples whose layouts are
+// generated from their element types.
 pub fn compile_record(
-    record: &eir::types::Record,
+    type_: &eir::types::Type,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> fmm::types::Type {
-    if is_record_boxed(record, types) {
+    if is_record_boxed(type_, types) {
         fmm::types::Pointer::new(fmm::types::Record::new(vec![])).into()
     } else {
-        compile_unboxed_record(record, types).into()
+        compile_unboxed_record(type_, types).into()
     }
 }
 
 // TODO Unbox small non-recursive records.
 pub fn is_record_boxed(
-    record: &eir::types::Record,
+    type_: &eir::types::Type,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> bool {
-    !types[record.name()].elements().is_empty()
+    !get_record_elements(type_, types).is_empty()
 }
 
 pub fn compile_unboxed_record(
-    record: &eir::types::Record,
+    type_: &eir::types::Type,
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> fmm::types::Record {
     fmm::types::Record::new(
-        types[record.name()]
-            .elements()
+        get_record_elements(type_, types)
             .iter()
             .map(|type_| compile(type_, types))
             .collect(),
     )
 }
 
+pub fn get_record_elements<'a>(
+    type_: &'a eir::types::Type,
+    types: &'a HashMap<String, eir::types::RecordBody>,
+) -> &'a [eir::types::Type] {
+    match type_ {
+        eir::types::Type::Record(record) => types[record.name()].elements(),
+        eir::types::Type::Tuple(tuple) => tuple.elements(),
+        _ => unreachable!("record type expected"),
+    }
+}
+
 pub fn compile_sized_closure(
     definition: &eir::ir::Definition,
     types: &HashMap<String, eir::types::RecordBody>,
@@ -111,16 +164,37 @@ pub fn compile_closure_payload(
     types: &HashMap<String, eir::types::RecordBody>,
 ) -> fmm::types::Type {
     if definition.is_thunk() {
-        fmm::types::Union::new(vec![
-            compile_environment(definition, types).into(),
-            compile(definition.result_type(), types),
-        ])
-        .into()
+        compile_thunk_payload(definition, types).into()
     } else {
         compile_environment(definition, types).into()
     }
 }
 
+// Thunk payloads have IDs of threads evaluating them to detect reentrance.
+pub fn compile_thunk_payload(
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> fmm::types::Record {
+    fmm::types::Record::new(vec![
+        compile_thunk_content(definition, types).into(),
+        compile_thread_id().into(),
+    ])
+}
+
+pub fn compile_thunk_content(
+    definition: &eir::ir::Definition,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> fmm::types::Union {
+    fmm::types::Union::new(vec![
+        compile_environment(definition, types).into(),
+        compile(definition.result_type(), types),
+    ])
+}
+
+pub fn compile_thread_id() -> fmm::types::Primitive {
+    fmm::types::Primitive::PointerInteger
+}
+
 pub fn compile_unsized_closure(
     function: &eir::types::Function,
     types: &HashMap<String, eir::types::RecordBody>,
@@ -1,4 +1,4 @@
-use crate::{types, CompileError};
+use crate::{reference_count, types, CompileError};
 use std::collections::HashMap;
 
 pub const VARIANT_TAG_ELEMENT_INDEX: usize = 0;
@@ -8,14 +8,31 @@ pub fn compile_tag(type_: &eir::types::Type) -> fmm::build::TypedExpression {
     fmm::build::variable(types::compile_type_id(type_), types::compile_variant_tag())
 }
 
+// Variants and sums in variants are boxed on heap as they do not fit in
+// payloads.
+pub fn is_payload_boxed(type_: &eir::types::Type) -> bool {
+    matches!(type_, eir::types::Type::Sum(_) | eir::types::Type::Variant)
+}
+
 pub fn compile_boxed_payload(
     builder: &fmm::build::InstructionBuilder,
     payload: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
 ) -> Result<fmm::build::TypedExpression, CompileError> {
+    let payload = if is_payload_boxed(type_) {
+        let pointer = reference_count::allocate_heap(builder, payload.type_().clone())?;
+
+        builder.store(payload.clone(), pointer.clone());
+
+        pointer
+    } else {
+        payload.clone()
+    };
+
     Ok(compile_union_bit_cast(
         builder,
         types::compile_variant_payload(),
-        payload.clone(),
+        payload,
     )?)
 }
 
@@ -27,12 +44,44 @@ pub fn compile_unboxed_payload(
 ) -> Result<fmm::build::TypedExpression, CompileError> {
     Ok(compile_union_bit_cast(
         builder,
-        types::compile(type_, types),
+        compile_payload_type(type_, types),
         payload.clone(),
     )?)
 }
 
-fn compile_union_bit_cast(
+// Takes payloads out of variants owned by callers.
+pub fn compile_payload(
+    builder: &fmm::build::InstructionBuilder,
+    payload: &fmm::build::TypedExpression,
+    type_: &eir::types::Type,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> Result<fmm::build::TypedExpression, CompileError> {
+    let payload = compile_unboxed_payload(builder, payload, type_, types)?;
+
+    Ok(if is_payload_boxed(type_) {
+        let value = builder.load(payload.clone())?;
+
+        reference_count::clone_expression(builder, &value, type_, types)?;
+        reference_count::drop_boxed_payload(builder, &payload, type_, types)?;
+
+        value
+    } else {
+        payload
+    })
+}
+
+fn compile_payload_type(
+    type_: &eir::types::Type,
+    types: &HashMap<String, eir::types::RecordBody>,
+) -> fmm::types::Type {
+    if is_payload_boxed(type_) {
+        fmm::types::Pointer::new(types::compile(type_, types)).into()
+    } else {
+        types::compile(type_, types)
+    }
+}
+
+pub fn compile_union_bit_cast(
     builder: &fmm::build::InstructionBuilder,
     to_type: impl Into<fmm::types::Type>,
     argument: impl Into<fmm::build::TypedExpression>,
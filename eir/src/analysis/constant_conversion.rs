@@ -0,0 +1,319 @@
+use super::free_variables::find_free_variables;
+use crate::{ir::*, types};
+use std::collections::HashMap;
+
+// Converts constants into thunks which take empty tuples, and references to
+// them into applications of the thunks so that they are evaluated only once
+// lazily. This needs to run before the other transformations as they expect
+// every definition to have arguments.
+pub fn convert_constants(module: &Module) -> Module {
+    ConstantConverter {
+        constants: module
+            .definitions()
+            .iter()
+            .filter(|definition| definition.is_constant())
+            .map(|definition| (definition.name().to_owned(), definition.type_().clone()))
+            .collect(),
+    }
+    .fold_module(module)
+}
+
+struct ConstantConverter {
+    constants: HashMap<String, types::Function>,
+}
+
+impl ConstantConverter {
+    // Folds with constants shadowed by the given names.
+    fn shadow<'a, T>(
+        &mut self,
+        names: impl IntoIterator<Item = &'a str>,
+        fold: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let constants = self.constants.clone();
+
+        for name in names {
+            self.constants.remove(name);
+        }
+
+        let value = fold(self);
+        self.constants = constants;
+
+        value
+    }
+
+    fn convert_constant(&mut self, definition: &Definition) -> Definition {
+        let free_variables = find_free_variables(definition.body());
+        let mut name = format!("{}'", definition.name());
+
+        while free_variables.contains(&name) {
+            name.push('\'');
+        }
+
+        Definition::with_options(
+            definition.name(),
+            definition.type_parameters().to_vec(),
+            definition.environment().to_vec(),
+            vec![Argument::new(name, types::Tuple::new(vec![]))],
+            self.fold_expression(definition.body()),
+            definition.result_type().clone(),
+            true,
+        )
+    }
+
+    fn convert_alternatives(&mut self, alternatives: &[Alternative]) -> Vec<Alternative> {
+        alternatives
+            .iter()
+            .map(|alternative| {
+                Alternative::new(
+                    alternative.type_().clone(),
+                    alternative.name(),
+                    self.shadow([alternative.name()], |converter| {
+                        converter.fold_expression(alternative.expression())
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    fn convert_default_alternative(
+        &mut self,
+        alternative: Option<&DefaultAlternative>,
+    ) -> Option<DefaultAlternative> {
+        alternative.map(|alternative| {
+            DefaultAlternative::new(
+                alternative.name(),
+                self.shadow([alternative.name()], |converter| {
+                    converter.fold_expression(alternative.expression())
+                }),
+            )
+        })
+    }
+}
+
+impl Fold for ConstantConverter {
+    fn fold_definition(&mut self, definition: &Definition) -> Definition {
+        if definition.is_constant() {
+            self.convert_constant(definition)
+        } else {
+            self.shadow(
+                definition
+                    .environment()
+                    .iter()
+                    .chain(definition.arguments())
+                    .map(|argument| argument.name()),
+                |converter| fold_definition_children(converter, definition),
+            )
+        }
+    }
+
+    fn fold_expression(&mut self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::Case(case) => Case::new(
+                self.fold_expression(case.argument()),
+                self.convert_alternatives(case.alternatives()),
+                self.convert_default_alternative(case.default_alternative()),
+            )
+            .into(),
+            Expression::Let(let_) => Let::new(
+                let_.name(),
+                let_.type_().clone(),
+                self.fold_expression(let_.bound_expression()),
+                self.shadow([let_.name()], |converter| {
+                    converter.fold_expression(let_.expression())
+                }),
+            )
+            .into(),
+            Expression::LetRecord(let_) => LetRecord::new(
+                let_.type_().clone(),
+                let_.elements().to_vec(),
+                self.fold_expression(let_.bound_expression()),
+                self.shadow(
+                    let_.elements().iter().map(|element| element.name()),
+                    |converter| converter.fold_expression(let_.expression()),
+                ),
+            )
+            .into(),
+            Expression::LetRecursive(let_) => self.shadow(
+                let_.definitions()
+                    .iter()
+                    .map(|definition| definition.name()),
+                |converter| fold_expression_children(converter, expression),
+            ),
+            Expression::LiteralCase(case) => LiteralCase::new(
+                case.type_().clone(),
+                self.fold_expression(case.argument()),
+                case.alternatives()
+                    .iter()
+                    .map(|alternative| {
+                        LiteralAlternative::new(
+                            alternative.literal().clone(),
+                            self.fold_expression(alternative.expression()),
+                        )
+                    })
+                    .collect(),
+                self.convert_default_alternative(case.default_alternative()),
+            )
+            .into(),
+            Expression::SumCase(case) => SumCase::new(
+                case.type_().clone(),
+                self.fold_expression(case.argument()),
+                self.convert_alternatives(case.alternatives()),
+                self.convert_default_alternative(case.default_alternative()),
+            )
+            .into(),
+            Expression::TryOperation(operation) => TryOperation::new(
+                self.fold_expression(operation.operand()),
+                operation.name(),
+                operation.type_().clone(),
+                self.shadow([operation.name()], |converter| {
+                    converter.fold_expression(operation.then())
+                }),
+            )
+            .into(),
+            Expression::Variable(variable) => {
+                if let Some(type_) = self.constants.get(variable.name()) {
+                    FunctionApplication::new(
+                        type_.clone(),
+                        variable.clone(),
+                        Record::new(types::Tuple::new(vec![]), vec![]),
+                    )
+                    .into()
+                } else {
+                    variable.clone().into()
+                }
+            }
+            _ => fold_expression_children(self, expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type;
+    use pretty_assertions::assert_eq;
+
+    fn create_constant_type() -> types::Function {
+        types::Function::new(types::Tuple::new(vec![]), Type::Number)
+    }
+
+    fn create_constant_application() -> FunctionApplication {
+        FunctionApplication::new(
+            create_constant_type(),
+            Variable::new("c"),
+            Record::new(types::Tuple::new(vec![]), vec![]),
+        )
+    }
+
+    fn convert_module(definitions: Vec<Definition>) -> Vec<Definition> {
+        convert_constants(&Module::new(vec![], vec![], vec![], vec![], definitions))
+            .definitions()
+            .to_vec()
+    }
+
+    #[test]
+    fn convert_constant() {
+        assert_eq!(
+            convert_module(vec![Definition::new("c", vec![], 42.0, Type::Number)]),
+            vec![Definition::thunk(
+                "c",
+                vec![Argument::new("c'", types::Tuple::new(vec![]))],
+                42.0,
+                Type::Number
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_constant_with_free_variable() {
+        let body = Expression::from(Variable::new("c'"));
+
+        assert_eq!(
+            convert_module(vec![Definition::new(
+                "c",
+                vec![],
+                body.clone(),
+                Type::Number
+            )]),
+            vec![Definition::thunk(
+                "c",
+                vec![Argument::new("c''", types::Tuple::new(vec![]))],
+                body,
+                Type::Number
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_reference_to_constant() {
+        assert_eq!(
+            convert_module(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Variable::new("c"),
+                    Type::Number
+                )
+            ])[1],
+            Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                create_constant_application(),
+                Type::Number
+            )
+        );
+    }
+
+    #[test]
+    fn convert_reference_to_constant_in_constant() {
+        assert_eq!(
+            convert_module(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                Definition::new("d", vec![], Variable::new("c"), Type::Number)
+            ])[1],
+            Definition::thunk(
+                "d",
+                vec![Argument::new("d'", types::Tuple::new(vec![]))],
+                create_constant_application(),
+                Type::Number
+            )
+        );
+    }
+
+    #[test]
+    fn do_not_convert_argument_shadowing_constant() {
+        let definition = Definition::new(
+            "f",
+            vec![Argument::new("c", Type::Number)],
+            Variable::new("c"),
+            Type::Number,
+        );
+
+        assert_eq!(
+            convert_module(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                definition.clone()
+            ])[1],
+            definition
+        );
+    }
+
+    #[test]
+    fn do_not_convert_let_variable_shadowing_constant() {
+        let definition = Definition::new(
+            "f",
+            vec![Argument::new("x", Type::Number)],
+            Let::new("c", Type::Number, Variable::new("x"), Variable::new("c")),
+            Type::Number,
+        );
+
+        assert_eq!(
+            convert_module(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                definition.clone()
+            ])[1],
+            definition
+        );
+    }
+}
@@ -1,314 +1,328 @@
 use super::free_variables::find_free_variables;
 use crate::{ir::*, types::Type};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub fn infer_environment(module: &Module) -> Module {
-    Module::new(
-        module.type_definitions().to_vec(),
-        module.foreign_declarations().to_vec(),
-        module.foreign_definitions().to_vec(),
-        module.declarations().to_vec(),
-        module
-            .definitions()
-            .iter()
-            .map(infer_in_global_definition)
-            .collect(),
-    )
+    EnvironmentInferrer {
+        variables: Default::default(),
+    }
+    .fold_module(module)
 }
 
-fn infer_in_global_definition(definition: &Definition) -> Definition {
-    Definition::with_options(
-        definition.name(),
-        vec![],
-        definition.arguments().to_vec(),
-        infer_in_expression(
-            definition.body(),
-            &definition
-                .arguments()
-                .iter()
-                .map(|argument| (argument.name().into(), argument.type_().clone()))
-                .collect(),
-        ),
-        definition.result_type().clone(),
-        definition.is_thunk(),
-    )
+struct EnvironmentInferrer {
+    variables: HashMap<String, Type>,
 }
 
-fn infer_in_local_definition(
-    definition: &Definition,
-    variables: &HashMap<String, Type>,
-) -> Definition {
-    Definition::with_options(
-        definition.name(),
-        find_free_variables(definition.body())
+impl EnvironmentInferrer {
+    // Folds with the given variables in scope.
+    fn bind<T>(
+        &mut self,
+        variables: impl IntoIterator<Item = (String, Type)>,
+        fold: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let outer_variables = self.variables.clone();
+
+        self.variables.extend(variables);
+        let value = fold(self);
+        self.variables = outer_variables;
+
+        value
+    }
+
+    // Definitions in a group share an environment so that each of them can
+    // create closures of the others.
+    fn infer_in_local_definitions(&mut self, definitions: &[Definition]) -> Vec<Definition> {
+        let names = definitions
             .iter()
+            .map(|definition| definition.name())
+            .collect::<HashSet<_>>();
+        let environment = definitions
+            .iter()
+            .flat_map(|definition| {
+                find_free_variables(definition.body())
+                    .into_iter()
+                    .filter(move |name| {
+                        definition
+                            .arguments()
+                            .iter()
+                            .all(|argument| argument.name() != name)
+                    })
+            })
+            .filter(|name| !names.contains(name.as_str()))
+            .collect::<HashSet<_>>()
+            .into_iter()
             .filter_map(|name| {
-                variables
-                    .get(name)
+                self.variables
+                    .get(&name)
                     .map(|type_| Argument::new(name, type_.clone()))
             })
-            .collect(),
-        definition.arguments().to_vec(),
-        infer_in_expression(
-            definition.body(),
-            &variables
-                .clone()
-                .drain()
-                .chain(vec![(
-                    definition.name().into(),
-                    definition.type_().clone().into(),
-                )])
-                .chain(
-                    definition
-                        .arguments()
-                        .iter()
-                        .map(|argument| (argument.name().into(), argument.type_().clone())),
-                )
-                .collect(),
-        ),
-        definition.result_type().clone(),
-        definition.is_thunk(),
-    )
-}
+            .collect::<Vec<_>>();
 
-fn infer_in_expression(expression: &Expression, variables: &HashMap<String, Type>) -> Expression {
-    match expression {
-        Expression::ArithmeticOperation(operation) => {
-            infer_in_arithmetic_operation(operation, variables).into()
-        }
-        Expression::Case(case) => infer_in_case(case, variables).into(),
-        Expression::CloneVariables(clone) => infer_in_clone_variables(clone, variables).into(),
-        Expression::ComparisonOperation(operation) => {
-            infer_in_comparison_operation(operation, variables).into()
-        }
-        Expression::DropVariables(drop) => infer_in_drop_variables(drop, variables).into(),
-        Expression::FunctionApplication(application) => {
-            infer_in_function_application(application, variables).into()
-        }
-        Expression::If(if_) => infer_in_if(if_, variables).into(),
-        Expression::Let(let_) => infer_in_let(let_, variables).into(),
-        Expression::LetRecursive(let_) => infer_in_let_recursive(let_, variables).into(),
-        Expression::Record(record) => infer_in_record(record, variables).into(),
-        Expression::RecordElement(element) => infer_in_record_element(element, variables).into(),
-        Expression::Variant(variant) => infer_in_variant(variant, variables).into(),
-        Expression::Boolean(_)
-        | Expression::ByteString(_)
-        | Expression::Number(_)
-        | Expression::Variable(_) => expression.clone(),
+        self.bind(
+            definitions
+                .iter()
+                .map(|definition| (definition.name().into(), definition.type_().clone().into())),
+            |inferrer| {
+                definitions
+                    .iter()
+                    .map(|definition| {
+                        Definition::with_options(
+                            definition.name(),
+                            definition.type_parameters().to_vec(),
+                            environment.clone(),
+                            definition.arguments().to_vec(),
+                            inferrer.bind(
+                                definition.arguments().iter().map(|argument| {
+                                    (argument.name().into(), argument.type_().clone())
+                                }),
+                                |inferrer| inferrer.fold_expression(definition.body()),
+                            ),
+                            definition.result_type().clone(),
+                            definition.is_thunk(),
+                        )
+                    })
+                    .collect()
+            },
+        )
     }
-}
 
-fn infer_in_arithmetic_operation(
-    operation: &ArithmeticOperation,
-    variables: &HashMap<String, Type>,
-) -> ArithmeticOperation {
-    ArithmeticOperation::new(
-        operation.operator(),
-        infer_in_expression(operation.lhs(), variables),
-        infer_in_expression(operation.rhs(), variables),
-    )
-}
-
-fn infer_in_if(if_: &If, variables: &HashMap<String, Type>) -> If {
-    If::new(
-        infer_in_expression(if_.condition(), variables),
-        infer_in_expression(if_.then(), variables),
-        infer_in_expression(if_.else_(), variables),
-    )
-}
-
-fn infer_in_case(case: &Case, variables: &HashMap<String, Type>) -> Case {
-    Case::new(
-        infer_in_expression(case.argument(), variables),
-        case.alternatives()
+    fn infer_in_alternatives(&mut self, alternatives: &[Alternative]) -> Vec<Alternative> {
+        alternatives
             .iter()
-            .map(|alternative| infer_in_alternative(alternative, variables))
-            .collect(),
-        case.default_alternative()
-            .map(|alternative| infer_in_default_alternative(alternative, variables)),
-    )
-}
-
-fn infer_in_alternative(
-    alternative: &Alternative,
-    variables: &HashMap<String, Type>,
-) -> Alternative {
-    let mut variables = variables.clone();
-
-    variables.insert(alternative.name().into(), alternative.type_().clone());
-
-    Alternative::new(
-        alternative.type_().clone(),
-        alternative.name(),
-        infer_in_expression(alternative.expression(), &variables),
-    )
-}
-
-fn infer_in_default_alternative(
-    alternative: &DefaultAlternative,
-    variables: &HashMap<String, Type>,
-) -> DefaultAlternative {
-    let mut variables = variables.clone();
-
-    variables.insert(alternative.name().into(), Type::Variant);
-
-    DefaultAlternative::new(
-        alternative.name(),
-        infer_in_expression(alternative.expression(), &variables),
-    )
-}
+            .map(|alternative| {
+                Alternative::new(
+                    alternative.type_().clone(),
+                    alternative.name(),
+                    self.bind(
+                        vec![(alternative.name().into(), alternative.type_().clone())],
+                        |inferrer| inferrer.fold_expression(alternative.expression()),
+                    ),
+                )
+            })
+            .collect()
+    }
 
-fn infer_in_clone_variables(
-    clone: &CloneVariables,
-    variables: &HashMap<String, Type>,
-) -> CloneVariables {
-    CloneVariables::new(
-        clone.variables().clone(),
-        infer_in_expression(clone.expression(), variables),
-    )
+    fn infer_in_default_alternative(
+        &mut self,
+        alternative: Option<&DefaultAlternative>,
+        type_: &Type,
+    ) -> Option<DefaultAlternative> {
+        alternative.map(|alternative| {
+            DefaultAlternative::new(
+                alternative.name(),
+                self.bind(
+                    vec![(alternative.name().into(), type_.clone())],
+                    |inferrer| inferrer.fold_expression(alternative.expression()),
+                ),
+            )
+        })
+    }
 }
 
-fn infer_in_comparison_operation(
-    operation: &ComparisonOperation,
-    variables: &HashMap<String, Type>,
-) -> ComparisonOperation {
-    ComparisonOperation::new(
-        operation.operator(),
-        infer_in_expression(operation.lhs(), variables),
-        infer_in_expression(operation.rhs(), variables),
-    )
-}
+impl Fold for EnvironmentInferrer {
+    fn fold_definition(&mut self, definition: &Definition) -> Definition {
+        Definition::with_options(
+            definition.name(),
+            definition.type_parameters().to_vec(),
+            vec![],
+            definition.arguments().to_vec(),
+            self.bind(
+                definition
+                    .arguments()
+                    .iter()
+                    .map(|argument| (argument.name().into(), argument.type_().clone())),
+                |inferrer| inferrer.fold_expression(definition.body()),
+            ),
+            definition.result_type().clone(),
+            definition.is_thunk(),
+        )
+    }
 
-fn infer_in_drop_variables(
-    drop: &DropVariables,
-    variables: &HashMap<String, Type>,
-) -> DropVariables {
-    DropVariables::new(
-        drop.variables().clone(),
-        infer_in_expression(drop.expression(), variables),
-    )
+    fn fold_expression(&mut self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::Case(case) => Case::new(
+                self.fold_expression(case.argument()),
+                self.infer_in_alternatives(case.alternatives()),
+                self.infer_in_default_alternative(case.default_alternative(), &Type::Variant),
+            )
+            .into(),
+            Expression::Lazy(lazy) => Lazy::with_definition(
+                self.infer_in_local_definitions(&[lazy.definition().clone()])
+                    .remove(0),
+            )
+            .into(),
+            Expression::Let(let_) => Let::new(
+                let_.name(),
+                let_.type_().clone(),
+                self.fold_expression(let_.bound_expression()),
+                self.bind(
+                    vec![(let_.name().into(), let_.type_().clone())],
+                    |inferrer| inferrer.fold_expression(let_.expression()),
+                ),
+            )
+            .into(),
+            Expression::LetRecord(let_) => LetRecord::new(
+                let_.type_().clone(),
+                let_.elements().to_vec(),
+                self.fold_expression(let_.bound_expression()),
+                self.bind(
+                    let_.elements()
+                        .iter()
+                        .map(|element| (element.name().into(), element.type_().clone())),
+                    |inferrer| inferrer.fold_expression(let_.expression()),
+                ),
+            )
+            .into(),
+            Expression::LetRecursive(let_) => LetRecursive::with_definitions(
+                self.infer_in_local_definitions(let_.definitions()),
+                self.bind(
+                    let_.definitions().iter().map(|definition| {
+                        (definition.name().into(), definition.type_().clone().into())
+                    }),
+                    |inferrer| inferrer.fold_expression(let_.expression()),
+                ),
+            )
+            .into(),
+            Expression::LiteralCase(case) => LiteralCase::new(
+                case.type_().clone(),
+                self.fold_expression(case.argument()),
+                case.alternatives()
+                    .iter()
+                    .map(|alternative| {
+                        LiteralAlternative::new(
+                            alternative.literal().clone(),
+                            self.fold_expression(alternative.expression()),
+                        )
+                    })
+                    .collect(),
+                self.infer_in_default_alternative(case.default_alternative(), case.type_()),
+            )
+            .into(),
+            Expression::SumCase(case) => SumCase::new(
+                case.type_().clone(),
+                self.fold_expression(case.argument()),
+                self.infer_in_alternatives(case.alternatives()),
+                self.infer_in_default_alternative(
+                    case.default_alternative(),
+                    &case.type_().clone().into(),
+                ),
+            )
+            .into(),
+            Expression::TryOperation(operation) => TryOperation::new(
+                self.fold_expression(operation.operand()),
+                operation.name(),
+                operation.type_().clone(),
+                self.bind(
+                    vec![(operation.name().into(), operation.type_().clone())],
+                    |inferrer| inferrer.fold_expression(operation.then()),
+                ),
+            )
+            .into(),
+            _ => fold_expression_children(self, expression),
+        }
+    }
 }
 
-fn infer_in_function_application(
-    application: &FunctionApplication,
-    variables: &HashMap<String, Type>,
-) -> FunctionApplication {
-    FunctionApplication::new(
-        application.type_().clone(),
-        infer_in_expression(application.function(), variables),
-        infer_in_expression(application.argument(), variables),
-    )
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types;
+    use pretty_assertions::assert_eq;
 
-fn infer_in_let(let_: &Let, variables: &HashMap<String, Type>) -> Let {
-    Let::new(
-        let_.name(),
-        let_.type_().clone(),
-        infer_in_expression(let_.bound_expression(), variables),
-        infer_in_expression(
-            let_.expression(),
-            &variables
-                .clone()
-                .drain()
-                .chain(vec![(let_.name().into(), let_.type_().clone())])
-                .collect(),
-        ),
-    )
-}
+    fn infer_in_expression(
+        expression: &Expression,
+        variables: &HashMap<String, Type>,
+    ) -> Expression {
+        EnvironmentInferrer {
+            variables: variables.clone(),
+        }
+        .fold_expression(expression)
+    }
 
-fn infer_in_let_recursive(let_: &LetRecursive, variables: &HashMap<String, Type>) -> LetRecursive {
-    LetRecursive::new(
-        infer_in_local_definition(let_.definition(), variables),
-        infer_in_expression(
-            let_.expression(),
-            &variables
-                .clone()
-                .drain()
-                .chain(vec![(
-                    let_.definition().name().into(),
-                    let_.definition().type_().clone().into(),
-                )])
-                .collect(),
-        ),
-    )
-}
+    fn infer_in_local_definitions(
+        definitions: &[Definition],
+        variables: &HashMap<String, Type>,
+    ) -> Vec<Definition> {
+        EnvironmentInferrer {
+            variables: variables.clone(),
+        }
+        .infer_in_local_definitions(definitions)
+    }
 
-fn infer_in_record(record: &Record, variables: &HashMap<String, Type>) -> Record {
-    Record::new(
-        record.type_().clone(),
-        record
-            .elements()
-            .iter()
-            .map(|element| infer_in_expression(element, variables))
-            .collect(),
-    )
-}
+    fn infer_in_let_recursive(
+        let_: &LetRecursive,
+        variables: &HashMap<String, Type>,
+    ) -> LetRecursive {
+        match infer_in_expression(&let_.clone().into(), variables) {
+            Expression::LetRecursive(let_) => let_,
+            _ => unreachable!(),
+        }
+    }
 
-fn infer_in_record_element(
-    element: &RecordElement,
-    variables: &HashMap<String, Type>,
-) -> RecordElement {
-    RecordElement::new(
-        element.type_().clone(),
-        element.index(),
-        infer_in_expression(element.record(), variables),
-    )
-}
+    fn infer_in_lazy(lazy: &Lazy, variables: &HashMap<String, Type>) -> Lazy {
+        match infer_in_expression(&lazy.clone().into(), variables) {
+            Expression::Lazy(lazy) => lazy,
+            _ => unreachable!(),
+        }
+    }
 
-fn infer_in_variant(variant: &Variant, variables: &HashMap<String, Type>) -> Variant {
-    Variant::new(
-        variant.type_().clone(),
-        infer_in_expression(variant.payload(), variables),
-    )
-}
+    #[test]
+    fn keep_type_arguments_and_tail_call_marks() {
+        let application = Expression::from(FunctionApplication::with_options(
+            types::Function::new(Type::Number, Type::Number),
+            Variable::new("f"),
+            vec![Type::Number],
+            42.0,
+            true,
+        ));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types;
-    use pretty_assertions::assert_eq;
+        assert_eq!(
+            infer_in_expression(&application, &Default::default()),
+            application
+        );
+    }
 
     #[test]
     fn infer_empty_environment() {
         assert_eq!(
-            infer_in_local_definition(
-                &Definition::new(
+            infer_in_local_definitions(
+                &[Definition::new(
                     "f",
                     vec![Argument::new("x", Type::Number)],
                     42.0,
                     Type::Number
-                ),
+                )],
                 &Default::default()
             ),
-            Definition::with_environment(
+            vec![Definition::with_environment(
                 "f",
                 vec![],
                 vec![Argument::new("x", Type::Number)],
                 42.0,
                 Type::Number
-            )
+            )]
         );
     }
 
     #[test]
     fn infer_environment() {
         assert_eq!(
-            infer_in_local_definition(
-                &Definition::new(
+            infer_in_local_definitions(
+                &[Definition::new(
                     "f",
                     vec![Argument::new("x", Type::Number)],
                     Variable::new("y"),
                     Type::Number
-                ),
+                )],
                 &vec![("y".into(), Type::Number)].drain(..).collect()
             ),
-            Definition::with_environment(
+            vec![Definition::with_environment(
                 "f",
                 vec![Argument::new("y", Type::Number)],
                 vec![Argument::new("x", Type::Number)],
                 Variable::new("y"),
                 Type::Number
-            )
+            )]
         );
     }
 
@@ -317,25 +331,25 @@ mod tests {
         let variables = vec![("y".into(), Type::Number)].drain(..).collect();
 
         assert_eq!(
-            infer_in_local_definition(
-                &infer_in_local_definition(
-                    &Definition::new(
+            infer_in_local_definitions(
+                &infer_in_local_definitions(
+                    &[Definition::new(
                         "f",
                         vec![Argument::new("x", Type::Number)],
                         Variable::new("y"),
                         Type::Number
-                    ),
+                    )],
                     &variables
                 ),
                 &variables
             ),
-            Definition::with_environment(
+            vec![Definition::with_environment(
                 "f",
                 vec![Argument::new("y", Type::Number)],
                 vec![Argument::new("x", Type::Number)],
                 Variable::new("y"),
                 Type::Number
-            )
+            )]
         );
     }
 
@@ -358,8 +372,8 @@ mod tests {
                 ),
                 &Default::default(),
             )
-            .definition(),
-            &Definition::with_environment(
+            .definitions(),
+            &[Definition::with_environment(
                 "f",
                 vec![],
                 vec![Argument::new("x", Type::Number)],
@@ -369,6 +383,69 @@ mod tests {
                     Variable::new("x")
                 ),
                 Type::Number
+            )]
+        );
+    }
+
+    #[test]
+    fn infer_shared_environment_for_mutually_recursive_definitions() {
+        let function_type = types::Function::new(Type::Number, Type::Number);
+
+        assert_eq!(
+            infer_in_let_recursive(
+                &LetRecursive::with_definitions(
+                    vec![
+                        Definition::new(
+                            "f",
+                            vec![Argument::new("x", Type::Number)],
+                            FunctionApplication::new(
+                                function_type.clone(),
+                                Variable::new("g"),
+                                Variable::new("y")
+                            ),
+                            Type::Number
+                        ),
+                        Definition::new(
+                            "g",
+                            vec![Argument::new("x", Type::Number)],
+                            FunctionApplication::new(
+                                function_type.clone(),
+                                Variable::new("f"),
+                                Variable::new("x")
+                            ),
+                            Type::Number
+                        ),
+                    ],
+                    Variable::new("f")
+                ),
+                &vec![("y".into(), Type::Number)].drain(..).collect(),
+            ),
+            LetRecursive::with_definitions(
+                vec![
+                    Definition::with_environment(
+                        "f",
+                        vec![Argument::new("y", Type::Number)],
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            function_type.clone(),
+                            Variable::new("g"),
+                            Variable::new("y")
+                        ),
+                        Type::Number
+                    ),
+                    Definition::with_environment(
+                        "g",
+                        vec![Argument::new("y", Type::Number)],
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            function_type,
+                            Variable::new("f"),
+                            Variable::new("x")
+                        ),
+                        Type::Number
+                    ),
+                ],
+                Variable::new("f")
             )
         );
     }
@@ -404,6 +481,7 @@ mod tests {
             &LetRecursive::new(
                 Definition::with_options(
                     "g",
+                    vec![],
                     vec![Argument::new(
                         "f",
                         types::Function::new(Type::Number, Type::Number)
@@ -422,4 +500,17 @@ mod tests {
             .into()
         );
     }
+
+    #[test]
+    fn infer_environment_of_lazy() {
+        assert_eq!(
+            infer_in_lazy(
+                &Lazy::new(Type::Number, Variable::new("y")),
+                &vec![("y".into(), Type::Number)].drain(..).collect()
+            )
+            .definition()
+            .environment(),
+            &[Argument::new("y", Type::Number)]
+        );
+    }
 }
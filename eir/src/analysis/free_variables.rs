@@ -2,89 +2,196 @@ use crate::ir::*;
 use std::collections::HashSet;
 
 pub fn find_free_variables(expression: &Expression) -> HashSet<String> {
-    find_in_expression(expression)
-}
+    let mut finder = FreeVariableFinder::default();
 
-fn find_in_expression(expression: &Expression) -> HashSet<String> {
-    match expression {
-        Expression::ArithmeticOperation(operation) => find_in_expression(operation.lhs())
-            .into_iter()
-            .chain(find_in_expression(operation.rhs()))
-            .collect(),
-        Expression::Case(case) => find_in_case(case),
-        Expression::CloneVariables(clone) => find_in_expression(clone.expression()),
-        Expression::ComparisonOperation(operation) => find_in_expression(operation.lhs())
-            .into_iter()
-            .chain(find_in_expression(operation.rhs()))
-            .collect(),
-        Expression::DropVariables(drop) => find_in_expression(drop.expression()),
-        Expression::FunctionApplication(application) => find_in_expression(application.function())
-            .into_iter()
-            .chain(find_in_expression(application.argument()))
-            .collect(),
-        Expression::If(if_) => find_in_expression(if_.condition())
-            .into_iter()
-            .chain(find_in_expression(if_.then()))
-            .chain(find_in_expression(if_.else_()))
-            .collect(),
-        Expression::LetRecursive(let_) => find_in_definition(let_.definition())
-            .into_iter()
-            .chain(find_in_expression(let_.expression()))
-            .filter(|variable| variable != let_.definition().name())
-            .collect(),
-        Expression::Let(let_) => find_in_expression(let_.bound_expression())
-            .into_iter()
-            .chain(
-                find_in_expression(let_.expression())
-                    .into_iter()
-                    .filter(|variable| variable != let_.name()),
-            )
-            .collect(),
-        Expression::Record(record) => record
-            .elements()
-            .iter()
-            .flat_map(find_in_expression)
-            .collect(),
-        Expression::RecordElement(element) => find_in_expression(element.record()),
-        Expression::Variable(variable) => vec![variable.name().into()].into_iter().collect(),
-        Expression::Variant(variant) => find_in_expression(variant.payload()),
-        Expression::Boolean(_) | Expression::ByteString(_) | Expression::Number(_) => {
-            HashSet::new()
-        }
-    }
+    finder.visit_expression(expression);
+
+    finder.variables
 }
 
-fn find_in_case(case: &Case) -> HashSet<String> {
-    find_in_expression(case.argument())
-        .into_iter()
-        .chain(case.alternatives().iter().flat_map(|alternative| {
-            find_in_expression(alternative.expression())
-                .into_iter()
-                .filter(|variable| variable != alternative.name())
-                .collect::<HashSet<_>>()
-        }))
-        .chain(
-            case.default_alternative()
-                .into_iter()
-                .flat_map(|alternative| {
-                    find_in_expression(alternative.expression())
-                        .into_iter()
-                        .filter(|variable| variable != alternative.name())
-                        .collect::<HashSet<_>>()
-                }),
-        )
+// Finds the other definitions in a recursive group which are referred to by a
+// definition in the group.
+pub fn find_sibling_references<'a>(
+    definition: &Definition,
+    definitions: &'a [Definition],
+) -> Vec<&'a Definition> {
+    let mut finder = FreeVariableFinder::default();
+
+    finder.visit_definition(definition);
+
+    definitions
+        .iter()
+        .filter(|sibling| {
+            sibling.name() != definition.name() && finder.variables.contains(sibling.name())
+        })
         .collect()
 }
 
-fn find_in_definition(definition: &Definition) -> HashSet<String> {
-    find_in_expression(definition.body())
-        .into_iter()
-        .filter(|variable| {
-            variable != definition.name()
-                && definition
+#[derive(Default)]
+struct FreeVariableFinder {
+    bound_variables: Vec<String>,
+    variables: HashSet<String>,
+}
+
+impl FreeVariableFinder {
+    // Visits with the given names bound to variables.
+    fn bind<'a>(
+        &mut self,
+        names: impl IntoIterator<Item = &'a str>,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let length = self.bound_variables.len();
+
+        self.bound_variables
+            .extend(names.into_iter().map(String::from));
+        visit(self);
+        self.bound_variables.truncate(length);
+    }
+
+    // Visits an expression in the scope of a variable.
+    fn visit_scoped(&mut self, name: &str, expression: &Expression) {
+        self.bind([name], |finder| finder.visit_expression(expression));
+    }
+
+    fn visit_default_alternative(&mut self, alternative: Option<&DefaultAlternative>) {
+        if let Some(alternative) = alternative {
+            self.visit_scoped(alternative.name(), alternative.expression());
+        }
+    }
+}
+
+impl Visitor for FreeVariableFinder {
+    fn visit_definition(&mut self, definition: &Definition) {
+        self.bind(
+            std::iter::once(definition.name()).chain(
+                definition
                     .arguments()
                     .iter()
-                    .all(|argument| variable != argument.name())
-        })
-        .collect()
+                    .map(|argument| argument.name()),
+            ),
+            |finder| walk_definition(finder, definition),
+        );
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Case(case) => {
+                self.visit_expression(case.argument());
+
+                for alternative in case.alternatives() {
+                    self.visit_scoped(alternative.name(), alternative.expression());
+                }
+
+                self.visit_default_alternative(case.default_alternative());
+            }
+            Expression::Let(let_) => {
+                self.visit_expression(let_.bound_expression());
+                self.visit_scoped(let_.name(), let_.expression());
+            }
+            Expression::LetRecord(let_) => {
+                self.visit_expression(let_.bound_expression());
+                self.bind(
+                    let_.elements().iter().map(|element| element.name()),
+                    |finder| finder.visit_expression(let_.expression()),
+                );
+            }
+            Expression::LetRecursive(let_) => self.bind(
+                let_.definitions()
+                    .iter()
+                    .map(|definition| definition.name()),
+                |finder| walk_expression(finder, expression),
+            ),
+            Expression::LiteralCase(case) => {
+                self.visit_expression(case.argument());
+
+                for alternative in case.alternatives() {
+                    self.visit_expression(alternative.expression());
+                }
+
+                self.visit_default_alternative(case.default_alternative());
+            }
+            Expression::SumCase(case) => {
+                self.visit_expression(case.argument());
+
+                for alternative in case.alternatives() {
+                    self.visit_scoped(alternative.name(), alternative.expression());
+                }
+
+                self.visit_default_alternative(case.default_alternative());
+            }
+            Expression::TryOperation(operation) => {
+                self.visit_expression(operation.operand());
+                self.visit_scoped(operation.name(), operation.then());
+            }
+            Expression::Variable(variable) => {
+                if !self
+                    .bound_variables
+                    .iter()
+                    .any(|name| name == variable.name())
+                {
+                    self.variables.insert(variable.name().into());
+                }
+            }
+            _ => walk_expression(self, expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_variable() {
+        assert_eq!(
+            find_free_variables(&Variable::new("x").into()),
+            vec!["x".into()].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn find_no_variable_bound_by_let() {
+        assert_eq!(
+            find_free_variables(
+                &Let::new("x", Type::Number, Variable::new("y"), Variable::new("x")).into()
+            ),
+            vec!["y".into()].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn find_variable_shadowed_only_in_alternative() {
+        assert_eq!(
+            find_free_variables(
+                &Case::new(
+                    Variable::new("x"),
+                    vec![Alternative::new(Type::Number, "x", Variable::new("x"))],
+                    Some(DefaultAlternative::new("y", Variable::new("x"))),
+                )
+                .into()
+            ),
+            vec!["x".into()].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn find_no_variable_bound_by_let_recursive() {
+        assert_eq!(
+            find_free_variables(
+                &LetRecursive::new(
+                    Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Number)],
+                        Variable::new("g"),
+                        Type::Number,
+                    ),
+                    Variable::new("f"),
+                )
+                .into()
+            ),
+            vec!["g".into()].into_iter().collect()
+        );
+    }
 }
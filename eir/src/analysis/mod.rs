@@ -1,11 +1,20 @@
+mod constant_conversion;
 mod environment_inference;
 mod free_variables;
+mod monomorphization;
 mod reference_count;
+mod shared_closure;
+mod tail_call_marking;
 mod type_check;
+mod type_substitution;
 mod variant_type_collection;
 
+pub use constant_conversion::*;
 pub use environment_inference::*;
 pub use free_variables::*;
+pub use monomorphization::*;
 pub use reference_count::*;
+pub use shared_closure::*;
+pub use tail_call_marking::*;
 pub use type_check::*;
 pub use variant_type_collection::*;
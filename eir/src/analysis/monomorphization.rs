@@ -0,0 +1,687 @@
+use super::type_substitution::{create_substitutions, substitute_type};
+use crate::{
+    ir::*,
+    types::{self, Type},
+};
+use std::collections::{HashMap, HashSet};
+
+struct Context<'a> {
+    type_definitions: HashMap<&'a str, &'a TypeDefinition>,
+    definitions: HashMap<&'a str, &'a Definition>,
+    type_instances: HashSet<String>,
+    definition_instances: HashSet<String>,
+    pending_type_instances: Vec<(String, &'a TypeDefinition, Vec<Type>)>,
+    pending_definition_instances: Vec<(String, &'a Definition, Vec<Type>)>,
+}
+
+// Instantiates polymorphic type definitions and definitions for every
+// combination of type arguments used in a module. Modules need to be
+// type-checked before monomorphization.
+pub fn monomorphize(module: &Module) -> Module {
+    let mut context = Context {
+        type_definitions: module
+            .type_definitions()
+            .iter()
+            .map(|definition| (definition.name(), definition))
+            .collect(),
+        definitions: module
+            .definitions()
+            .iter()
+            .map(|definition| (definition.name(), definition))
+            .collect(),
+        type_instances: Default::default(),
+        definition_instances: Default::default(),
+        pending_type_instances: vec![],
+        pending_definition_instances: vec![],
+    };
+
+    let mut type_definitions = module
+        .type_definitions()
+        .iter()
+        .filter(|definition| definition.parameters().is_empty())
+        .map(|definition| {
+            TypeDefinition::new(
+                definition.name(),
+                convert_record_body(definition.type_(), &Default::default(), &mut context),
+            )
+        })
+        .collect::<Vec<_>>();
+    let sum_definitions = module
+        .sum_definitions()
+        .iter()
+        .map(|definition| {
+            SumDefinition::new(
+                definition.name(),
+                definition
+                    .constructors()
+                    .iter()
+                    .map(|record| convert_record_type(record, &Default::default(), &mut context))
+                    .collect(),
+            )
+        })
+        .collect();
+    let foreign_declarations = module
+        .foreign_declarations()
+        .iter()
+        .map(|declaration| {
+            ForeignDeclaration::new(
+                declaration.name(),
+                declaration.foreign_name(),
+                convert_function_type(declaration.type_(), &Default::default(), &mut context),
+                declaration.calling_convention(),
+            )
+        })
+        .collect();
+    let declarations = module
+        .declarations()
+        .iter()
+        .map(|declaration| {
+            Declaration::new(
+                declaration.name(),
+                convert_function_type(declaration.type_(), &Default::default(), &mut context),
+            )
+        })
+        .collect();
+    let mut definitions = module
+        .definitions()
+        .iter()
+        .filter(|definition| definition.type_parameters().is_empty())
+        .map(|definition| {
+            convert_definition(
+                definition,
+                definition.name(),
+                &Default::default(),
+                &mut context,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    loop {
+        if let Some((name, definition, arguments)) = context.pending_type_instances.pop() {
+            type_definitions.push(TypeDefinition::new(
+                name,
+                convert_record_body(
+                    definition.type_(),
+                    &create_substitutions(definition.parameters(), &arguments),
+                    &mut context,
+                ),
+            ));
+        } else if let Some((name, definition, arguments)) =
+            context.pending_definition_instances.pop()
+        {
+            definitions.push(convert_definition(
+                definition,
+                &name,
+                &create_substitutions(definition.type_parameters(), &arguments),
+                &mut context,
+            ));
+        } else {
+            break;
+        }
+    }
+
+    Module::with_sum_definitions(
+        type_definitions,
+        sum_definitions,
+        foreign_declarations,
+        module.foreign_definitions().to_vec(),
+        declarations,
+        definitions,
+    )
+}
+
+fn convert_definition(
+    definition: &Definition,
+    name: &str,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> Definition {
+    Definition::with_options(
+        name,
+        vec![],
+        convert_arguments(definition.environment(), substitutions, context),
+        convert_arguments(definition.arguments(), substitutions, context),
+        ExpressionConverter {
+            substitutions,
+            context,
+        }
+        .fold_expression(definition.body()),
+        convert_type(definition.result_type(), substitutions, context),
+        definition.is_thunk(),
+    )
+}
+
+fn convert_arguments(
+    arguments: &[Argument],
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> Vec<Argument> {
+    arguments
+        .iter()
+        .map(|argument| {
+            Argument::new(
+                argument.name(),
+                convert_type(argument.type_(), substitutions, context),
+            )
+        })
+        .collect()
+}
+
+struct ExpressionConverter<'a, 'b> {
+    substitutions: &'b HashMap<String, Type>,
+    context: &'b mut Context<'a>,
+}
+
+impl ExpressionConverter<'_, '_> {
+    fn convert_type(&mut self, type_: &Type) -> Type {
+        convert_type(type_, self.substitutions, self.context)
+    }
+
+    fn convert_variables(&mut self, variables: &HashMap<String, Type>) -> HashMap<String, Type> {
+        variables
+            .iter()
+            .map(|(name, type_)| (name.clone(), self.convert_type(type_)))
+            .collect()
+    }
+
+    fn convert_alternatives(&mut self, alternatives: &[Alternative]) -> Vec<Alternative> {
+        alternatives
+            .iter()
+            .map(|alternative| {
+                Alternative::new(
+                    self.convert_type(alternative.type_()),
+                    alternative.name(),
+                    self.fold_expression(alternative.expression()),
+                )
+            })
+            .collect()
+    }
+}
+
+// Only expressions with types are converted here. The others are rebuilt
+// with their children converted by the default fold.
+impl Fold for ExpressionConverter<'_, '_> {
+    fn fold_definition(&mut self, definition: &Definition) -> Definition {
+        convert_definition(
+            definition,
+            definition.name(),
+            self.substitutions,
+            self.context,
+        )
+    }
+
+    fn fold_expression(&mut self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::ArithmeticOperation(operation) => ArithmeticOperation::new(
+                self.convert_type(operation.type_()),
+                operation.operator(),
+                self.fold_expression(operation.lhs()),
+                self.fold_expression(operation.rhs()),
+            )
+            .into(),
+            Expression::Case(case) => Case::new(
+                self.fold_expression(case.argument()),
+                self.convert_alternatives(case.alternatives()),
+                fold_default_alternative(self, case.default_alternative()),
+            )
+            .into(),
+            Expression::CloneVariables(clone) => CloneVariables::new(
+                self.convert_variables(clone.variables()),
+                self.fold_expression(clone.expression()),
+            )
+            .into(),
+            Expression::ComparisonOperation(operation) => ComparisonOperation::new(
+                self.convert_type(operation.type_()),
+                operation.operator(),
+                self.fold_expression(operation.lhs()),
+                self.fold_expression(operation.rhs()),
+            )
+            .into(),
+            Expression::DropVariables(drop) => DropVariables::new(
+                self.convert_variables(drop.variables()),
+                self.fold_expression(drop.expression()),
+            )
+            .into(),
+            Expression::FunctionApplication(application) => {
+                let function = if application.type_arguments().is_empty() {
+                    self.fold_expression(application.function())
+                } else {
+                    convert_polymorphic_function(application, self.substitutions, self.context)
+                };
+
+                FunctionApplication::with_options(
+                    convert_function_type(application.type_(), self.substitutions, self.context),
+                    function,
+                    vec![],
+                    self.fold_expression(application.argument()),
+                    application.is_tail(),
+                )
+                .into()
+            }
+            Expression::Let(let_) => Let::new(
+                let_.name(),
+                self.convert_type(let_.type_()),
+                self.fold_expression(let_.bound_expression()),
+                self.fold_expression(let_.expression()),
+            )
+            .into(),
+            Expression::LetRecord(let_) => LetRecord::new(
+                self.convert_type(let_.type_()),
+                convert_arguments(let_.elements(), self.substitutions, self.context),
+                self.fold_expression(let_.bound_expression()),
+                self.fold_expression(let_.expression()),
+            )
+            .into(),
+            Expression::Negation(negation) => Negation::new(
+                self.convert_type(negation.type_()),
+                self.fold_expression(negation.expression()),
+            )
+            .into(),
+            Expression::Record(record) => Record::new(
+                self.convert_type(record.type_()),
+                record
+                    .elements()
+                    .iter()
+                    .map(|element| self.fold_expression(element))
+                    .collect(),
+            )
+            .into(),
+            Expression::RecordElement(element) => RecordElement::with_field(
+                self.convert_type(element.type_()),
+                element.field().clone(),
+                self.fold_expression(element.record()),
+            )
+            .into(),
+            Expression::RecordUpdate(update) => RecordUpdate::new(
+                self.convert_type(update.type_()),
+                self.fold_expression(update.record()),
+                update
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        RecordUpdateField::with_field(
+                            field.field().clone(),
+                            self.fold_expression(field.expression()),
+                        )
+                    })
+                    .collect(),
+            )
+            .into(),
+            Expression::Sum(sum) => Sum::new(
+                sum.type_().clone(),
+                convert_record_type(sum.constructor(), self.substitutions, self.context),
+                self.fold_expression(sum.payload()),
+            )
+            .into(),
+            Expression::SumCase(case) => SumCase::new(
+                case.type_().clone(),
+                self.fold_expression(case.argument()),
+                self.convert_alternatives(case.alternatives()),
+                fold_default_alternative(self, case.default_alternative()),
+            )
+            .into(),
+            Expression::TryOperation(operation) => TryOperation::new(
+                self.fold_expression(operation.operand()),
+                operation.name(),
+                self.convert_type(operation.type_()),
+                self.fold_expression(operation.then()),
+            )
+            .into(),
+            Expression::Variant(variant) => Variant::new(
+                self.convert_type(variant.type_()),
+                self.fold_expression(variant.payload()),
+            )
+            .into(),
+            _ => fold_expression_children(self, expression),
+        }
+    }
+}
+
+fn convert_polymorphic_function(
+    application: &FunctionApplication,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> Expression {
+    let name = match application.function() {
+        Expression::Variable(variable) => variable.name(),
+        _ => unreachable!("type-checked polymorphic function"),
+    };
+    let arguments = application
+        .type_arguments()
+        .iter()
+        .map(|type_| convert_type(type_, substitutions, context))
+        .collect::<Vec<_>>();
+    let instance_name = get_instance_name(name, &arguments);
+
+    if context.definition_instances.insert(instance_name.clone()) {
+        let definition = context.definitions[name];
+
+        context
+            .pending_definition_instances
+            .push((instance_name.clone(), definition, arguments));
+    }
+
+    Variable::new(instance_name).into()
+}
+
+fn convert_record_body(
+    body: &types::RecordBody,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> types::RecordBody {
+    let elements = body
+        .elements()
+        .iter()
+        .map(|type_| convert_type(type_, substitutions, context))
+        .collect::<Vec<_>>();
+
+    if let Some(names) = body.field_names() {
+        types::RecordBody::with_fields(names.iter().cloned().zip(elements).collect())
+    } else {
+        types::RecordBody::new(elements)
+    }
+}
+
+fn convert_type(
+    type_: &Type,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> Type {
+    rename_type(&substitute_type(type_, substitutions), context)
+}
+
+fn convert_function_type(
+    type_: &types::Function,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> types::Function {
+    convert_type(&type_.clone().into(), substitutions, context)
+        .into_function()
+        .unwrap()
+}
+
+fn convert_record_type(
+    record: &types::Record,
+    substitutions: &HashMap<String, Type>,
+    context: &mut Context,
+) -> types::Record {
+    convert_type(&record.clone().into(), substitutions, context)
+        .into_record()
+        .unwrap()
+}
+
+// Renames instances of polymorphic record types after their type arguments.
+fn rename_type(type_: &Type, context: &mut Context) -> Type {
+    match type_ {
+        Type::Function(function) => types::Function::new(
+            rename_type(function.argument(), context),
+            rename_type(function.result(), context),
+        )
+        .into(),
+        Type::Lazy(lazy) => types::Lazy::new(rename_type(lazy.type_(), context)).into(),
+        Type::Record(record) if !record.arguments().is_empty() => {
+            let arguments = record
+                .arguments()
+                .iter()
+                .map(|type_| rename_type(type_, context))
+                .collect::<Vec<_>>();
+            let instance_name = get_instance_name(record.name(), &arguments);
+
+            if context.type_instances.insert(instance_name.clone()) {
+                let definition = context.type_definitions[record.name()];
+
+                context
+                    .pending_type_instances
+                    .push((instance_name.clone(), definition, arguments));
+            }
+
+            types::Record::new(instance_name).into()
+        }
+        Type::Tuple(tuple) => {
+            let elements = tuple
+                .elements()
+                .iter()
+                .map(|type_| rename_type(type_, context))
+                .collect::<Vec<_>>();
+
+            if let Some(names) = tuple.field_names() {
+                types::Tuple::with_fields(names.iter().cloned().zip(elements).collect())
+            } else {
+                types::Tuple::new(elements)
+            }
+            .into()
+        }
+        Type::Boolean
+        | Type::ByteString
+        | Type::Integer(_)
+        | Type::Number
+        | Type::Record(_)
+        | Type::Sum(_)
+        | Type::Variable(_)
+        | Type::Variant => type_.clone(),
+    }
+}
+
+fn get_instance_name(name: &str, arguments: &[Type]) -> String {
+    format!(
+        "{}<{}>",
+        name,
+        arguments
+            .iter()
+            .map(|type_| type_.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn create_identity_definition() -> Definition {
+        Definition::with_type_parameters(
+            "id",
+            vec!["a".into()],
+            vec![Argument::new("x", Type::Variable("a".into()))],
+            Variable::new("x"),
+            Type::Variable("a".into()),
+        )
+    }
+
+    #[test]
+    fn monomorphize_function() {
+        assert_eq!(
+            monomorphize(&Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    create_identity_definition(),
+                    Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::with_type_arguments(
+                            types::Function::new(Type::Number, Type::Number),
+                            Variable::new("id"),
+                            vec![Type::Number],
+                            Variable::new("x"),
+                        ),
+                        Type::Number,
+                    ),
+                ],
+            )),
+            Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            types::Function::new(Type::Number, Type::Number),
+                            Variable::new("id<number>"),
+                            Variable::new("x"),
+                        ),
+                        Type::Number,
+                    ),
+                    Definition::new(
+                        "id<number>",
+                        vec![Argument::new("x", Type::Number)],
+                        Variable::new("x"),
+                        Type::Number,
+                    ),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn keep_tail_call_marks() {
+        let module = Module::new(
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                FunctionApplication::with_options(
+                    types::Function::new(Type::Number, Type::Number),
+                    Variable::new("f"),
+                    vec![],
+                    Variable::new("x"),
+                    true,
+                ),
+                Type::Number,
+            )],
+        );
+
+        assert_eq!(monomorphize(&module), module);
+    }
+
+    #[test]
+    fn monomorphize_function_in_polymorphic_function() {
+        let type_ = Type::Variable("a".into());
+
+        assert_eq!(
+            monomorphize(&Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    create_identity_definition(),
+                    Definition::with_type_parameters(
+                        "g",
+                        vec!["a".into()],
+                        vec![Argument::new("x", type_.clone())],
+                        FunctionApplication::with_type_arguments(
+                            types::Function::new(type_.clone(), type_.clone()),
+                            Variable::new("id"),
+                            vec![type_.clone()],
+                            Variable::new("x"),
+                        ),
+                        type_,
+                    ),
+                    Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Boolean)],
+                        FunctionApplication::with_type_arguments(
+                            types::Function::new(Type::Boolean, Type::Boolean),
+                            Variable::new("g"),
+                            vec![Type::Boolean],
+                            Variable::new("x"),
+                        ),
+                        Type::Boolean,
+                    ),
+                ],
+            ))
+            .definitions()
+            .iter()
+            .map(|definition| definition.name())
+            .collect::<Vec<_>>(),
+            vec!["f", "g<boolean>", "id<boolean>"]
+        );
+    }
+
+    #[test]
+    fn monomorphize_record() {
+        let record_type = types::Record::with_arguments("box", vec![Type::Number]);
+
+        assert_eq!(
+            monomorphize(&Module::new(
+                vec![TypeDefinition::with_parameters(
+                    "box",
+                    vec!["a".into()],
+                    types::RecordBody::new(vec![Type::Variable("a".into())]),
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Record::new(record_type.clone(), vec![Variable::new("x").into()]),
+                    record_type,
+                )],
+            )),
+            Module::new(
+                vec![TypeDefinition::new(
+                    "box<number>",
+                    types::RecordBody::new(vec![Type::Number]),
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Record::new(
+                        types::Record::new("box<number>"),
+                        vec![Variable::new("x").into()]
+                    ),
+                    types::Record::new("box<number>"),
+                )],
+            )
+        );
+    }
+
+    #[test]
+    fn monomorphize_sum_constructors() {
+        assert_eq!(
+            monomorphize(&Module::with_sum_definitions(
+                vec![TypeDefinition::with_parameters(
+                    "box",
+                    vec!["a".into()],
+                    types::RecordBody::new(vec![Type::Variable("a".into())]),
+                )],
+                vec![SumDefinition::new(
+                    "foo",
+                    vec![types::Record::with_arguments("box", vec![Type::Number])],
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            )),
+            Module::with_sum_definitions(
+                vec![TypeDefinition::new(
+                    "box<number>",
+                    types::RecordBody::new(vec![Type::Number]),
+                )],
+                vec![SumDefinition::new(
+                    "foo",
+                    vec![types::Record::new("box<number>")],
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            )
+        );
+    }
+}
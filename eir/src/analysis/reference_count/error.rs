@@ -8,7 +8,11 @@ pub enum ReferenceCountError {
 
 impl Display for ReferenceCountError {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(formatter, "{:#?}", self)
+        match self {
+            Self::ExpressionNotSupported(expression) => {
+                write!(formatter, "expression not supported: {}", expression)
+            }
+        }
     }
 }
 
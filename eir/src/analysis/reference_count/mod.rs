@@ -1,29 +1,40 @@
 mod error;
 
+use super::free_variables::find_sibling_references;
 use crate::{ir::*, types::Type};
 pub use error::ReferenceCountError;
 use std::collections::{HashMap, HashSet};
 
 // Closure environments need to be inferred before reference counting.
 pub fn count_references(module: &Module) -> Result<Module, ReferenceCountError> {
-    Ok(Module::new(
+    Ok(Module::with_sum_definitions(
         module.type_definitions().to_vec(),
+        module.sum_definitions().to_vec(),
         module.foreign_declarations().to_vec(),
         module.foreign_definitions().to_vec(),
         module.declarations().to_vec(),
         module
             .definitions()
             .iter()
-            .map(convert_definition)
+            .map(|definition| convert_definition(definition, &[]))
             .collect::<Result<_, _>>()?,
     ))
 }
 
-fn convert_definition(definition: &Definition) -> Result<Definition, ReferenceCountError> {
+fn convert_definition(
+    definition: &Definition,
+    group: &[Definition],
+) -> Result<Definition, ReferenceCountError> {
     // Backend is expected to clone a function itself and its free variables at the very beginning
-    // of the function.
+    // of the function. It also creates closures of the other definitions in the same recursive
+    // group which are referred to by the function.
     let owned_variables = vec![(definition.name().into(), definition.type_().clone().into())]
         .into_iter()
+        .chain(
+            find_sibling_references(definition, group)
+                .into_iter()
+                .map(|sibling| (sibling.name().into(), sibling.type_().clone().into())),
+        )
         .chain(
             definition
                 .environment()
@@ -38,6 +49,7 @@ fn convert_definition(definition: &Definition) -> Result<Definition, ReferenceCo
 
     Ok(Definition::with_options(
         definition.name(),
+        definition.type_parameters().to_vec(),
         definition.environment().to_vec(),
         definition.arguments().to_vec(),
         drop_variables(
@@ -67,185 +79,55 @@ fn convert_expression(
     moved_variables: &HashSet<String>,
 ) -> Result<(Expression, HashSet<String>), ReferenceCountError> {
     Ok(match expression {
-        Expression::ArithmeticOperation(operation) => {
-            let (rhs, moved_variables) =
+        Expression::BooleanOperation(operation) => {
+            let (rhs, rhs_moved_variables) =
                 convert_expression(operation.rhs(), owned_variables, moved_variables)?;
-            let (lhs, moved_variables) =
-                convert_expression(operation.lhs(), owned_variables, &moved_variables)?;
-
-            (
-                ArithmeticOperation::new(operation.operator(), lhs, rhs).into(),
-                moved_variables,
-            )
-        }
-        Expression::Case(case) => {
-            let (default_alternative, default_alternative_moved_variables) =
-                if let Some(alternative) = case.default_alternative() {
-                    let (expression, moved_variables) = convert_expression(
-                        alternative.expression(),
-                        &owned_variables
-                            .clone()
-                            .into_iter()
-                            .chain(vec![(alternative.name().into(), Type::Variant)])
-                            .collect(),
-                        &moved_variables
-                            .clone()
-                            .into_iter()
-                            .filter(|variable| variable != alternative.name())
-                            .collect(),
-                    )?;
-
-                    (
-                        Some(DefaultAlternative::new(alternative.name(), expression)),
-                        moved_variables,
-                    )
-                } else {
-                    (None, moved_variables.clone())
-                };
-
-            let alternative_tuples = case
-                .alternatives()
-                .iter()
-                .map(|alternative| {
-                    let (expression, moved_variables) = convert_expression(
-                        alternative.expression(),
-                        &owned_variables
-                            .clone()
-                            .into_iter()
-                            .chain(vec![(
-                                alternative.name().into(),
-                                alternative.type_().clone(),
-                            )])
-                            .collect(),
-                        &moved_variables
-                            .clone()
-                            .into_iter()
-                            .filter(|variable| variable != alternative.name())
-                            .collect(),
-                    )?;
-
-                    Ok((
-                        Alternative::new(
-                            alternative.type_().clone(),
-                            alternative.name(),
-                            expression,
-                        ),
-                        moved_variables,
-                    ))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            let (lhs, lhs_moved_variables) =
+                convert_expression(operation.lhs(), owned_variables, &rhs_moved_variables)?;
 
-            let alternative_moved_variables = default_alternative_moved_variables
-                .iter()
-                .cloned()
+            // Variables moved only in right-hand sides need to be dropped when
+            // the right-hand sides are short-circuited.
+            let dropped_variables = rhs_moved_variables
+                .difference(moved_variables)
                 .filter(|variable| {
-                    if let Some(alternative) = case.default_alternative() {
-                        variable != alternative.name()
-                    } else {
-                        true
-                    }
+                    owned_variables
+                        .get(variable.as_str())
+                        .map(is_reference_counted)
+                        .unwrap_or_default()
                 })
-                .chain(
-                    alternative_tuples
-                        .iter()
-                        .flat_map(|(alternative, moved_variables)| {
-                            moved_variables
-                                .iter()
-                                .cloned()
-                                .filter(|variable| variable != alternative.name())
-                                .collect::<HashSet<String>>()
-                        }),
-                )
+                .cloned()
                 .collect::<HashSet<_>>();
 
-            let (argument, moved_variables) = convert_expression(
-                case.argument(),
-                owned_variables,
-                &moved_variables
-                    .iter()
-                    .cloned()
-                    .chain(alternative_moved_variables.clone())
-                    .collect(),
-            )?;
-
-            (
-                Case::new(
-                    argument,
-                    alternative_tuples
-                        .into_iter()
-                        .map(|(alternative, moved_variables)| {
-                            Alternative::new(
-                                alternative.type_().clone(),
-                                alternative.name(),
-                                drop_variables(
-                                    alternative.expression().clone(),
-                                    alternative_moved_variables
-                                        .clone()
-                                        .into_iter()
-                                        .chain(vec![alternative.name().into()])
-                                        .collect::<HashSet<_>>()
-                                        .difference(&moved_variables)
-                                        .cloned()
-                                        .collect(),
-                                    &owned_variables
-                                        .clone()
-                                        .into_iter()
-                                        .chain(vec![(
-                                            alternative.name().into(),
-                                            alternative.type_().clone(),
-                                        )])
-                                        .collect(),
-                                ),
-                            )
-                        })
-                        .collect(),
-                    default_alternative.map(|alternative| {
-                        DefaultAlternative::new(
-                            alternative.name(),
-                            drop_variables(
-                                alternative.expression().clone(),
-                                alternative_moved_variables
-                                    .into_iter()
-                                    .chain(vec![alternative.name().into()])
-                                    .collect::<HashSet<_>>()
-                                    .difference(&default_alternative_moved_variables)
-                                    .cloned()
-                                    .collect(),
-                                &owned_variables
-                                    .clone()
-                                    .into_iter()
-                                    .chain(vec![(alternative.name().into(), Type::Variant)])
-                                    .collect(),
-                            ),
-                        )
-                    }),
-                )
-                .into(),
-                moved_variables,
-            )
-        }
-        Expression::ComparisonOperation(operation) => {
-            let (rhs, moved_variables) =
-                convert_expression(operation.rhs(), owned_variables, moved_variables)?;
-            let (lhs, moved_variables) =
-                convert_expression(operation.lhs(), owned_variables, &moved_variables)?;
-
             (
-                ComparisonOperation::new(operation.operator(), lhs, rhs).into(),
-                moved_variables,
-            )
-        }
-        Expression::FunctionApplication(application) => {
-            let (argument, moved_variables) =
-                convert_expression(application.argument(), owned_variables, moved_variables)?;
-            let (function, moved_variables) =
-                convert_expression(application.function(), owned_variables, &moved_variables)?;
+                if dropped_variables.is_empty() {
+                    BooleanOperation::new(operation.operator(), lhs, rhs).into()
+                } else {
+                    let short_circuit = drop_variables(
+                        operation.operator() == BooleanOperator::Or,
+                        dropped_variables,
+                        owned_variables,
+                    );
 
-            (
-                FunctionApplication::new(application.type_().clone(), function, argument).into(),
-                moved_variables,
+                    match operation.operator() {
+                        BooleanOperator::And => If::new(lhs, rhs, short_circuit),
+                        BooleanOperator::Or => If::new(lhs, short_circuit, rhs),
+                    }
+                    .into()
+                },
+                lhs_moved_variables,
             )
         }
+        Expression::Case(case) => convert_case(
+            case.argument(),
+            case.alternatives(),
+            case.default_alternative(),
+            &Type::Variant,
+            owned_variables,
+            moved_variables,
+            |argument, alternatives, default_alternative| {
+                Case::new(argument, alternatives, default_alternative).into()
+            },
+        )?,
         Expression::If(if_) => {
             let (then, then_moved_variables) =
                 convert_expression(if_.then(), owned_variables, moved_variables)?;
@@ -296,8 +178,8 @@ fn convert_expression(
                 &let_owned_variables,
                 &moved_variables
                     .iter()
+                    .filter(|&variable| variable != let_.name())
                     .cloned()
-                    .filter(|variable| variable != let_.name())
                     .collect(),
             )?;
             let (bound_expression, moved_variables) = convert_expression(
@@ -309,8 +191,8 @@ fn convert_expression(
                     .chain(
                         expression_moved_variables
                             .iter()
-                            .cloned()
-                            .filter(|variable| variable != let_.name()),
+                            .filter(|&variable| variable != let_.name())
+                            .cloned(),
                     )
                     .collect(),
             )?;
@@ -334,100 +216,204 @@ fn convert_expression(
                 moved_variables,
             )
         }
-        Expression::LetRecursive(let_) => {
+        // Records are moved into let expressions and their elements are owned by
+        // them. Backends move elements out of unique records.
+        Expression::LetRecord(let_) => {
+            let names = let_
+                .elements()
+                .iter()
+                .map(|element| element.name().into())
+                .collect::<HashSet<String>>();
             let let_owned_variables = owned_variables
                 .clone()
                 .into_iter()
-                .chain(vec![(
-                    let_.definition().name().into(),
-                    let_.definition().type_().clone().into(),
-                )])
+                .chain(
+                    let_.elements()
+                        .iter()
+                        .map(|element| (element.name().into(), element.type_().clone())),
+                )
                 .collect();
             let (expression, expression_moved_variables) = convert_expression(
                 let_.expression(),
                 &let_owned_variables,
+                &moved_variables.difference(&names).cloned().collect(),
+            )?;
+            let (bound_expression, moved_variables) = convert_expression(
+                let_.bound_expression(),
+                owned_variables,
                 &moved_variables
+                    .clone()
+                    .into_iter()
+                    .chain(expression_moved_variables.difference(&names).cloned())
+                    .collect(),
+            )?;
+
+            (
+                LetRecord::new(
+                    let_.type_().clone(),
+                    let_.elements().to_vec(),
+                    bound_expression,
+                    drop_variables(
+                        expression,
+                        names
+                            .difference(&expression_moved_variables)
+                            .cloned()
+                            .collect(),
+                        &let_owned_variables,
+                    ),
+                )
+                .into(),
+                moved_variables,
+            )
+        }
+        // Lazy values hold their own references to the variables in their
+        // environments like closures.
+        Expression::Lazy(lazy) => {
+            let environment_variables = lazy
+                .definition()
+                .environment()
+                .iter()
+                .map(|argument| argument.name().into())
+                .collect::<HashSet<String>>();
+
+            (
+                clone_variables(
+                    Lazy::with_definition(convert_definition(lazy.definition(), &[])?),
+                    environment_variables
+                        .intersection(moved_variables)
+                        .cloned()
+                        .collect(),
+                    owned_variables,
+                ),
+                moved_variables
                     .iter()
                     .cloned()
-                    .filter(|variable| variable != let_.definition().name())
+                    .chain(environment_variables)
                     .collect(),
+            )
+        }
+        Expression::LetRecursive(let_) => {
+            let names = let_
+                .definitions()
+                .iter()
+                .map(|definition| definition.name().into())
+                .collect::<HashSet<String>>();
+            let let_owned_variables = owned_variables
+                .clone()
+                .into_iter()
+                .chain(let_.definitions().iter().map(|definition| {
+                    (definition.name().into(), definition.type_().clone().into())
+                }))
+                .collect();
+            let (expression, expression_moved_variables) = convert_expression(
+                let_.expression(),
+                &let_owned_variables,
+                &moved_variables.difference(&names).cloned().collect(),
             )?;
             let moved_variables = moved_variables
                 .clone()
                 .into_iter()
-                .chain(
-                    expression_moved_variables
-                        .iter()
-                        .cloned()
-                        .filter(|variable| variable != let_.definition().name()),
-                )
-                .collect();
-            let cloned_variables = let_
-                .definition()
-                .environment()
+                .chain(expression_moved_variables.difference(&names).cloned())
+                .collect::<HashSet<_>>();
+            // Definitions in a group share the same environment.
+            let environment_variables = let_
+                .definitions()
                 .iter()
+                .flat_map(|definition| definition.environment())
                 .map(|argument| argument.name().into())
-                .collect::<HashSet<_>>()
+                .collect::<HashSet<String>>();
+            let cloned_variables = environment_variables
                 .intersection(&moved_variables)
                 .cloned()
                 .collect::<HashSet<_>>();
 
+            let expression = Expression::from(LetRecursive::with_definitions(
+                let_.definitions()
+                    .iter()
+                    .map(|definition| convert_definition(definition, let_.definitions()))
+                    .collect::<Result<_, _>>()?,
+                drop_variables(
+                    expression,
+                    names
+                        .difference(&expression_moved_variables)
+                        .cloned()
+                        .collect(),
+                    &let_owned_variables,
+                ),
+            ));
+
             (
-                clone_variables(
-                    LetRecursive::new(
-                        convert_definition(let_.definition())?,
-                        if expression_moved_variables.contains(let_.definition().name()) {
-                            expression
-                        } else {
-                            drop_variables(
-                                expression,
-                                vec![let_.definition().name().into()].into_iter().collect(),
-                                &let_owned_variables,
-                            )
-                        },
-                    ),
-                    cloned_variables,
-                    owned_variables,
+                // Every closure in a group holds its own references to the
+                // variables in the environment.
+                let_.definitions().iter().skip(1).fold(
+                    clone_variables(expression, cloned_variables, owned_variables),
+                    |expression, _| {
+                        clone_variables(expression, environment_variables.clone(), owned_variables)
+                    },
                 ),
                 moved_variables
                     .into_iter()
-                    .chain(
-                        let_.definition()
-                            .environment()
-                            .iter()
-                            .map(|argument| argument.name().into()),
-                    )
+                    .chain(environment_variables)
                     .collect::<HashSet<String>>(),
             )
         }
-        Expression::Record(record) => {
-            let (elements, moved_variables) = record.elements().iter().rev().fold(
-                Ok((vec![], moved_variables.clone())),
-                |result, element| {
-                    let (elements, moved_variables) = result?;
-                    let (element, moved_variables) =
-                        convert_expression(element, owned_variables, &moved_variables)?;
-
-                    Ok((
-                        vec![element].into_iter().chain(elements).collect(),
-                        moved_variables,
-                    ))
-                },
+        Expression::LiteralCase(case) => {
+            let (argument, mut expressions, moved_variables) = convert_branches(
+                case.argument(),
+                &case
+                    .alternatives()
+                    .iter()
+                    .map(|alternative| (None, alternative.expression()))
+                    .chain(case.default_alternative().map(|alternative| {
+                        (
+                            Some((alternative.name(), case.type_())),
+                            alternative.expression(),
+                        )
+                    }))
+                    .collect::<Vec<_>>(),
+                owned_variables,
+                moved_variables,
             )?;
+            let default_alternative = case.default_alternative().map(|alternative| {
+                DefaultAlternative::new(alternative.name(), expressions.pop().unwrap())
+            });
 
             (
-                Record::new(record.type_().clone(), elements).into(),
+                LiteralCase::new(
+                    case.type_().clone(),
+                    argument,
+                    case.alternatives()
+                        .iter()
+                        .zip(expressions)
+                        .map(|(alternative, expression)| {
+                            LiteralAlternative::new(alternative.literal().clone(), expression)
+                        })
+                        .collect(),
+                    default_alternative,
+                )
+                .into(),
                 moved_variables,
             )
         }
-        Expression::RecordElement(element) => {
-            let (record, moved_variables) =
-                convert_expression(element.record(), owned_variables, moved_variables)?;
-
-            (
-                RecordElement::new(element.type_().clone(), element.index(), record).into(),
-                moved_variables,
-            )
+        Expression::SumCase(case) => convert_case(
+            case.argument(),
+            case.alternatives(),
+            case.default_alternative(),
+            &case.type_().clone().into(),
+            owned_variables,
+            moved_variables,
+            |argument, alternatives, default_alternative| {
+                SumCase::new(
+                    case.type_().clone(),
+                    argument,
+                    alternatives,
+                    default_alternative,
+                )
+                .into()
+            },
+        )?,
+        Expression::TryOperation(operation) => {
+            convert_try_operation(operation, owned_variables, moved_variables)?
         }
         Expression::Variable(variable) => {
             if should_clone_variable(variable.name(), owned_variables, moved_variables) {
@@ -450,26 +436,267 @@ fn convert_expression(
                 )
             }
         }
-        Expression::Variant(variant) => {
-            let (expression, moved_variables) =
-                convert_expression(variant.payload(), owned_variables, moved_variables)?;
-
-            (
-                Variant::new(variant.type_().clone(), expression).into(),
-                moved_variables,
-            )
-        }
-        Expression::Boolean(_) | Expression::ByteString(_) | Expression::Number(_) => {
-            (expression.clone(), moved_variables.clone())
-        }
         Expression::CloneVariables(_) | Expression::DropVariables(_) => {
             return Err(ReferenceCountError::ExpressionNotSupported(
                 expression.clone(),
             ));
         }
+        _ => convert_operands(expression, owned_variables, moved_variables)?,
     })
 }
 
+// Operands are evaluated in order. They are converted in reverse so that
+// variables moved by later operands are known when converting earlier ones.
+fn convert_operands(
+    expression: &Expression,
+    owned_variables: &HashMap<String, Type>,
+    moved_variables: &HashSet<String>,
+) -> Result<(Expression, HashSet<String>), ReferenceCountError> {
+    let mut collector = OperandCollector { operands: vec![] };
+
+    walk_expression(&mut collector, expression);
+
+    let (operands, moved_variables) = collector.operands.iter().rev().try_fold(
+        (vec![], moved_variables.clone()),
+        |(operands, moved_variables), operand| {
+            let (operand, moved_variables) =
+                convert_expression(operand, owned_variables, &moved_variables)?;
+
+            Ok((
+                vec![operand]
+                    .into_iter()
+                    .chain(operands)
+                    .collect::<Vec<_>>(),
+                moved_variables,
+            ))
+        },
+    )?;
+
+    Ok((
+        fold_expression_children(
+            &mut OperandReplacer {
+                operands: operands.into_iter(),
+            },
+            expression,
+        ),
+        moved_variables,
+    ))
+}
+
+struct OperandCollector {
+    operands: Vec<Expression>,
+}
+
+impl Visitor for OperandCollector {
+    fn visit_expression(&mut self, expression: &Expression) {
+        self.operands.push(expression.clone());
+    }
+}
+
+struct OperandReplacer {
+    operands: std::vec::IntoIter<Expression>,
+}
+
+impl Fold for OperandReplacer {
+    fn fold_expression(&mut self, _: &Expression) -> Expression {
+        self.operands.next().unwrap()
+    }
+}
+
+// Variables alive after try operations are dropped on their error paths.
+// Variables moved only on error paths are dropped right after try operations
+// on their success paths instead.
+fn convert_try_operation(
+    operation: &TryOperation,
+    owned_variables: &HashMap<String, Type>,
+    moved_variables: &HashSet<String>,
+) -> Result<(Expression, HashSet<String>), ReferenceCountError> {
+    let then_owned_variables = owned_variables
+        .clone()
+        .into_iter()
+        .chain(vec![(operation.name().into(), operation.type_().clone())])
+        .collect();
+    let (then, then_moved_variables) =
+        convert_expression(operation.then(), &then_owned_variables, &Default::default())?;
+    let then = drop_variables(
+        then,
+        moved_variables
+            .iter()
+            .filter(|variable| owned_variables.contains_key(variable.as_str()))
+            .cloned()
+            .chain(vec![operation.name().into()])
+            .filter(|variable| !then_moved_variables.contains(variable))
+            .collect(),
+        &then_owned_variables,
+    );
+    let then_moved_variables = then_moved_variables
+        .into_iter()
+        .filter(|variable| {
+            variable != operation.name() && owned_variables.contains_key(variable.as_str())
+        })
+        .collect::<HashSet<_>>();
+    let (operand, operand_moved_variables) = convert_expression(
+        operation.operand(),
+        owned_variables,
+        &moved_variables
+            .iter()
+            .cloned()
+            .chain(then_moved_variables.clone())
+            .collect(),
+    )?;
+    let operation = TryOperation::new(operand, operation.name(), operation.type_().clone(), then);
+    let dropped_variables = then_moved_variables
+        .difference(moved_variables)
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    Ok((
+        if dropped_variables.is_empty() {
+            operation.into()
+        } else {
+            let mut name = operation.name().to_owned();
+
+            while dropped_variables.contains(&name) {
+                name.push('\'');
+            }
+
+            Let::new(
+                &name,
+                Type::Variant,
+                operation,
+                drop_variables(Variable::new(&name), dropped_variables, owned_variables),
+            )
+            .into()
+        },
+        operand_moved_variables,
+    ))
+}
+
+fn convert_case(
+    argument: &Expression,
+    alternatives: &[Alternative],
+    default_alternative: Option<&DefaultAlternative>,
+    default_alternative_type: &Type,
+    owned_variables: &HashMap<String, Type>,
+    moved_variables: &HashSet<String>,
+    create_case: impl Fn(Expression, Vec<Alternative>, Option<DefaultAlternative>) -> Expression,
+) -> Result<(Expression, HashSet<String>), ReferenceCountError> {
+    let (argument, expressions, moved_variables) = convert_branches(
+        argument,
+        &alternatives
+            .iter()
+            .map(|alternative| {
+                (
+                    Some((alternative.name(), alternative.type_())),
+                    alternative.expression(),
+                )
+            })
+            .chain(default_alternative.map(|alternative| {
+                (
+                    Some((alternative.name(), default_alternative_type)),
+                    alternative.expression(),
+                )
+            }))
+            .collect::<Vec<_>>(),
+        owned_variables,
+        moved_variables,
+    )?;
+    let mut expressions = expressions.into_iter();
+
+    Ok((
+        create_case(
+            argument,
+            alternatives
+                .iter()
+                .zip(&mut expressions)
+                .map(|(alternative, expression)| {
+                    Alternative::new(alternative.type_().clone(), alternative.name(), expression)
+                })
+                .collect(),
+            default_alternative
+                .zip(expressions.next())
+                .map(|(alternative, expression)| {
+                    DefaultAlternative::new(alternative.name(), expression)
+                }),
+        ),
+        moved_variables,
+    ))
+}
+
+// Branches optionally bind variables owned by themselves. Variables moved in
+// some branches are dropped in the others.
+fn convert_branches(
+    argument: &Expression,
+    branches: &[(Option<(&str, &Type)>, &Expression)],
+    owned_variables: &HashMap<String, Type>,
+    moved_variables: &HashSet<String>,
+) -> Result<(Expression, Vec<Expression>, HashSet<String>), ReferenceCountError> {
+    let get_branch_variables = |variable: Option<(&str, &Type)>| {
+        let mut owned_variables = owned_variables.clone();
+        let mut moved_variables = moved_variables.clone();
+
+        if let Some((name, type_)) = variable {
+            owned_variables.insert(name.into(), type_.clone());
+            moved_variables.remove(name);
+        }
+
+        (owned_variables, moved_variables)
+    };
+
+    let converted_branches = branches
+        .iter()
+        .map(|(variable, expression)| {
+            let (owned_variables, moved_variables) = get_branch_variables(*variable);
+
+            convert_expression(expression, &owned_variables, &moved_variables)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let branch_moved_variables = branches
+        .iter()
+        .zip(&converted_branches)
+        .flat_map(|((variable, _), (_, moved_variables))| {
+            moved_variables
+                .iter()
+                .filter(move |name| Some(name.as_str()) != variable.map(|(name, _)| name))
+                .cloned()
+        })
+        .collect::<HashSet<_>>();
+
+    let (argument, moved_variables) = convert_expression(
+        argument,
+        owned_variables,
+        &moved_variables
+            .iter()
+            .cloned()
+            .chain(branch_moved_variables.clone())
+            .collect(),
+    )?;
+
+    Ok((
+        argument,
+        branches
+            .iter()
+            .zip(converted_branches)
+            .map(|((variable, _), (expression, moved_variables))| {
+                drop_variables(
+                    expression,
+                    branch_moved_variables
+                        .iter()
+                        .cloned()
+                        .chain(variable.map(|(name, _)| name.into()))
+                        .collect::<HashSet<_>>()
+                        .difference(&moved_variables)
+                        .cloned()
+                        .collect(),
+                    &get_branch_variables(*variable).0,
+                )
+            })
+            .collect(),
+        moved_variables,
+    ))
+}
+
 fn clone_variables(
     expression: impl Into<Expression>,
     cloned_variables: HashSet<String>,
@@ -514,6 +741,10 @@ fn drop_variables(
     }
 }
 
+fn is_reference_counted(type_: &Type) -> bool {
+    !matches!(type_, Type::Boolean | Type::Integer(_) | Type::Number)
+}
+
 fn should_clone_variable(
     variable: &str,
     owned_variables: &HashMap<String, Type>,
@@ -558,10 +789,59 @@ mod tests {
         );
     }
 
-    mod function_applications {
-        use super::*;
+    #[test]
+    fn convert_record_update() {
+        assert_eq!(
+            convert_expression(
+                &RecordUpdate::new(
+                    types::Record::new("a"),
+                    Variable::new("x"),
+                    vec![RecordUpdateField::new(0, Variable::new("x"))]
+                )
+                .into(),
+                &vec![("x".into(), types::Record::new("a").into())]
+                    .into_iter()
+                    .collect(),
+                &Default::default()
+            )
+            .unwrap(),
+            (
+                RecordUpdate::new(
+                    types::Record::new("a"),
+                    CloneVariables::new(
+                        vec![("x".into(), types::Record::new("a").into())]
+                            .into_iter()
+                            .collect(),
+                        Variable::new("x")
+                    ),
+                    vec![RecordUpdateField::new(0, Variable::new("x"))]
+                )
+                .into(),
+                vec!["x".into()].into_iter().collect()
+            ),
+        );
+    }
+
+    mod function_applications {
+        use super::*;
         use pretty_assertions::assert_eq;
 
+        #[test]
+        fn keep_type_arguments_and_tail_call_marks() {
+            let application = Expression::from(FunctionApplication::with_options(
+                types::Function::new(Type::Number, Type::Number),
+                Variable::new("f"),
+                vec![Type::Number],
+                42.0,
+                true,
+            ));
+
+            assert_eq!(
+                convert_expression(&application, &Default::default(), &Default::default()).unwrap(),
+                (application, vec!["f".into()].into_iter().collect()),
+            );
+        }
+
         #[test]
         fn convert_single() {
             assert_eq!(
@@ -690,6 +970,7 @@ mod tests {
                         Type::Number,
                         42.0,
                         ArithmeticOperation::new(
+                            Type::Number,
                             ArithmeticOperator::Add,
                             Variable::new("x"),
                             Variable::new("x")
@@ -706,6 +987,7 @@ mod tests {
                     Type::Number,
                     42.0,
                     ArithmeticOperation::new(
+                        Type::Number,
                         ArithmeticOperator::Add,
                         CloneVariables::new(
                             vec![("x".into(), Type::Number)].into_iter().collect(),
@@ -825,6 +1107,122 @@ mod tests {
         }
     }
 
+    mod let_record {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn create_type() -> types::Tuple {
+            types::Tuple::new(vec![Type::ByteString, Type::ByteString])
+        }
+
+        #[test]
+        fn convert_with_moved_elements() {
+            let expression = Expression::from(LetRecord::new(
+                create_type(),
+                vec![
+                    Argument::new("x", Type::ByteString),
+                    Argument::new("y", Type::ByteString),
+                ],
+                Variable::new("r"),
+                ByteStringConcatenation::new(Variable::new("x"), Variable::new("y")),
+            ));
+
+            assert_eq!(
+                convert_expression(
+                    &expression,
+                    &vec![("r".into(), create_type().into())]
+                        .into_iter()
+                        .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (expression, vec!["r".into()].into_iter().collect())
+            );
+        }
+
+        #[test]
+        fn convert_with_dropped_element() {
+            assert_eq!(
+                convert_expression(
+                    &LetRecord::new(
+                        create_type(),
+                        vec![
+                            Argument::new("x", Type::ByteString),
+                            Argument::new("y", Type::ByteString),
+                        ],
+                        Variable::new("r"),
+                        Variable::new("x"),
+                    )
+                    .into(),
+                    &vec![("r".into(), create_type().into())]
+                        .into_iter()
+                        .collect(),
+                    &Default::default()
+                )
+                .unwrap()
+                .0,
+                LetRecord::new(
+                    create_type(),
+                    vec![
+                        Argument::new("x", Type::ByteString),
+                        Argument::new("y", Type::ByteString),
+                    ],
+                    Variable::new("r"),
+                    DropVariables::new(
+                        vec![("y".into(), Type::ByteString)].into_iter().collect(),
+                        Variable::new("x")
+                    ),
+                )
+                .into()
+            );
+        }
+
+        #[test]
+        fn convert_with_cloned_record() {
+            assert_eq!(
+                convert_expression(
+                    &LetRecord::new(
+                        create_type(),
+                        vec![
+                            Argument::new("x", Type::ByteString),
+                            Argument::new("y", Type::ByteString),
+                        ],
+                        Variable::new("r"),
+                        Record::new(
+                            create_type(),
+                            vec![Variable::new("x").into(), Variable::new("y").into()]
+                        ),
+                    )
+                    .into(),
+                    &vec![("r".into(), create_type().into())]
+                        .into_iter()
+                        .collect(),
+                    &vec!["r".into()].into_iter().collect(),
+                )
+                .unwrap()
+                .0,
+                LetRecord::new(
+                    create_type(),
+                    vec![
+                        Argument::new("x", Type::ByteString),
+                        Argument::new("y", Type::ByteString),
+                    ],
+                    CloneVariables::new(
+                        vec![("r".into(), create_type().into())]
+                            .into_iter()
+                            .collect(),
+                        Variable::new("r")
+                    ),
+                    Record::new(
+                        create_type(),
+                        vec![Variable::new("x").into(), Variable::new("y").into()]
+                    ),
+                )
+                .into()
+            );
+        }
+    }
+
     mod let_recursive {
         use super::*;
         use pretty_assertions::assert_eq;
@@ -1106,6 +1504,95 @@ mod tests {
             );
         }
 
+        #[test]
+        fn convert_mutually_recursive_definitions() {
+            let function_type = types::Function::new(Type::Number, Type::Number);
+
+            assert_eq!(
+                convert_expression(
+                    &LetRecursive::with_definitions(
+                        vec![
+                            Definition::with_environment(
+                                "f",
+                                vec![Argument::new("y", Type::Number)],
+                                vec![Argument::new("x", Type::Number)],
+                                FunctionApplication::new(
+                                    function_type.clone(),
+                                    Variable::new("g"),
+                                    Variable::new("x")
+                                ),
+                                Type::Number
+                            ),
+                            Definition::with_environment(
+                                "g",
+                                vec![Argument::new("y", Type::Number)],
+                                vec![Argument::new("x", Type::Number)],
+                                42.0,
+                                Type::Number
+                            ),
+                        ],
+                        Variable::new("f")
+                    )
+                    .into(),
+                    &vec![("y".into(), Type::Number)].into_iter().collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    CloneVariables::new(
+                        vec![("y".into(), Type::Number)].into_iter().collect(),
+                        LetRecursive::with_definitions(
+                            vec![
+                                Definition::with_environment(
+                                    "f",
+                                    vec![Argument::new("y", Type::Number)],
+                                    vec![Argument::new("x", Type::Number)],
+                                    DropVariables::new(
+                                        vec![
+                                            ("f".into(), function_type.clone().into()),
+                                            ("y".into(), Type::Number),
+                                        ]
+                                        .into_iter()
+                                        .collect(),
+                                        FunctionApplication::new(
+                                            function_type.clone(),
+                                            Variable::new("g"),
+                                            Variable::new("x")
+                                        ),
+                                    ),
+                                    Type::Number
+                                ),
+                                Definition::with_environment(
+                                    "g",
+                                    vec![Argument::new("y", Type::Number)],
+                                    vec![Argument::new("x", Type::Number)],
+                                    DropVariables::new(
+                                        vec![
+                                            ("g".into(), function_type.clone().into()),
+                                            ("x".into(), Type::Number),
+                                            ("y".into(), Type::Number),
+                                        ]
+                                        .into_iter()
+                                        .collect(),
+                                        42.0,
+                                    ),
+                                    Type::Number
+                                ),
+                            ],
+                            DropVariables::new(
+                                vec![("g".into(), function_type.into())]
+                                    .into_iter()
+                                    .collect(),
+                                Variable::new("f")
+                            )
+                        )
+                    )
+                    .into(),
+                    vec!["y".into()].into_iter().collect()
+                ),
+            );
+        }
+
         #[test]
         fn convert_let_recursive_in_let() {
             let function_type = types::Function::new(Type::Number, Type::Number);
@@ -1234,12 +1721,15 @@ mod tests {
         #[test]
         fn convert_with_dropped_argument() {
             assert_eq!(
-                convert_definition(&Definition::new(
-                    "f",
-                    vec![Argument::new("x", Type::Number)],
-                    42.0,
-                    Type::Number
-                ))
+                convert_definition(
+                    &Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Number)],
+                        42.0,
+                        Type::Number
+                    ),
+                    &[]
+                )
                 .unwrap(),
                 Definition::new(
                     "f",
@@ -1264,13 +1754,16 @@ mod tests {
         #[test]
         fn convert_with_dropped_free_variable() {
             assert_eq!(
-                convert_definition(&Definition::with_environment(
-                    "f",
-                    vec![Argument::new("y", Type::Number)],
-                    vec![Argument::new("x", Type::Number)],
-                    42.0,
-                    Type::Number
-                ))
+                convert_definition(
+                    &Definition::with_environment(
+                        "f",
+                        vec![Argument::new("y", Type::Number)],
+                        vec![Argument::new("x", Type::Number)],
+                        42.0,
+                        Type::Number
+                    ),
+                    &[]
+                )
                 .unwrap(),
                 Definition::with_environment(
                     "f",
@@ -1334,6 +1827,131 @@ mod tests {
         }
     }
 
+    mod boolean_operation {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn convert_with_unboxed_variables() {
+            let expression = Expression::from(BooleanOperation::new(
+                BooleanOperator::And,
+                Variable::new("x"),
+                Variable::new("y"),
+            ));
+
+            assert_eq!(
+                convert_expression(
+                    &expression,
+                    &vec![("x".into(), Type::Boolean), ("y".into(), Type::Boolean)]
+                        .into_iter()
+                        .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    expression,
+                    vec!["x".into(), "y".into()].into_iter().collect()
+                ),
+            );
+        }
+
+        #[test]
+        fn convert_with_moved_variable_in_rhs() {
+            let record_type = types::Record::new("a");
+
+            assert_eq!(
+                convert_expression(
+                    &BooleanOperation::new(
+                        BooleanOperator::Or,
+                        Variable::new("x"),
+                        RecordElement::new(record_type.clone(), 0, Variable::new("y")),
+                    )
+                    .into(),
+                    &vec![
+                        ("x".into(), Type::Boolean),
+                        ("y".into(), record_type.clone().into())
+                    ]
+                    .into_iter()
+                    .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    If::new(
+                        Variable::new("x"),
+                        DropVariables::new(
+                            vec![("y".into(), record_type.clone().into())]
+                                .into_iter()
+                                .collect(),
+                            true
+                        ),
+                        RecordElement::new(record_type, 0, Variable::new("y")),
+                    )
+                    .into(),
+                    vec!["x".into(), "y".into()].into_iter().collect()
+                ),
+            );
+        }
+    }
+
+    mod byte_strings {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn convert_concatenation_with_same_variables() {
+            assert_eq!(
+                convert_expression(
+                    &ByteStringConcatenation::new(Variable::new("x"), Variable::new("x")).into(),
+                    &vec![("x".into(), Type::ByteString)].into_iter().collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    ByteStringConcatenation::new(
+                        CloneVariables::new(
+                            vec![("x".into(), Type::ByteString)].into_iter().collect(),
+                            Variable::new("x")
+                        ),
+                        Variable::new("x")
+                    )
+                    .into(),
+                    vec!["x".into()].into_iter().collect()
+                ),
+            );
+        }
+
+        #[test]
+        fn convert_slice() {
+            let expression = Expression::from(ByteStringSlice::new(
+                Variable::new("x"),
+                Variable::new("y"),
+                ByteStringLength::new(Variable::new("z")),
+            ));
+
+            assert_eq!(
+                convert_expression(
+                    &expression,
+                    &vec![
+                        ("x".into(), Type::ByteString),
+                        ("y".into(), types::Integer::Unsigned64.into()),
+                        ("z".into(), Type::ByteString)
+                    ]
+                    .into_iter()
+                    .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    expression,
+                    vec!["x".into(), "y".into(), "z".into()]
+                        .into_iter()
+                        .collect()
+                ),
+            );
+        }
+    }
+
     mod case {
         use super::*;
         use pretty_assertions::assert_eq;
@@ -1448,6 +2066,51 @@ mod tests {
             );
         }
 
+        #[test]
+        fn convert_literal_case() {
+            assert_eq!(
+                convert_expression(
+                    &LiteralCase::new(
+                        Type::ByteString,
+                        Variable::new("x"),
+                        vec![LiteralAlternative::new(
+                            ByteString::new("foo"),
+                            Variable::new("y")
+                        )],
+                        Some(DefaultAlternative::new("z", Variable::new("z")))
+                    )
+                    .into(),
+                    &vec![
+                        ("x".into(), Type::ByteString),
+                        ("y".into(), Type::ByteString)
+                    ]
+                    .into_iter()
+                    .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    LiteralCase::new(
+                        Type::ByteString,
+                        Variable::new("x"),
+                        vec![LiteralAlternative::new(
+                            ByteString::new("foo"),
+                            Variable::new("y")
+                        )],
+                        Some(DefaultAlternative::new(
+                            "z",
+                            DropVariables::new(
+                                vec![("y".into(), Type::ByteString)].into_iter().collect(),
+                                Variable::new("z")
+                            )
+                        ))
+                    )
+                    .into(),
+                    vec!["x".into(), "y".into()].into_iter().collect()
+                ),
+            );
+        }
+
         #[test]
         fn convert_case_with_moved_argument() {
             assert_eq!(
@@ -1568,4 +2231,277 @@ mod tests {
             );
         }
     }
+
+    mod lazy {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn create_lazy(body: impl Into<Expression>) -> Lazy {
+            Lazy::with_definition(Definition::with_options(
+                "lazy",
+                vec![],
+                vec![Argument::new("x", Type::ByteString)],
+                vec![Argument::new("lazy'", types::Tuple::new(vec![]))],
+                body,
+                Type::ByteString,
+                true,
+            ))
+        }
+
+        #[test]
+        fn convert_with_moved_environment() {
+            let tuple_type = types::Tuple::new(vec![
+                types::Lazy::new(Type::ByteString).into(),
+                Type::ByteString,
+            ]);
+
+            assert_eq!(
+                convert_expression(
+                    &Record::new(
+                        tuple_type.clone(),
+                        vec![
+                            create_lazy(Variable::new("x")).into(),
+                            Variable::new("x").into()
+                        ]
+                    )
+                    .into(),
+                    &vec![("x".into(), Type::ByteString)].into_iter().collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (
+                    Record::new(
+                        tuple_type,
+                        vec![
+                            CloneVariables::new(
+                                vec![("x".into(), Type::ByteString)].into_iter().collect(),
+                                create_lazy(DropVariables::new(
+                                    vec![
+                                        (
+                                            "lazy".into(),
+                                            types::Function::new(
+                                                types::Tuple::new(vec![]),
+                                                Type::ByteString
+                                            )
+                                            .into()
+                                        ),
+                                        ("lazy'".into(), types::Tuple::new(vec![]).into())
+                                    ]
+                                    .into_iter()
+                                    .collect(),
+                                    Variable::new("x")
+                                ))
+                            )
+                            .into(),
+                            Variable::new("x").into()
+                        ]
+                    )
+                    .into(),
+                    vec!["x".into()].into_iter().collect()
+                )
+            );
+        }
+
+        #[test]
+        fn convert_force() {
+            let expression = Expression::from(Force::new(Variable::new("x")));
+
+            assert_eq!(
+                convert_expression(
+                    &expression,
+                    &vec![("x".into(), types::Lazy::new(Type::Number).into())]
+                        .into_iter()
+                        .collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (expression, vec!["x".into()].into_iter().collect())
+            );
+        }
+    }
+
+    mod try_operation {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn convert_with_moved_payload() {
+            let expression = Expression::from(TryOperation::new(
+                Variable::new("x"),
+                "e",
+                Type::ByteString,
+                Variant::new(Type::ByteString, Variable::new("e")),
+            ));
+
+            assert_eq!(
+                convert_expression(
+                    &expression,
+                    &vec![("x".into(), Type::Variant)].into_iter().collect(),
+                    &Default::default()
+                )
+                .unwrap(),
+                (expression, vec!["x".into()].into_iter().collect())
+            );
+        }
+
+        #[test]
+        fn convert_with_dropped_payload() {
+            assert_eq!(
+                convert_expression(
+                    &TryOperation::new(
+                        Variable::new("x"),
+                        "e",
+                        Type::ByteString,
+                        Variant::new(Type::Number, 42.0)
+                    )
+                    .into(),
+                    &vec![("x".into(), Type::Variant)].into_iter().collect(),
+                    &Default::default()
+                )
+                .unwrap()
+                .0,
+                TryOperation::new(
+                    Variable::new("x"),
+                    "e",
+                    Type::ByteString,
+                    DropVariables::new(
+                        vec![("e".into(), Type::ByteString)].into_iter().collect(),
+                        Variant::new(Type::Number, 42.0)
+                    )
+                )
+                .into()
+            );
+        }
+
+        #[test]
+        fn convert_with_variable_dropped_on_error_path() {
+            assert_eq!(
+                convert_expression(
+                    &TryOperation::new(
+                        Variable::new("x"),
+                        "e",
+                        Type::ByteString,
+                        Variant::new(Type::ByteString, Variable::new("e")),
+                    )
+                    .into(),
+                    &vec![("x".into(), Type::Variant), ("y".into(), Type::ByteString)]
+                        .into_iter()
+                        .collect(),
+                    &vec!["y".into()].into_iter().collect(),
+                )
+                .unwrap(),
+                (
+                    TryOperation::new(
+                        Variable::new("x"),
+                        "e",
+                        Type::ByteString,
+                        DropVariables::new(
+                            vec![("y".into(), Type::ByteString)].into_iter().collect(),
+                            Variant::new(Type::ByteString, Variable::new("e"))
+                        ),
+                    )
+                    .into(),
+                    vec!["x".into(), "y".into()].into_iter().collect()
+                )
+            );
+        }
+
+        #[test]
+        fn convert_with_variable_dropped_on_success_path() {
+            assert_eq!(
+                convert_expression(
+                    &TryOperation::new(
+                        Variable::new("x"),
+                        "e",
+                        Type::ByteString,
+                        Variant::new(
+                            Type::ByteString,
+                            ByteStringConcatenation::new(Variable::new("y"), Variable::new("e"))
+                        ),
+                    )
+                    .into(),
+                    &vec![("x".into(), Type::Variant), ("y".into(), Type::ByteString)]
+                        .into_iter()
+                        .collect(),
+                    &Default::default(),
+                )
+                .unwrap(),
+                (
+                    Let::new(
+                        "e",
+                        Type::Variant,
+                        TryOperation::new(
+                            Variable::new("x"),
+                            "e",
+                            Type::ByteString,
+                            Variant::new(
+                                Type::ByteString,
+                                ByteStringConcatenation::new(
+                                    Variable::new("y"),
+                                    Variable::new("e")
+                                )
+                            ),
+                        ),
+                        DropVariables::new(
+                            vec![("y".into(), Type::ByteString)].into_iter().collect(),
+                            Variable::new("e")
+                        )
+                    )
+                    .into(),
+                    vec!["x".into(), "y".into()].into_iter().collect()
+                )
+            );
+        }
+
+        #[test]
+        fn convert_with_variable_moved_after_let_bound_try_operation() {
+            let record_type = types::Record::new("r");
+
+            assert_eq!(
+                convert_expression(
+                    &Let::new(
+                        "y",
+                        Type::Variant,
+                        TryOperation::new(
+                            Variable::new("v"),
+                            "e",
+                            Type::ByteString,
+                            Variant::new(Type::ByteString, Variable::new("e")),
+                        ),
+                        Record::new(
+                            record_type.clone(),
+                            vec![Variable::new("x").into(), Variable::new("y").into()]
+                        )
+                    )
+                    .into(),
+                    &vec![("v".into(), Type::Variant), ("x".into(), Type::ByteString)]
+                        .into_iter()
+                        .collect(),
+                    &Default::default(),
+                )
+                .unwrap(),
+                (
+                    Let::new(
+                        "y",
+                        Type::Variant,
+                        TryOperation::new(
+                            Variable::new("v"),
+                            "e",
+                            Type::ByteString,
+                            DropVariables::new(
+                                vec![("x".into(), Type::ByteString)].into_iter().collect(),
+                                Variant::new(Type::ByteString, Variable::new("e"))
+                            ),
+                        ),
+                        Record::new(
+                            record_type,
+                            vec![Variable::new("x").into(), Variable::new("y").into()]
+                        )
+                    )
+                    .into(),
+                    vec!["v".into(), "x".into()].into_iter().collect()
+                )
+            );
+        }
+    }
 }
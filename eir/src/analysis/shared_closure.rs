@@ -0,0 +1,217 @@
+use super::free_variables::find_free_variables;
+use crate::ir::*;
+use std::collections::HashMap;
+
+// Checks if definitions in a recursive group can share closures with their
+// callers. Definitions in a group have the same environments. So they can if
+// they refer to the group only by applying its definitions to at least as many
+// arguments as they take and never capture or shadow them.
+pub fn can_share_closure(definitions: &[Definition]) -> bool {
+    let mut checker = ReferenceChecker {
+        arities: definitions
+            .iter()
+            .map(|definition| (definition.name(), definition.arguments().len()))
+            .collect(),
+        applied_only: true,
+    };
+
+    for definition in definitions {
+        for argument in definition.arguments() {
+            checker.check_name(argument.name());
+        }
+
+        checker.visit_expression(definition.body());
+    }
+
+    checker.applied_only
+}
+
+struct ReferenceChecker<'a> {
+    arities: HashMap<&'a str, usize>,
+    applied_only: bool,
+}
+
+impl ReferenceChecker<'_> {
+    // Names of the group are allowed only as functions of applications.
+    fn check_name(&mut self, name: &str) {
+        if self.arities.contains_key(name) {
+            self.applied_only = false;
+        }
+    }
+
+    fn bind_alternatives(
+        &mut self,
+        alternatives: &[Alternative],
+        default_alternative: Option<&DefaultAlternative>,
+    ) {
+        for alternative in alternatives {
+            self.check_name(alternative.name());
+        }
+
+        if let Some(alternative) = default_alternative {
+            self.check_name(alternative.name());
+        }
+    }
+}
+
+impl Visitor for ReferenceChecker<'_> {
+    // Definitions nested in the group capture the group as values.
+    fn visit_definition(&mut self, definition: &Definition) {
+        self.check_name(definition.name());
+
+        for argument in definition.arguments() {
+            self.check_name(argument.name());
+        }
+
+        for name in find_free_variables(definition.body()) {
+            self.check_name(&name);
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Case(case) => {
+                self.bind_alternatives(case.alternatives(), case.default_alternative());
+                walk_expression(self, expression);
+            }
+            Expression::FunctionApplication(application) => {
+                let arguments = application.arguments().into_iter().collect::<Vec<_>>();
+
+                match application.first_function() {
+                    Expression::Variable(variable)
+                        if self
+                            .arities
+                            .get(variable.name())
+                            .map(|&arity| arity <= arguments.len())
+                            .unwrap_or_default() =>
+                    {
+                        for argument in arguments {
+                            self.visit_expression(argument);
+                        }
+                    }
+                    _ => walk_expression(self, expression),
+                }
+            }
+            Expression::Let(let_) => {
+                self.check_name(let_.name());
+                walk_expression(self, expression);
+            }
+            Expression::LetRecord(let_) => {
+                for element in let_.elements() {
+                    self.check_name(element.name());
+                }
+
+                walk_expression(self, expression);
+            }
+            Expression::LiteralCase(case) => {
+                self.bind_alternatives(&[], case.default_alternative());
+                walk_expression(self, expression);
+            }
+            Expression::SumCase(case) => {
+                self.bind_alternatives(case.alternatives(), case.default_alternative());
+                walk_expression(self, expression);
+            }
+            Expression::TryOperation(operation) => {
+                self.check_name(operation.name());
+                walk_expression(self, expression);
+            }
+            Expression::Variable(variable) => self.check_name(variable.name()),
+            _ => walk_expression(self, expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{self, Type};
+
+    fn create_function_type() -> types::Function {
+        types::Function::new(Type::Number, Type::Number)
+    }
+
+    fn create_definition(name: &str, body: impl Into<Expression>) -> Definition {
+        Definition::new(
+            name,
+            vec![Argument::new("x", Type::Number)],
+            body,
+            Type::Number,
+        )
+    }
+
+    fn create_application(name: &str) -> FunctionApplication {
+        FunctionApplication::new(
+            create_function_type(),
+            Variable::new(name),
+            Variable::new("x"),
+        )
+    }
+
+    #[test]
+    fn share_closure_of_applied_definitions() {
+        assert!(can_share_closure(&[
+            create_definition("f", create_application("g")),
+            create_definition(
+                "g",
+                If::new(true, Variable::new("x"), create_application("f"))
+            ),
+        ]));
+    }
+
+    #[test]
+    fn share_closure_with_other_functions_applied() {
+        assert!(can_share_closure(&[create_definition(
+            "f",
+            FunctionApplication::new(
+                create_function_type(),
+                Variable::new("h"),
+                create_application("f")
+            )
+        )]));
+    }
+
+    #[test]
+    fn do_not_share_closure_of_definition_used_as_value() {
+        assert!(!can_share_closure(&[create_definition(
+            "f",
+            FunctionApplication::new(
+                types::Function::new(create_function_type(), Type::Number),
+                Variable::new("h"),
+                Variable::new("f")
+            )
+        )]));
+    }
+
+    #[test]
+    fn do_not_share_closure_of_partially_applied_definition() {
+        assert!(!can_share_closure(&[Definition::new(
+            "f",
+            vec![
+                Argument::new("x", Type::Number),
+                Argument::new("y", Type::Number)
+            ],
+            FunctionApplication::new(
+                types::Function::new(Type::Number, create_function_type()),
+                Variable::new("f"),
+                Variable::new("x")
+            ),
+            create_function_type(),
+        )]));
+    }
+
+    #[test]
+    fn do_not_share_closure_of_definition_captured_by_lazy_value() {
+        assert!(!can_share_closure(&[create_definition(
+            "f",
+            Force::new(Lazy::new(Type::Number, create_application("f")))
+        )]));
+    }
+
+    #[test]
+    fn do_not_share_closure_of_shadowed_definition() {
+        assert!(!can_share_closure(&[create_definition(
+            "f",
+            Let::new("f", Type::Number, 42.0, Variable::new("x"))
+        )]));
+    }
+}
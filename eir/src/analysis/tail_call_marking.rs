@@ -0,0 +1,309 @@
+use crate::ir::*;
+
+// Marks function applications in tail positions so that backends can compile
+// them into tail calls. Tail positions are bodies of definitions, branches of
+// if and case expressions in tail positions, bodies of let expressions and
+// reference count operations in tail positions, and `then` expressions of try
+// operations. This needs to run after the other transformations as they do not
+// keep marks.
+pub fn mark_tail_calls(module: &Module) -> Module {
+    TailCallMarker { tail: false }.fold_module(module)
+}
+
+struct TailCallMarker {
+    tail: bool,
+}
+
+impl TailCallMarker {
+    fn fold_with_tail(&mut self, expression: &Expression, tail: bool) -> Expression {
+        let outer = self.tail;
+
+        self.tail = tail;
+        let expression = self.fold_expression(expression);
+        self.tail = outer;
+
+        expression
+    }
+
+    fn fold_non_tail(&mut self, expression: &Expression) -> Expression {
+        self.fold_with_tail(expression, false)
+    }
+}
+
+impl Fold for TailCallMarker {
+    fn fold_definition(&mut self, definition: &Definition) -> Definition {
+        let outer = self.tail;
+
+        self.tail = true;
+        let definition = fold_definition_children(self, definition);
+        self.tail = outer;
+
+        definition
+    }
+
+    fn fold_expression(&mut self, expression: &Expression) -> Expression {
+        match expression {
+            Expression::FunctionApplication(application) => FunctionApplication::with_options(
+                application.type_().clone(),
+                self.fold_non_tail(application.function()),
+                application.type_arguments().to_vec(),
+                self.fold_non_tail(application.argument()),
+                self.tail,
+            )
+            .into(),
+            // Try operations return values of `then` expressions from functions
+            // wherever they are.
+            Expression::TryOperation(operation) => TryOperation::new(
+                self.fold_non_tail(operation.operand()),
+                operation.name(),
+                operation.type_().clone(),
+                self.fold_with_tail(operation.then(), true),
+            )
+            .into(),
+            // Outside tail positions, no children are in tail positions either.
+            _ if !self.tail => fold_expression_children(self, expression),
+            Expression::Case(case) => Case::new(
+                self.fold_non_tail(case.argument()),
+                fold_alternatives(self, case.alternatives()),
+                fold_default_alternative(self, case.default_alternative()),
+            )
+            .into(),
+            Expression::If(if_) => If::new(
+                self.fold_non_tail(if_.condition()),
+                self.fold_expression(if_.then()),
+                self.fold_expression(if_.else_()),
+            )
+            .into(),
+            Expression::Let(let_) => Let::new(
+                let_.name(),
+                let_.type_().clone(),
+                self.fold_non_tail(let_.bound_expression()),
+                self.fold_expression(let_.expression()),
+            )
+            .into(),
+            Expression::LetRecord(let_) => LetRecord::new(
+                let_.type_().clone(),
+                let_.elements().to_vec(),
+                self.fold_non_tail(let_.bound_expression()),
+                self.fold_expression(let_.expression()),
+            )
+            .into(),
+            Expression::LiteralCase(case) => LiteralCase::new(
+                case.type_().clone(),
+                self.fold_non_tail(case.argument()),
+                case.alternatives()
+                    .iter()
+                    .map(|alternative| {
+                        LiteralAlternative::new(
+                            alternative.literal().clone(),
+                            self.fold_expression(alternative.expression()),
+                        )
+                    })
+                    .collect(),
+                fold_default_alternative(self, case.default_alternative()),
+            )
+            .into(),
+            Expression::SumCase(case) => SumCase::new(
+                case.type_().clone(),
+                self.fold_non_tail(case.argument()),
+                fold_alternatives(self, case.alternatives()),
+                fold_default_alternative(self, case.default_alternative()),
+            )
+            .into(),
+            // Bodies of reference count operations and let-recursive
+            // expressions are their only children in tail positions.
+            Expression::CloneVariables(_)
+            | Expression::DropVariables(_)
+            | Expression::LetRecursive(_) => fold_expression_children(self, expression),
+            _ => {
+                self.tail = false;
+                let expression = fold_expression_children(self, expression);
+                self.tail = true;
+
+                expression
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{self, Type};
+    use pretty_assertions::assert_eq;
+
+    fn create_function_type() -> types::Function {
+        types::Function::new(Type::Number, Type::Number)
+    }
+
+    fn create_application(argument: impl Into<Expression>) -> FunctionApplication {
+        FunctionApplication::new(create_function_type(), Variable::new("f"), argument)
+    }
+
+    fn create_tail_application(argument: impl Into<Expression>) -> FunctionApplication {
+        FunctionApplication::with_options(
+            create_function_type(),
+            Variable::new("f"),
+            vec![],
+            argument,
+            true,
+        )
+    }
+
+    fn mark_in_body(body: impl Into<Expression>) -> Expression {
+        mark_tail_calls(&Module::new(
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                body,
+                Type::Number,
+            )],
+        ))
+        .definitions()[0]
+            .body()
+            .clone()
+    }
+
+    #[test]
+    fn mark_call_in_body() {
+        assert_eq!(
+            mark_in_body(create_application(create_application(Variable::new("x")))),
+            create_tail_application(create_application(Variable::new("x"))).into()
+        );
+    }
+
+    #[test]
+    fn mark_calls_in_branches() {
+        assert_eq!(
+            mark_in_body(If::new(
+                true,
+                create_application(Variable::new("x")),
+                Let::new(
+                    "y",
+                    Type::Number,
+                    create_application(Variable::new("x")),
+                    DropVariables::new(Default::default(), create_application(Variable::new("y"))),
+                ),
+            )),
+            If::new(
+                true,
+                create_tail_application(Variable::new("x")),
+                Let::new(
+                    "y",
+                    Type::Number,
+                    create_application(Variable::new("x")),
+                    DropVariables::new(
+                        Default::default(),
+                        create_tail_application(Variable::new("y"))
+                    ),
+                ),
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn mark_call_in_case() {
+        assert_eq!(
+            mark_in_body(Case::new(
+                Variable::new("x"),
+                vec![Alternative::new(
+                    Type::Number,
+                    "y",
+                    create_application(Variable::new("y"))
+                )],
+                Some(DefaultAlternative::new("y", 42.0)),
+            )),
+            Case::new(
+                Variable::new("x"),
+                vec![Alternative::new(
+                    Type::Number,
+                    "y",
+                    create_tail_application(Variable::new("y"))
+                )],
+                Some(DefaultAlternative::new("y", 42.0)),
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn do_not_mark_call_in_operand() {
+        let expression = Expression::from(ArithmeticOperation::new(
+            Type::Number,
+            ArithmeticOperator::Add,
+            create_application(Variable::new("x")),
+            1.0,
+        ));
+
+        assert_eq!(mark_in_body(expression.clone()), expression);
+    }
+
+    #[test]
+    fn mark_call_in_local_definition() {
+        assert_eq!(
+            mark_in_body(LetRecursive::new(
+                Definition::new(
+                    "g",
+                    vec![Argument::new("y", Type::Number)],
+                    create_application(Variable::new("y")),
+                    Type::Number,
+                ),
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Add,
+                    create_application(Variable::new("x")),
+                    1.0,
+                ),
+            )),
+            LetRecursive::new(
+                Definition::new(
+                    "g",
+                    vec![Argument::new("y", Type::Number)],
+                    create_tail_application(Variable::new("y")),
+                    Type::Number,
+                ),
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Add,
+                    create_application(Variable::new("x")),
+                    1.0,
+                ),
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn mark_call_in_try_operation() {
+        assert_eq!(
+            mark_in_body(Let::new(
+                "y",
+                Type::Variant,
+                TryOperation::new(
+                    Variable::new("x"),
+                    "e",
+                    Type::Number,
+                    create_application(Variable::new("e")),
+                ),
+                42.0,
+            )),
+            Let::new(
+                "y",
+                Type::Variant,
+                TryOperation::new(
+                    Variable::new("x"),
+                    "e",
+                    Type::Number,
+                    create_tail_application(Variable::new("e")),
+                ),
+                42.0,
+            )
+            .into()
+        );
+    }
+}
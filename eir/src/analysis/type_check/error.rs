@@ -6,22 +6,169 @@ use std::{error::Error, fmt::Display};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum TypeCheckError {
+    ComparableTypeExpected(Type),
+    ConstructorNotFound(types::Sum, types::Record),
+    DuplicateAlternative(types::Sum, types::Record),
+    DuplicateFieldNames(String),
     DuplicateFunctionNames(String),
+    DuplicateSumConstructors(types::Sum, types::Record),
     DuplicateTypeNames(String),
-    ElementIndexOutOfBounds(RecordElement),
+    DuplicateVariableNames(String),
+    ElementIndexOutOfBounds(RecordElement, Vec<String>),
     ForeignDefinitionNotFound(ForeignDefinition),
     FunctionExpected(Expression),
-    NoAlternativeFound(Case),
+    ImpossibleAlternative(types::Sum, Type),
+    InvalidTryOperationPosition(Expression),
+    LazyExpected(Expression),
+    LocalConstant(String),
+    MissingAlternatives(types::Sum, Vec<types::Record>),
+    MissingDefaultAlternative(Expression),
+    NoAlternativeFound(Expression),
+    NumericTypeExpected(Type),
+    PolymorphicConstant(String),
+    PolymorphicFunctionExpected(Expression),
+    PolymorphicLocalDefinition(String),
+    PolymorphicRecursion(String),
+    RecordTypeExpected(Type),
+    SumNotFound(types::Sum),
+    ThunkInRecursiveGroup(String),
     TypeNotFound(types::Record),
+    TypeVariableNotFound(String),
     TypesNotMatched(Type, Type),
     VariableNotFound(Variable),
-    VariantInVariant(Variant),
     WrongElementCount(Expression),
+    WrongTypeArgumentCount(String),
 }
 
 impl Display for TypeCheckError {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(formatter, "{:#?}", self)
+        match self {
+            Self::ComparableTypeExpected(type_) => {
+                write!(formatter, "comparable type expected: {}", type_)
+            }
+            Self::ConstructorNotFound(sum, record) => write!(
+                formatter,
+                "constructor not found in sum {}: {}",
+                sum.name(),
+                record.name()
+            ),
+            Self::DuplicateAlternative(sum, record) => write!(
+                formatter,
+                "duplicate alternative in case of sum {}: {}",
+                sum.name(),
+                record.name()
+            ),
+            Self::DuplicateFieldNames(name) => {
+                write!(formatter, "duplicate field name: {}", name)
+            }
+            Self::DuplicateFunctionNames(name) => {
+                write!(formatter, "duplicate function name: {}", name)
+            }
+            Self::DuplicateSumConstructors(sum, record) => write!(
+                formatter,
+                "duplicate constructor in sum {}: {}",
+                sum.name(),
+                record.name()
+            ),
+            Self::DuplicateTypeNames(name) => write!(formatter, "duplicate type name: {}", name),
+            Self::DuplicateVariableNames(name) => {
+                write!(formatter, "duplicate variable name: {}", name)
+            }
+            Self::ElementIndexOutOfBounds(element, field_names) => {
+                write!(
+                    formatter,
+                    "element index out of bounds: {}",
+                    Expression::from(element.clone())
+                )?;
+
+                if !field_names.is_empty() {
+                    write!(formatter, " (fields: {})", field_names.join(", "))?;
+                }
+
+                Ok(())
+            }
+            Self::ForeignDefinitionNotFound(definition) => write!(
+                formatter,
+                "foreign definition not found: {}",
+                definition.name()
+            ),
+            Self::FunctionExpected(expression) => {
+                write!(formatter, "function expected: {}", expression)
+            }
+            Self::ImpossibleAlternative(sum, type_) => {
+                write!(
+                    formatter,
+                    "impossible alternative of sum {}: {}",
+                    sum.name(),
+                    type_
+                )
+            }
+            Self::InvalidTryOperationPosition(expression) => write!(
+                formatter,
+                "try operation not in let-bound or tail position: {}",
+                expression
+            ),
+            Self::LazyExpected(expression) => {
+                write!(formatter, "lazy value expected: {}", expression)
+            }
+            Self::LocalConstant(name) => write!(formatter, "local constant: {}", name),
+            Self::MissingAlternatives(sum, records) => write!(
+                formatter,
+                "missing alternatives of sum {}: {}",
+                sum.name(),
+                records
+                    .iter()
+                    .map(|record| Type::from(record.clone()).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::MissingDefaultAlternative(expression) => {
+                write!(formatter, "missing default alternative: {}", expression)
+            }
+            Self::NoAlternativeFound(expression) => {
+                write!(formatter, "no alternative found: {}", expression)
+            }
+            Self::NumericTypeExpected(type_) => {
+                write!(formatter, "numeric type expected: {}", type_)
+            }
+            Self::PolymorphicConstant(name) => write!(formatter, "polymorphic constant: {}", name),
+            Self::PolymorphicFunctionExpected(expression) => {
+                write!(formatter, "polymorphic function expected: {}", expression)
+            }
+            Self::PolymorphicLocalDefinition(name) => {
+                write!(formatter, "polymorphic local definition: {}", name)
+            }
+            Self::PolymorphicRecursion(name) => {
+                write!(formatter, "polymorphic recursion: {}", name)
+            }
+            Self::RecordTypeExpected(type_) => {
+                write!(formatter, "record type expected: {}", type_)
+            }
+            Self::SumNotFound(sum) => write!(formatter, "sum not found: {}", sum.name()),
+            Self::ThunkInRecursiveGroup(name) => {
+                write!(
+                    formatter,
+                    "thunk in mutually recursive definitions: {}",
+                    name
+                )
+            }
+            Self::TypeNotFound(record) => write!(formatter, "type not found: {}", record.name()),
+            Self::TypeVariableNotFound(name) => {
+                write!(formatter, "type variable not found: '{}", name)
+            }
+            Self::TypesNotMatched(one, other) => {
+                write!(formatter, "types not matched: {} and {}", one, other)
+            }
+            Self::VariableNotFound(variable) => {
+                write!(formatter, "variable not found: {}", variable.name())
+            }
+            Self::WrongElementCount(expression) => {
+                write!(formatter, "wrong element count: {}", expression)
+            }
+            Self::WrongTypeArgumentCount(name) => {
+                write!(formatter, "wrong type argument count: {}", name)
+            }
+        }
     }
 }
 
@@ -1,6 +1,9 @@
 mod error;
 mod names;
+mod recursion;
+mod try_operation;
 
+use super::type_substitution::{create_substitutions, substitute_record_body, substitute_type};
 use crate::{
     ir::*,
     types::{self, Type},
@@ -14,41 +17,100 @@ pub fn check_types(module: &Module) -> Result<(), TypeCheckError> {
     let types = module
         .type_definitions()
         .iter()
-        .map(|definition| (definition.name(), definition.type_()))
+        .map(|definition| (definition.name(), definition))
+        .collect();
+    // Polymorphic definitions are referred to only by function applications
+    // with type arguments.
+    let functions = module
+        .definitions()
+        .iter()
+        .filter(|definition| !definition.type_parameters().is_empty())
+        .map(|definition| (definition.name(), definition))
+        .collect();
+    let sums = module
+        .sum_definitions()
+        .iter()
+        .map(|definition| (definition.name(), definition))
         .collect();
     let mut variables = HashMap::<&str, Type>::new();
 
+    for definition in module.type_definitions() {
+        for type_ in definition.type_().elements() {
+            check_type_variables(type_, definition.parameters())?;
+        }
+    }
+
+    for definition in module.sum_definitions() {
+        for record in definition.constructors() {
+            let type_ = record.clone().into();
+
+            check_type_variables(&type_, &[])?;
+            check_record_body(&type_, &types)?;
+        }
+    }
+
     for declaration in module.foreign_declarations() {
+        check_type_variables(&declaration.type_().clone().into(), &[])?;
         variables.insert(declaration.name(), declaration.type_().clone().into());
     }
 
     for declaration in module.declarations() {
+        check_type_variables(&declaration.type_().clone().into(), &[])?;
         variables.insert(declaration.name(), declaration.type_().clone().into());
     }
 
     for definition in module.definitions() {
-        variables.insert(definition.name(), definition.type_().clone().into());
+        if definition.is_constant() {
+            if !definition.type_parameters().is_empty() {
+                return Err(TypeCheckError::PolymorphicConstant(
+                    definition.name().into(),
+                ));
+            }
+
+            // Constants are referred to as values of their result types.
+            variables.insert(definition.name(), definition.result_type().clone());
+        } else if definition.type_parameters().is_empty() {
+            variables.insert(definition.name(), definition.type_().clone().into());
+        }
     }
 
     for definition in module.definitions() {
-        check_definition(definition, &variables, &types)?;
+        check_type_variables(
+            &definition.type_().clone().into(),
+            definition.type_parameters(),
+        )?;
+        check_definition(definition, &variables, &functions, &types, &sums)?;
     }
 
+    recursion::check_polymorphic_recursion(module)?;
+
     for definition in module.foreign_definitions() {
         if !variables.contains_key(definition.name()) {
             return Err(TypeCheckError::ForeignDefinitionNotFound(
                 definition.clone(),
             ));
+        } else if module
+            .definitions()
+            .iter()
+            .any(|other| other.name() == definition.name() && other.is_constant())
+        {
+            return Err(TypeCheckError::FunctionExpected(
+                Variable::new(definition.name()).into(),
+            ));
         }
     }
 
+    try_operation::check_try_operation_positions(module)?;
+
     Ok(())
 }
 
 fn check_definition(
     definition: &Definition,
     variables: &HashMap<&str, Type>,
-    types: &HashMap<&str, &types::RecordBody>,
+    functions: &HashMap<&str, &Definition>,
+    types: &HashMap<&str, &TypeDefinition>,
+    sums: &HashMap<&str, &SumDefinition>,
 ) -> Result<(), TypeCheckError> {
     let mut variables = variables.clone();
 
@@ -61,7 +123,14 @@ fn check_definition(
     }
 
     check_equality(
-        &check_expression(definition.body(), &variables, types)?,
+        &check_expression(
+            definition.body(),
+            &variables,
+            definition.result_type(),
+            functions,
+            types,
+            sums,
+        )?,
         &definition.result_type().clone(),
     )
 }
@@ -69,25 +138,79 @@ fn check_definition(
 fn check_expression(
     expression: &Expression,
     variables: &HashMap<&str, Type>,
-    types: &HashMap<&str, &types::RecordBody>,
+    result_type: &Type,
+    functions: &HashMap<&str, &Definition>,
+    types: &HashMap<&str, &TypeDefinition>,
+    sums: &HashMap<&str, &SumDefinition>,
 ) -> Result<Type, TypeCheckError> {
-    let check_expression = |expression, variables| check_expression(expression, variables, types);
+    let check_expression = |expression, variables| {
+        check_expression(expression, variables, result_type, functions, types, sums)
+    };
 
     Ok(match expression {
         Expression::ArithmeticOperation(operation) => {
+            check_numeric_type(operation.type_())?;
             check_equality(
                 &check_expression(operation.lhs(), variables)?,
-                &Type::Number,
+                operation.type_(),
             )?;
             check_equality(
                 &check_expression(operation.rhs(), variables)?,
-                &Type::Number,
+                operation.type_(),
             )?;
 
-            Type::Number
+            operation.type_().clone()
         }
         Expression::Boolean(_) => Type::Boolean,
-        Expression::Case(case) => check_case(case, variables, types)?,
+        Expression::BooleanOperation(operation) => {
+            check_equality(
+                &check_expression(operation.lhs(), variables)?,
+                &Type::Boolean,
+            )?;
+            check_equality(
+                &check_expression(operation.rhs(), variables)?,
+                &Type::Boolean,
+            )?;
+
+            Type::Boolean
+        }
+        Expression::ByteStringConcatenation(concatenation) => {
+            check_equality(
+                &check_expression(concatenation.lhs(), variables)?,
+                &Type::ByteString,
+            )?;
+            check_equality(
+                &check_expression(concatenation.rhs(), variables)?,
+                &Type::ByteString,
+            )?;
+
+            Type::ByteString
+        }
+        Expression::ByteStringLength(length) => {
+            check_equality(
+                &check_expression(length.string(), variables)?,
+                &Type::ByteString,
+            )?;
+
+            types::Integer::Unsigned64.into()
+        }
+        Expression::ByteStringSlice(slice) => {
+            check_equality(
+                &check_expression(slice.string(), variables)?,
+                &Type::ByteString,
+            )?;
+            check_equality(
+                &check_expression(slice.start(), variables)?,
+                &types::Integer::Unsigned64.into(),
+            )?;
+            check_equality(
+                &check_expression(slice.end(), variables)?,
+                &types::Integer::Unsigned64.into(),
+            )?;
+
+            Type::ByteString
+        }
+        Expression::Case(case) => check_case(case, variables, result_type, functions, types, sums)?,
         Expression::CloneVariables(clone) => {
             for (variable, type_) in clone.variables() {
                 check_equality(&check_variable(&Variable::new(variable), variables)?, type_)?;
@@ -96,13 +219,14 @@ fn check_expression(
             check_expression(clone.expression(), variables)?
         }
         Expression::ComparisonOperation(operation) => {
+            check_comparable_type(operation.type_())?;
             check_equality(
                 &check_expression(operation.lhs(), variables)?,
-                &Type::Number,
+                operation.type_(),
             )?;
             check_equality(
                 &check_expression(operation.rhs(), variables)?,
-                &Type::Number,
+                operation.type_(),
             )?;
 
             Type::Boolean
@@ -114,10 +238,18 @@ fn check_expression(
 
             check_expression(drop.expression(), variables)?
         }
+        Expression::Force(force) => match check_expression(force.expression(), variables)? {
+            Type::Lazy(lazy) => lazy.type_().clone(),
+            _ => return Err(TypeCheckError::LazyExpected(force.expression().clone())),
+        },
         Expression::FunctionApplication(application) => {
-            let function_type = check_expression(application.function(), variables)?
-                .into_function()
-                .ok_or_else(|| TypeCheckError::FunctionExpected(application.function().clone()))?;
+            let function_type = if application.type_arguments().is_empty() {
+                check_expression(application.function(), variables)?
+            } else {
+                check_polymorphic_function(application, variables, functions)?
+            }
+            .into_function()
+            .ok_or_else(|| TypeCheckError::FunctionExpected(application.function().clone()))?;
 
             check_equality(
                 &application.type_().clone().into(),
@@ -143,17 +275,54 @@ fn check_expression(
 
             then
         }
+        Expression::Integer(integer) => integer.type_().into(),
+        Expression::Lazy(lazy) => {
+            let definition = lazy.definition();
+            let mut variables = variables.clone();
+            variables.insert(definition.name(), definition.type_().clone().into());
+
+            check_definition(definition, &variables, functions, types, sums)?;
+
+            types::Lazy::new(lazy.type_().clone()).into()
+        }
         Expression::LetRecursive(let_) => {
+            let mut names = HashSet::new();
+
+            for definition in let_.definitions() {
+                if !definition.type_parameters().is_empty() {
+                    return Err(TypeCheckError::PolymorphicLocalDefinition(
+                        definition.name().into(),
+                    ));
+                } else if definition.is_constant() {
+                    return Err(TypeCheckError::LocalConstant(definition.name().into()));
+                } else if !names.insert(definition.name()) {
+                    return Err(TypeCheckError::DuplicateFunctionNames(
+                        definition.name().into(),
+                    ));
+                } else if definition.is_thunk() && let_.definitions().len() > 1 {
+                    // Siblings either share closures of their callers or get
+                    // new ones on every call. So thunks in groups would be
+                    // evaluated more than once.
+                    return Err(TypeCheckError::ThunkInRecursiveGroup(
+                        definition.name().into(),
+                    ));
+                }
+            }
+
             let variables = variables
                 .clone()
                 .into_iter()
-                .chain(vec![(
-                    let_.definition().name(),
-                    let_.definition().type_().clone().into(),
-                )])
+                .chain(
+                    let_.definitions()
+                        .iter()
+                        .map(|definition| (definition.name(), definition.type_().clone().into())),
+                )
                 .collect();
 
-            check_definition(let_.definition(), &variables, types)?;
+            for definition in let_.definitions() {
+                check_definition(definition, &variables, functions, types, sums)?;
+            }
+
             check_expression(let_.expression(), &variables)?
         }
         Expression::Let(let_) => {
@@ -167,43 +336,164 @@ fn check_expression(
 
             check_expression(let_.expression(), &variables)?
         }
+        Expression::LetRecord(let_) => {
+            check_equality(
+                &check_expression(let_.bound_expression(), variables)?,
+                let_.type_(),
+            )?;
+
+            let body = check_record_body(let_.type_(), types)?;
+
+            if let_.elements().len() != body.elements().len() {
+                return Err(TypeCheckError::WrongElementCount(expression.clone()));
+            }
+
+            let mut variables = variables.clone();
+            let mut names = HashSet::new();
+
+            for (element, type_) in let_.elements().iter().zip(body.elements()) {
+                check_equality(element.type_(), type_)?;
+
+                if !names.insert(element.name()) {
+                    return Err(TypeCheckError::DuplicateVariableNames(
+                        element.name().into(),
+                    ));
+                }
+
+                variables.insert(element.name(), element.type_().clone());
+            }
+
+            check_expression(let_.expression(), &variables)?
+        }
+        Expression::LiteralCase(case) => {
+            check_literal_case(case, variables, result_type, functions, types, sums)?
+        }
+        Expression::Negation(negation) => {
+            check_numeric_type(negation.type_())?;
+            check_equality(
+                &check_expression(negation.expression(), variables)?,
+                negation.type_(),
+            )?;
+
+            negation.type_().clone()
+        }
+        Expression::Not(not) => {
+            check_equality(
+                &check_expression(not.expression(), variables)?,
+                &Type::Boolean,
+            )?;
+
+            Type::Boolean
+        }
         Expression::Number(_) => Type::Number,
+        Expression::NumericConversion(conversion) => {
+            check_numeric_type(conversion.from())?;
+            check_numeric_type(conversion.to())?;
+            check_equality(
+                &check_expression(conversion.argument(), variables)?,
+                conversion.from(),
+            )?;
+
+            conversion.to().clone()
+        }
         Expression::Record(record) => {
-            let record_type = types
-                .get(record.type_().name())
-                .ok_or_else(|| TypeCheckError::TypeNotFound(record.type_().clone()))?;
+            let body = check_record_body(record.type_(), types)?;
+            let element_types = body.elements();
 
-            if record.elements().len() != record_type.elements().len() {
+            if record.elements().len() != element_types.len() {
                 return Err(TypeCheckError::WrongElementCount(expression.clone()));
             }
 
-            for (element, element_type) in record.elements().iter().zip(record_type.elements()) {
+            for (element, element_type) in record.elements().iter().zip(element_types) {
                 check_equality(&check_expression(element, variables)?, element_type)?;
             }
 
-            record.type_().clone().into()
+            record.type_().clone()
         }
         Expression::RecordElement(element) => {
             check_equality(
                 &check_expression(element.record(), variables)?,
-                &element.type_().clone().into(),
+                element.type_(),
             )?;
 
-            types
-                .get(element.type_().name())
-                .ok_or_else(|| TypeCheckError::TypeNotFound(element.type_().clone()))?
-                .elements()
-                .get(element.index())
-                .ok_or_else(|| TypeCheckError::ElementIndexOutOfBounds(element.clone()))?
-                .clone()
+            let body = check_record_body(element.type_(), types)?;
+
+            body.elements()[check_field_index(element, &body)?].clone()
+        }
+        Expression::RecordUpdate(update) => {
+            check_equality(
+                &check_expression(update.record(), variables)?,
+                update.type_(),
+            )?;
+
+            let body = check_record_body(update.type_(), types)?;
+            let mut indices = HashSet::new();
+
+            for field in update.fields() {
+                let index = check_field_index(
+                    &RecordElement::with_field(
+                        update.type_().clone(),
+                        field.field().clone(),
+                        update.record().clone(),
+                    ),
+                    &body,
+                )?;
+
+                if !indices.insert(index) {
+                    return Err(TypeCheckError::DuplicateFieldNames(
+                        body.field_names()
+                            .map(|names| names[index].clone())
+                            .unwrap_or_else(|| index.to_string()),
+                    ));
+                }
+
+                check_equality(
+                    &check_expression(field.expression(), variables)?,
+                    &body.elements()[index],
+                )?;
+            }
+
+            update.type_().clone()
         }
         Expression::ByteString(_) => Type::ByteString,
-        Expression::Variable(variable) => check_variable(variable, variables)?,
-        Expression::Variant(variant) => {
-            if matches!(variant.type_(), Type::Variant) {
-                return Err(TypeCheckError::VariantInVariant(variant.clone()));
+        Expression::Sum(sum) => {
+            let definition = check_sum_definition(sum.type_(), sums)?;
+
+            if !definition.constructors().contains(sum.constructor()) {
+                return Err(TypeCheckError::ConstructorNotFound(
+                    sum.type_().clone(),
+                    sum.constructor().clone(),
+                ));
             }
 
+            check_equality(
+                &check_expression(sum.payload(), variables)?,
+                &sum.constructor().clone().into(),
+            )?;
+
+            sum.type_().clone().into()
+        }
+        Expression::SumCase(case) => {
+            check_sum_case(case, variables, result_type, functions, types, sums)?
+        }
+        Expression::TryOperation(operation) => {
+            check_equality(
+                &check_expression(operation.operand(), variables)?,
+                &Type::Variant,
+            )?;
+
+            let mut variables = variables.clone();
+            variables.insert(operation.name(), operation.type_().clone());
+
+            check_equality(
+                &check_expression(operation.then(), &variables)?,
+                result_type,
+            )?;
+
+            Type::Variant
+        }
+        Expression::Variable(variable) => check_variable(variable, variables)?,
+        Expression::Variant(variant) => {
             check_equality(
                 &check_expression(variant.payload(), variables)?,
                 variant.type_(),
@@ -217,10 +507,13 @@ fn check_expression(
 fn check_case(
     case: &Case,
     variables: &HashMap<&str, Type>,
-    types: &HashMap<&str, &types::RecordBody>,
+    result_type: &Type,
+    functions: &HashMap<&str, &Definition>,
+    types: &HashMap<&str, &TypeDefinition>,
+    sums: &HashMap<&str, &SumDefinition>,
 ) -> Result<Type, TypeCheckError> {
     let check_expression = |expression: &Expression, variables: &HashMap<&str, Type>| {
-        check_expression(expression, variables, types)
+        check_expression(expression, variables, result_type, functions, types, sums)
     };
 
     check_equality(
@@ -228,9 +521,153 @@ fn check_case(
         &Type::Variant,
     )?;
 
-    let mut expression_type = None;
+    check_alternatives(
+        case.alternatives(),
+        case.default_alternative(),
+        &Type::Variant,
+        variables,
+        &check_expression,
+    )?
+    .ok_or_else(|| TypeCheckError::NoAlternativeFound(case.clone().into()))
+}
+
+// Literal cases are exhaustive without default alternatives only if they
+// cover both booleans.
+fn check_literal_case(
+    case: &LiteralCase,
+    variables: &HashMap<&str, Type>,
+    result_type: &Type,
+    functions: &HashMap<&str, &Definition>,
+    types: &HashMap<&str, &TypeDefinition>,
+    sums: &HashMap<&str, &SumDefinition>,
+) -> Result<Type, TypeCheckError> {
+    let check_expression = |expression: &Expression, variables: &HashMap<&str, Type>| {
+        check_expression(expression, variables, result_type, functions, types, sums)
+    };
+
+    check_comparable_type(case.type_())?;
+    check_equality(&check_expression(case.argument(), variables)?, case.type_())?;
+
+    for alternative in case.alternatives() {
+        check_equality(&alternative.literal().type_(), case.type_())?;
+    }
+
+    if case.default_alternative().is_none()
+        && ![true, false].iter().all(|&boolean| {
+            case.alternatives()
+                .iter()
+                .any(|alternative| alternative.literal() == &Literal::Boolean(boolean))
+        })
+    {
+        return Err(TypeCheckError::MissingDefaultAlternative(
+            case.clone().into(),
+        ));
+    }
+
+    let alternative_types = case
+        .alternatives()
+        .iter()
+        .map(|alternative| check_expression(alternative.expression(), variables))
+        .chain(
+            check_alternatives(
+                &[],
+                case.default_alternative(),
+                case.type_(),
+                variables,
+                &check_expression,
+            )
+            .transpose(),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+    let (expression_type, other_types) = alternative_types
+        .split_first()
+        .ok_or_else(|| TypeCheckError::NoAlternativeFound(case.clone().into()))?;
+
+    for type_ in other_types {
+        check_equality(type_, expression_type)?;
+    }
+
+    Ok(expression_type.clone())
+}
+
+// Sums are closed, so alternatives must cover all of their constructors
+// unless default alternatives are given.
+fn check_sum_case(
+    case: &SumCase,
+    variables: &HashMap<&str, Type>,
+    result_type: &Type,
+    functions: &HashMap<&str, &Definition>,
+    types: &HashMap<&str, &TypeDefinition>,
+    sums: &HashMap<&str, &SumDefinition>,
+) -> Result<Type, TypeCheckError> {
+    let check_expression = |expression: &Expression, variables: &HashMap<&str, Type>| {
+        check_expression(expression, variables, result_type, functions, types, sums)
+    };
+
+    let definition = check_sum_definition(case.type_(), sums)?;
+
+    check_equality(
+        &check_expression(case.argument(), variables)?,
+        &case.type_().clone().into(),
+    )?;
+
+    let mut constructors = HashSet::new();
 
     for alternative in case.alternatives() {
+        match alternative.type_() {
+            Type::Record(record) if definition.constructors().contains(record) => {
+                if !constructors.insert(record) {
+                    return Err(TypeCheckError::DuplicateAlternative(
+                        case.type_().clone(),
+                        record.clone(),
+                    ));
+                }
+            }
+            _ => {
+                return Err(TypeCheckError::ImpossibleAlternative(
+                    case.type_().clone(),
+                    alternative.type_().clone(),
+                ))
+            }
+        }
+    }
+
+    if case.default_alternative().is_none() {
+        let missing_constructors = definition
+            .constructors()
+            .iter()
+            .filter(|record| !constructors.contains(record))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !missing_constructors.is_empty() {
+            return Err(TypeCheckError::MissingAlternatives(
+                case.type_().clone(),
+                missing_constructors,
+            ));
+        }
+    }
+
+    check_alternatives(
+        case.alternatives(),
+        case.default_alternative(),
+        &case.type_().clone().into(),
+        variables,
+        &check_expression,
+    )?
+    .ok_or_else(|| TypeCheckError::NoAlternativeFound(case.clone().into()))
+}
+
+fn check_alternatives(
+    alternatives: &[Alternative],
+    default_alternative: Option<&DefaultAlternative>,
+    default_alternative_type: &Type,
+    variables: &HashMap<&str, Type>,
+    check_expression: &impl Fn(&Expression, &HashMap<&str, Type>) -> Result<Type, TypeCheckError>,
+) -> Result<Option<Type>, TypeCheckError> {
+    let mut expression_type = None;
+
+    for alternative in alternatives {
         let mut variables = variables.clone();
 
         variables.insert(alternative.name(), alternative.type_().clone());
@@ -244,10 +681,10 @@ fn check_case(
         }
     }
 
-    if let Some(alternative) = case.default_alternative() {
+    if let Some(alternative) = default_alternative {
         let mut variables = variables.clone();
 
-        variables.insert(alternative.name(), Type::Variant);
+        variables.insert(alternative.name(), default_alternative_type.clone());
 
         let alternative_type = check_expression(alternative.expression(), &variables)?;
 
@@ -258,7 +695,85 @@ fn check_case(
         }
     }
 
-    expression_type.ok_or_else(|| TypeCheckError::NoAlternativeFound(case.clone()))
+    Ok(expression_type)
+}
+
+fn check_polymorphic_function(
+    application: &FunctionApplication,
+    variables: &HashMap<&str, Type>,
+    functions: &HashMap<&str, &Definition>,
+) -> Result<Type, TypeCheckError> {
+    let definition = match application.function() {
+        Expression::Variable(variable) if !variables.contains_key(variable.name()) => {
+            functions.get(variable.name())
+        }
+        _ => None,
+    }
+    .ok_or_else(|| TypeCheckError::PolymorphicFunctionExpected(application.function().clone()))?;
+
+    if definition.type_parameters().len() != application.type_arguments().len() {
+        return Err(TypeCheckError::WrongTypeArgumentCount(
+            definition.name().into(),
+        ));
+    }
+
+    Ok(substitute_type(
+        &definition.type_().clone().into(),
+        &create_substitutions(definition.type_parameters(), application.type_arguments()),
+    ))
+}
+
+fn check_record_body(
+    type_: &Type,
+    types: &HashMap<&str, &TypeDefinition>,
+) -> Result<types::RecordBody, TypeCheckError> {
+    let record = match type_ {
+        Type::Record(record) => record,
+        Type::Tuple(tuple) => {
+            names::check_field_names(tuple.field_names().unwrap_or_default())?;
+
+            return Ok(tuple.body());
+        }
+        _ => return Err(TypeCheckError::RecordTypeExpected(type_.clone())),
+    };
+    let definition = types
+        .get(record.name())
+        .ok_or_else(|| TypeCheckError::TypeNotFound(record.clone()))?;
+
+    if definition.parameters().len() != record.arguments().len() {
+        return Err(TypeCheckError::WrongTypeArgumentCount(record.name().into()));
+    }
+
+    Ok(substitute_record_body(
+        definition.type_(),
+        &create_substitutions(definition.parameters(), record.arguments()),
+    ))
+}
+
+fn check_sum_definition<'a>(
+    sum: &types::Sum,
+    sums: &HashMap<&str, &'a SumDefinition>,
+) -> Result<&'a SumDefinition, TypeCheckError> {
+    sums.get(sum.name())
+        .copied()
+        .ok_or_else(|| TypeCheckError::SumNotFound(sum.clone()))
+}
+
+fn check_field_index(
+    element: &RecordElement,
+    body: &types::RecordBody,
+) -> Result<usize, TypeCheckError> {
+    match element.field() {
+        RecordField::Index(index) => Some(*index),
+        RecordField::Name(name) => body.field_index(name),
+    }
+    .filter(|&index| index < body.elements().len())
+    .ok_or_else(|| {
+        TypeCheckError::ElementIndexOutOfBounds(
+            element.clone(),
+            body.field_names().unwrap_or_default().to_vec(),
+        )
+    })
 }
 
 fn check_variable(
@@ -271,6 +786,59 @@ fn check_variable(
         .ok_or_else(|| TypeCheckError::VariableNotFound(variable.clone()))
 }
 
+fn check_type_variables(type_: &Type, parameters: &[String]) -> Result<(), TypeCheckError> {
+    match type_ {
+        Type::Function(function) => {
+            check_type_variables(function.argument(), parameters)?;
+            check_type_variables(function.result(), parameters)
+        }
+        Type::Lazy(lazy) => check_type_variables(lazy.type_(), parameters),
+        Type::Record(record) => {
+            for type_ in record.arguments() {
+                check_type_variables(type_, parameters)?;
+            }
+
+            Ok(())
+        }
+        Type::Tuple(tuple) => {
+            for type_ in tuple.elements() {
+                check_type_variables(type_, parameters)?;
+            }
+
+            Ok(())
+        }
+        Type::Variable(name) => {
+            if parameters.contains(name) {
+                Ok(())
+            } else {
+                Err(TypeCheckError::TypeVariableNotFound(name.clone()))
+            }
+        }
+        Type::Boolean
+        | Type::ByteString
+        | Type::Integer(_)
+        | Type::Number
+        | Type::Sum(_)
+        | Type::Variant => Ok(()),
+    }
+}
+
+fn check_numeric_type(type_: &Type) -> Result<(), TypeCheckError> {
+    match type_ {
+        Type::Integer(_) | Type::Number => Ok(()),
+        _ => Err(TypeCheckError::NumericTypeExpected(type_.clone())),
+    }
+}
+
+fn check_comparable_type(type_: &Type) -> Result<(), TypeCheckError> {
+    match type_ {
+        Type::Boolean | Type::ByteString | Type::Integer(_) | Type::Number => Ok(()),
+        _ => Err(TypeCheckError::ComparableTypeExpected(type_.clone())),
+    }
+}
+
+// Record types are compared by names while tuple types are compared
+// structurally by their element types.
 fn check_equality(one: &Type, other: &Type) -> Result<(), TypeCheckError> {
     if one == other {
         Ok(())
@@ -751,51 +1319,857 @@ mod tests {
                 Ok(())
             );
         }
-    }
-
-    mod variants {
-        use super::*;
 
         #[test]
-        fn check_variant() {
+        fn check_record_element_with_field_name() {
+            let record_type = types::Record::new("foo");
+
             assert_eq!(
-                check_types(&create_module_from_definitions(vec![
-                    Definition::with_environment(
+                check_types(&create_module_with_records(
+                    vec![TypeDefinition::new(
+                        "foo",
+                        types::RecordBody::with_fields(vec![
+                            ("x".into(), Type::Number),
+                            ("y".into(), Type::Boolean)
+                        ])
+                    )],
+                    vec![Definition::new(
                         "f",
-                        vec![],
-                        vec![Argument::new("x", Type::Number)],
-                        Variant::new(Type::Number, 42.0),
-                        Type::Variant
-                    )
-                ],)),
+                        vec![Argument::new("x", record_type.clone())],
+                        RecordElement::with_field_name(record_type, "y", Variable::new("x")),
+                        Type::Boolean
+                    )],
+                )),
                 Ok(())
             );
         }
 
         #[test]
-        fn fail_to_check_variant_in_variant() {
-            assert!(matches!(
-                check_types(&create_module_from_definitions(vec![
-                    Definition::with_environment(
+        fn fail_to_check_record_element_with_unknown_field_name() {
+            let record_type = types::Record::new("foo");
+            let element =
+                RecordElement::with_field_name(record_type.clone(), "z", Variable::new("x"));
+
+            assert_eq!(
+                check_types(&create_module_with_records(
+                    vec![TypeDefinition::new(
+                        "foo",
+                        types::RecordBody::with_fields(vec![("x".into(), Type::Number)])
+                    )],
+                    vec![Definition::new(
                         "f",
-                        vec![],
-                        vec![Argument::new("x", Type::Variant)],
-                        Variant::new(Type::Variant, Variable::new("x")),
-                        Type::Variant
-                    )
-                ],)),
-                Err(TypeCheckError::VariantInVariant(_))
-            ));
+                        vec![Argument::new("x", record_type)],
+                        element.clone(),
+                        Type::Number
+                    )],
+                )),
+                Err(TypeCheckError::ElementIndexOutOfBounds(
+                    element,
+                    vec!["x".into()]
+                ))
+            );
         }
-    }
 
-    #[test]
-    fn check_add_operator() {
-        let module = create_module_from_definitions(vec![Definition::with_environment(
-            "f",
-            vec![],
+        #[test]
+        fn check_record_update() {
+            let record_type = types::Record::new("foo");
+
+            assert_eq!(
+                check_types(&create_module_with_records(
+                    vec![TypeDefinition::new(
+                        "foo",
+                        types::RecordBody::with_fields(vec![
+                            ("x".into(), Type::Number),
+                            ("y".into(), Type::Boolean)
+                        ])
+                    )],
+                    vec![Definition::new(
+                        "f",
+                        vec![Argument::new("x", record_type.clone())],
+                        RecordUpdate::new(
+                            record_type.clone(),
+                            Variable::new("x"),
+                            vec![
+                                RecordUpdateField::with_field_name("y", true),
+                                RecordUpdateField::new(0, 42.0)
+                            ]
+                        ),
+                        record_type
+                    )],
+                )),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_record_update_with_wrong_field_type() {
+            let tuple_type = types::Tuple::new(vec![Type::Number]);
+
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    RecordUpdate::new(
+                        tuple_type.clone(),
+                        Variable::new("x"),
+                        vec![RecordUpdateField::new(0, true)]
+                    ),
+                    tuple_type
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn fail_to_check_record_update_with_duplicate_fields() {
+            let tuple_type = types::Tuple::new(vec![Type::Number]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    RecordUpdate::new(
+                        tuple_type.clone(),
+                        Variable::new("x"),
+                        vec![
+                            RecordUpdateField::new(0, 1.0),
+                            RecordUpdateField::new(0, 2.0)
+                        ]
+                    ),
+                    tuple_type
+                )])),
+                Err(TypeCheckError::DuplicateFieldNames("0".into()))
+            );
+        }
+
+        #[test]
+        fn check_let_record() {
+            let tuple_type = types::Tuple::new(vec![Type::Number, Type::Boolean]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    LetRecord::new(
+                        tuple_type,
+                        vec![
+                            Argument::new("y", Type::Number),
+                            Argument::new("z", Type::Boolean)
+                        ],
+                        Variable::new("x"),
+                        Variable::new("y")
+                    ),
+                    Type::Number
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_let_record_with_wrong_element_count() {
+            let tuple_type = types::Tuple::new(vec![Type::Number, Type::Boolean]);
+
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    LetRecord::new(
+                        tuple_type,
+                        vec![Argument::new("y", Type::Number)],
+                        Variable::new("x"),
+                        Variable::new("y")
+                    ),
+                    Type::Number
+                )])),
+                Err(TypeCheckError::WrongElementCount(_))
+            ));
+        }
+
+        #[test]
+        fn fail_to_check_let_record_with_wrong_element_type() {
+            let tuple_type = types::Tuple::new(vec![Type::Number, Type::Boolean]);
+
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    LetRecord::new(
+                        tuple_type,
+                        vec![
+                            Argument::new("y", Type::Number),
+                            Argument::new("z", Type::Number)
+                        ],
+                        Variable::new("x"),
+                        Variable::new("y")
+                    ),
+                    Type::Number
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn fail_to_check_let_record_with_duplicate_names() {
+            let tuple_type = types::Tuple::new(vec![Type::Number, Type::Number]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    LetRecord::new(
+                        tuple_type,
+                        vec![
+                            Argument::new("y", Type::Number),
+                            Argument::new("y", Type::Number)
+                        ],
+                        Variable::new("x"),
+                        Variable::new("y")
+                    ),
+                    Type::Number
+                )])),
+                Err(TypeCheckError::DuplicateVariableNames("y".into()))
+            );
+        }
+
+        #[test]
+        fn check_tuple() {
+            let tuple_type = types::Tuple::new(vec![Type::Number, Type::Boolean]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", tuple_type.clone())],
+                    RecordElement::new(
+                        types::Tuple::new(vec![Type::Number, Type::Boolean]),
+                        1,
+                        Record::new(
+                            tuple_type,
+                            vec![
+                                RecordElement::new(
+                                    types::Tuple::new(vec![Type::Number, Type::Boolean]),
+                                    0,
+                                    Variable::new("x")
+                                )
+                                .into(),
+                                true.into()
+                            ]
+                        )
+                    ),
+                    Type::Boolean
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_tuples_with_different_element_types() {
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", types::Tuple::new(vec![Type::Number]))],
+                    RecordElement::new(
+                        types::Tuple::new(vec![Type::Boolean]),
+                        0,
+                        Variable::new("x")
+                    ),
+                    Type::Boolean
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn check_anonymous_record_with_field_names() {
+            let record_type = types::Tuple::with_fields(vec![
+                ("x".into(), Type::Number),
+                ("y".into(), Type::Boolean),
+            ]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    RecordElement::with_field_name(
+                        record_type.clone(),
+                        "y",
+                        Record::new(record_type, vec![Variable::new("x").into(), true.into()])
+                    ),
+                    Type::Boolean
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_anonymous_records_with_different_field_names() {
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new(
+                        "x",
+                        types::Tuple::with_fields(vec![("x".into(), Type::Number)])
+                    )],
+                    Variable::new("x"),
+                    types::Tuple::with_fields(vec![("y".into(), Type::Number)])
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn fail_to_check_anonymous_record_with_duplicate_field_names() {
+            let record_type = types::Tuple::with_fields(vec![
+                ("x".into(), Type::Number),
+                ("x".into(), Type::Number),
+            ]);
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", record_type.clone())],
+                    RecordElement::with_field_name(record_type, "x", Variable::new("x")),
+                    Type::Number
+                )])),
+                Err(TypeCheckError::DuplicateFieldNames("x".into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_record_with_non_record_type() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Record::new(Type::Number, vec![]),
+                    Type::Number
+                )])),
+                Err(TypeCheckError::RecordTypeExpected(Type::Number))
+            );
+        }
+    }
+
+    mod variants {
+        use super::*;
+
+        #[test]
+        fn check_variant() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![
+                    Definition::with_environment(
+                        "f",
+                        vec![],
+                        vec![Argument::new("x", Type::Number)],
+                        Variant::new(Type::Number, 42.0),
+                        Type::Variant
+                    )
+                ],)),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn check_variant_in_variant() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![
+                    Definition::with_environment(
+                        "f",
+                        vec![],
+                        vec![Argument::new("x", Type::Variant)],
+                        Variant::new(Type::Variant, Variable::new("x")),
+                        Type::Variant
+                    )
+                ],)),
+                Ok(())
+            );
+        }
+    }
+
+    mod lazy_values {
+        use super::*;
+
+        #[test]
+        fn check_lazy_value() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Lazy::new(Type::Number, Variable::new("x")),
+                    types::Lazy::new(Type::Number),
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn check_force() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", types::Lazy::new(Type::Number))],
+                    Force::new(Variable::new("x")),
+                    Type::Number,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_lazy_value_with_wrong_type() {
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Lazy::new(Type::Boolean, Variable::new("x")),
+                    types::Lazy::new(Type::Boolean),
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn fail_to_force_non_lazy_value() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Force::new(Variable::new("x")),
+                    Type::Number,
+                )])),
+                Err(TypeCheckError::LazyExpected(Variable::new("x").into()))
+            );
+        }
+    }
+
+    mod try_operations {
+        use super::*;
+
+        #[test]
+        fn check_try_operation() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Variant)],
+                    Let::new(
+                        "y",
+                        Type::Variant,
+                        TryOperation::new(
+                            Variable::new("x"),
+                            "e",
+                            Type::ByteString,
+                            ByteStringLength::new(Variable::new("e")),
+                        ),
+                        Integer::new(types::Integer::Unsigned64, 42),
+                    ),
+                    types::Integer::Unsigned64,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn check_try_operation_in_tail_position() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Variant)],
+                    If::new(
+                        true,
+                        TryOperation::new(
+                            Variable::new("x"),
+                            "e",
+                            Type::Number,
+                            Variant::new(Type::Number, Variable::new("e")),
+                        ),
+                        Variable::new("x"),
+                    ),
+                    Type::Variant,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_try_operation_in_record_element() {
+            let record_type = types::Record::new("r");
+            let operation = TryOperation::new(
+                Variable::new("v"),
+                "e",
+                Type::ByteString,
+                Variant::new(Type::ByteString, Variable::new("e")),
+            );
+
+            assert_eq!(
+                check_types(&create_module_with_records(
+                    vec![TypeDefinition::new(
+                        "r",
+                        types::RecordBody::new(vec![Type::ByteString, Type::Variant])
+                    )],
+                    vec![Definition::new(
+                        "f",
+                        vec![
+                            Argument::new("x", Type::ByteString),
+                            Argument::new("v", Type::Variant),
+                        ],
+                        Variant::new(
+                            record_type.clone(),
+                            Record::new(
+                                record_type,
+                                vec![Variable::new("x").into(), operation.clone().into()]
+                            )
+                        ),
+                        Type::Variant,
+                    )],
+                )),
+                Err(TypeCheckError::InvalidTryOperationPosition(
+                    operation.into()
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_try_operation_with_wrong_result_type() {
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Variant)],
+                    TryOperation::new(Variable::new("x"), "e", Type::Number, Variable::new("e")),
+                    Type::Variant,
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+
+        #[test]
+        fn fail_to_check_try_operation_with_non_variant_operand() {
+            assert!(matches!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    TryOperation::new(Variable::new("x"), "e", Type::Number, Variable::new("x")),
+                    Type::Variant,
+                )])),
+                Err(TypeCheckError::TypesNotMatched(_, _))
+            ));
+        }
+    }
+
+    mod literal_cases {
+        use super::*;
+
+        #[test]
+        fn check_literal_case() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::ByteString)],
+                    LiteralCase::new(
+                        Type::ByteString,
+                        Variable::new("x"),
+                        vec![
+                            LiteralAlternative::new(ByteString::new("foo"), 1.0),
+                            LiteralAlternative::new(ByteString::new("bar"), 2.0),
+                        ],
+                        Some(DefaultAlternative::new("y", 3.0)),
+                    ),
+                    Type::Number,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_inconsistent_alternative_types() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::ByteString)],
+                    LiteralCase::new(
+                        Type::ByteString,
+                        Variable::new("x"),
+                        vec![LiteralAlternative::new(ByteString::new("foo"), 1.0)],
+                        Some(DefaultAlternative::new("y", Variable::new("y"))),
+                    ),
+                    Type::Number,
+                )])),
+                Err(TypeCheckError::TypesNotMatched(
+                    Type::ByteString,
+                    Type::Number
+                ))
+            );
+        }
+
+        #[test]
+        fn check_exhaustive_boolean_case() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Boolean)],
+                    LiteralCase::new(
+                        Type::Boolean,
+                        Variable::new("x"),
+                        vec![
+                            LiteralAlternative::new(true, 1.0),
+                            LiteralAlternative::new(false, 2.0),
+                        ],
+                        None,
+                    ),
+                    Type::Number,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn check_integer_case() {
+            let integer_type = types::Integer::Unsigned8;
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", integer_type)],
+                    LiteralCase::new(
+                        integer_type,
+                        Variable::new("x"),
+                        vec![LiteralAlternative::new(Integer::new(integer_type, 42), 1.0)],
+                        Some(DefaultAlternative::new("y", 2.0)),
+                    ),
+                    Type::Number,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_non_exhaustive_case() {
+            let case = LiteralCase::new(
+                Type::Boolean,
+                Variable::new("x"),
+                vec![LiteralAlternative::new(true, 1.0)],
+                None,
+            );
+
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Boolean)],
+                    case.clone(),
+                    Type::Number,
+                )])),
+                Err(TypeCheckError::MissingDefaultAlternative(case.into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_literal_of_wrong_type() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    LiteralCase::new(
+                        Type::Number,
+                        Variable::new("x"),
+                        vec![LiteralAlternative::new(true, 1.0)],
+                        Some(DefaultAlternative::new("y", Variable::new("y"))),
+                    ),
+                    Type::Number,
+                )])),
+                Err(TypeCheckError::TypesNotMatched(Type::Boolean, Type::Number))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_non_comparable_type() {
+            assert_eq!(
+                check_types(&create_module_from_definitions(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Variant)],
+                    LiteralCase::new(
+                        Type::Variant,
+                        Variable::new("x"),
+                        vec![],
+                        Some(DefaultAlternative::new("y", 42.0)),
+                    ),
+                    Type::Number,
+                )])),
+                Err(TypeCheckError::ComparableTypeExpected(Type::Variant))
+            );
+        }
+    }
+
+    mod sums {
+        use super::*;
+
+        fn create_module(definitions: Vec<Definition>) -> Module {
+            Module::with_sum_definitions(
+                vec![
+                    TypeDefinition::new("foo", types::RecordBody::new(vec![])),
+                    TypeDefinition::new("bar", types::RecordBody::new(vec![Type::Number])),
+                    TypeDefinition::new("baz", types::RecordBody::new(vec![])),
+                ],
+                vec![SumDefinition::new(
+                    "sum",
+                    vec![types::Record::new("foo"), types::Record::new("bar")],
+                )],
+                vec![],
+                vec![],
+                vec![],
+                definitions,
+            )
+        }
+
+        fn create_case_definition(
+            alternatives: Vec<Alternative>,
+            default_alternative: Option<DefaultAlternative>,
+        ) -> Definition {
+            Definition::new(
+                "f",
+                vec![Argument::new("x", types::Sum::new("sum"))],
+                SumCase::new(
+                    types::Sum::new("sum"),
+                    Variable::new("x"),
+                    alternatives,
+                    default_alternative,
+                ),
+                Type::Number,
+            )
+        }
+
+        #[test]
+        fn check_sum() {
+            assert_eq!(
+                check_types(&create_module(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Sum::new(
+                        types::Sum::new("sum"),
+                        types::Record::new("bar"),
+                        Record::new(types::Record::new("bar"), vec![Variable::new("x").into()]),
+                    ),
+                    types::Sum::new("sum"),
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_sum_with_unknown_constructor() {
+            assert_eq!(
+                check_types(&create_module(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Sum::new(
+                        types::Sum::new("sum"),
+                        types::Record::new("baz"),
+                        Record::new(types::Record::new("baz"), vec![]),
+                    ),
+                    types::Sum::new("sum"),
+                )])),
+                Err(TypeCheckError::ConstructorNotFound(
+                    types::Sum::new("sum"),
+                    types::Record::new("baz")
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_sum_with_unknown_sum() {
+            assert_eq!(
+                check_types(&create_module(vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Sum::new(
+                        types::Sum::new("qux"),
+                        types::Record::new("foo"),
+                        Record::new(types::Record::new("foo"), vec![]),
+                    ),
+                    types::Sum::new("qux"),
+                )])),
+                Err(TypeCheckError::SumNotFound(types::Sum::new("qux")))
+            );
+        }
+
+        #[test]
+        fn check_exhaustive_case() {
+            assert_eq!(
+                check_types(&create_module(vec![create_case_definition(
+                    vec![
+                        Alternative::new(types::Record::new("foo"), "y", 42.0),
+                        Alternative::new(
+                            types::Record::new("bar"),
+                            "y",
+                            RecordElement::new(types::Record::new("bar"), 0, Variable::new("y")),
+                        ),
+                    ],
+                    None,
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn check_case_with_default_alternative() {
+            assert_eq!(
+                check_types(&create_module(vec![create_case_definition(
+                    vec![Alternative::new(types::Record::new("foo"), "y", 42.0)],
+                    Some(DefaultAlternative::new("y", 42.0)),
+                )])),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn fail_to_check_non_exhaustive_case() {
+            assert_eq!(
+                check_types(&create_module(vec![create_case_definition(
+                    vec![Alternative::new(types::Record::new("foo"), "y", 42.0)],
+                    None,
+                )])),
+                Err(TypeCheckError::MissingAlternatives(
+                    types::Sum::new("sum"),
+                    vec![types::Record::new("bar")]
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_case_with_impossible_alternative() {
+            assert_eq!(
+                check_types(&create_module(vec![create_case_definition(
+                    vec![Alternative::new(types::Record::new("baz"), "y", 42.0)],
+                    Some(DefaultAlternative::new("y", 42.0)),
+                )])),
+                Err(TypeCheckError::ImpossibleAlternative(
+                    types::Sum::new("sum"),
+                    types::Record::new("baz").into()
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_case_with_duplicate_alternatives() {
+            assert_eq!(
+                check_types(&create_module(vec![create_case_definition(
+                    vec![
+                        Alternative::new(types::Record::new("foo"), "y", 42.0),
+                        Alternative::new(types::Record::new("foo"), "y", 42.0),
+                    ],
+                    Some(DefaultAlternative::new("y", 42.0)),
+                )])),
+                Err(TypeCheckError::DuplicateAlternative(
+                    types::Sum::new("sum"),
+                    types::Record::new("foo")
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn check_add_operator() {
+        let module = create_module_from_definitions(vec![Definition::with_environment(
+            "f",
+            vec![],
             vec![Argument::new("x", Type::Number)],
-            ArithmeticOperation::new(ArithmeticOperator::Add, 42.0, 42.0),
+            ArithmeticOperation::new(Type::Number, ArithmeticOperator::Add, 42.0, 42.0),
             Type::Number,
         )]);
         assert_eq!(check_types(&module), Ok(()));
@@ -807,12 +2181,340 @@ mod tests {
             "f",
             vec![],
             vec![Argument::new("x", Type::Number)],
-            ComparisonOperation::new(ComparisonOperator::Equal, 42.0, 42.0),
+            ComparisonOperation::new(Type::Number, ComparisonOperator::Equal, 42.0, 42.0),
+            Type::Boolean,
+        )]);
+        assert_eq!(check_types(&module), Ok(()));
+    }
+
+    #[test]
+    fn check_boolean_comparison() {
+        let module = create_module_from_definitions(vec![Definition::new(
+            "f",
+            vec![Argument::new("x", Type::Boolean)],
+            ComparisonOperation::new(
+                Type::Boolean,
+                ComparisonOperator::LessThan,
+                Variable::new("x"),
+                true,
+            ),
             Type::Boolean,
         )]);
+
         assert_eq!(check_types(&module), Ok(()));
     }
 
+    #[test]
+    fn fail_to_check_comparison_of_functions() {
+        let function_type = types::Function::new(Type::Number, Type::Number);
+        let module = create_module_from_definitions(vec![Definition::new(
+            "f",
+            vec![Argument::new("x", function_type.clone())],
+            ComparisonOperation::new(
+                function_type.clone(),
+                ComparisonOperator::Equal,
+                Variable::new("x"),
+                Variable::new("x"),
+            ),
+            Type::Boolean,
+        )]);
+
+        assert_eq!(
+            check_types(&module),
+            Err(TypeCheckError::ComparableTypeExpected(function_type.into()))
+        );
+    }
+
+    mod integers {
+        use super::*;
+
+        #[test]
+        fn check_integer_arithmetic_operation() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", types::Integer::Unsigned8)],
+                ArithmeticOperation::new(
+                    types::Integer::Unsigned8,
+                    ArithmeticOperator::Divide,
+                    Variable::new("x"),
+                    Integer::new(types::Integer::Unsigned8, 2),
+                ),
+                types::Integer::Unsigned8,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn check_integer_comparison_operation() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", types::Integer::Signed64)],
+                ComparisonOperation::new(
+                    types::Integer::Signed64,
+                    ComparisonOperator::LessThan,
+                    Variable::new("x"),
+                    Integer::new(types::Integer::Signed64, 0),
+                ),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_arithmetic_operation_with_different_integer_types() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", types::Integer::Signed32)],
+                ArithmeticOperation::new(
+                    types::Integer::Signed32,
+                    ArithmeticOperator::Add,
+                    Variable::new("x"),
+                    Integer::new(types::Integer::Unsigned32, 1),
+                ),
+                types::Integer::Signed32,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::TypesNotMatched(
+                    types::Integer::Unsigned32.into(),
+                    types::Integer::Signed32.into()
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_arithmetic_operation_with_non_numeric_type() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Boolean)],
+                ArithmeticOperation::new(
+                    Type::Boolean,
+                    ArithmeticOperator::Add,
+                    Variable::new("x"),
+                    Variable::new("x"),
+                ),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::NumericTypeExpected(Type::Boolean))
+            );
+        }
+    }
+
+    mod negations {
+        use super::*;
+
+        #[test]
+        fn check_negation() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", types::Integer::Signed8)],
+                Negation::new(types::Integer::Signed8, Variable::new("x")),
+                types::Integer::Signed8,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_negation_of_boolean() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Boolean)],
+                Negation::new(Type::Boolean, Variable::new("x")),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::NumericTypeExpected(Type::Boolean))
+            );
+        }
+    }
+
+    mod boolean_operations {
+        use super::*;
+
+        #[test]
+        fn check_boolean_operation() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Boolean)],
+                BooleanOperation::new(
+                    BooleanOperator::Or,
+                    Not::new(Variable::new("x")),
+                    Variable::new("x"),
+                ),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_boolean_operation_with_number() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Boolean)],
+                BooleanOperation::new(BooleanOperator::And, Variable::new("x"), 42.0),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::TypesNotMatched(Type::Number, Type::Boolean))
+            );
+        }
+    }
+
+    mod byte_strings {
+        use super::*;
+
+        #[test]
+        fn check_byte_string_operations() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::ByteString)],
+                ByteStringSlice::new(
+                    ByteStringConcatenation::new(Variable::new("x"), ByteString::new("foo")),
+                    Integer::new(types::Integer::Unsigned64, 1),
+                    ByteStringLength::new(Variable::new("x")),
+                ),
+                Type::ByteString,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn check_byte_string_comparison() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::ByteString)],
+                ComparisonOperation::new(
+                    Type::ByteString,
+                    ComparisonOperator::LessThan,
+                    Variable::new("x"),
+                    ByteString::new("foo"),
+                ),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_slice_with_number_index() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::ByteString)],
+                ByteStringSlice::new(
+                    Variable::new("x"),
+                    0.0,
+                    Integer::new(types::Integer::Unsigned64, 1),
+                ),
+                Type::ByteString,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::TypesNotMatched(
+                    Type::Number,
+                    types::Integer::Unsigned64.into()
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_comparison_of_variants() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Variant)],
+                ComparisonOperation::new(
+                    Type::Variant,
+                    ComparisonOperator::Equal,
+                    Variable::new("x"),
+                    Variable::new("x"),
+                ),
+                Type::Boolean,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::ComparableTypeExpected(Type::Variant))
+            );
+        }
+    }
+
+    mod numeric_conversions {
+        use super::*;
+
+        #[test]
+        fn check_numeric_conversion() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                NumericConversion::new(
+                    Type::Number,
+                    types::Integer::Signed32,
+                    Rounding::Floor,
+                    Variable::new("x"),
+                ),
+                types::Integer::Signed32,
+            )]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_numeric_conversion_with_wrong_argument() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", types::Integer::Unsigned8)],
+                NumericConversion::new(
+                    types::Integer::Signed8,
+                    Type::Number,
+                    Rounding::Truncate,
+                    Variable::new("x"),
+                ),
+                Type::Number,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::TypesNotMatched(
+                    types::Integer::Unsigned8.into(),
+                    types::Integer::Signed8.into()
+                ))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_numeric_conversion_to_non_numeric_type() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                NumericConversion::new(
+                    Type::Number,
+                    Type::ByteString,
+                    Rounding::Truncate,
+                    Variable::new("x"),
+                ),
+                Type::ByteString,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::NumericTypeExpected(Type::ByteString))
+            );
+        }
+    }
+
     mod foreign_declarations {
         use super::*;
 
@@ -907,6 +2609,98 @@ mod tests {
         }
     }
 
+    mod constants {
+        use super::*;
+
+        #[test]
+        fn check_types_of_constant() {
+            let module = create_module_from_definitions(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    ArithmeticOperation::new(
+                        Type::Number,
+                        ArithmeticOperator::Add,
+                        Variable::new("c"),
+                        Variable::new("x"),
+                    ),
+                    Type::Number,
+                ),
+            ]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_types_of_constant_applied_as_function() {
+            let application = FunctionApplication::new(
+                types::Function::new(types::Tuple::new(vec![]), Type::Number),
+                Variable::new("c"),
+                Record::new(types::Tuple::new(vec![]), vec![]),
+            );
+            let module = create_module_from_definitions(vec![
+                Definition::new("c", vec![], 42.0, Type::Number),
+                Definition::new("d", vec![], application, Type::Number),
+            ]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::FunctionExpected(Variable::new("c").into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_types_of_polymorphic_constant() {
+            let module = create_module_from_definitions(vec![Definition::with_type_parameters(
+                "c",
+                vec!["a".into()],
+                vec![],
+                42.0,
+                Type::Number,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::PolymorphicConstant("c".into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_types_of_local_constant() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Number)],
+                LetRecursive::new(
+                    Definition::new("c", vec![], 42.0, Type::Number),
+                    Variable::new("c"),
+                ),
+                Type::Number,
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::LocalConstant("c".into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_types_of_foreign_definition_for_constant() {
+            let module = Module::new(
+                vec![],
+                vec![],
+                vec![ForeignDefinition::new("c", "c")],
+                vec![],
+                vec![Definition::new("c", vec![], 42.0, Type::Number)],
+            );
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::FunctionExpected(Variable::new("c").into()))
+            );
+        }
+    }
+
     #[test]
     fn check_duplicate_function_names() {
         let module = create_module_from_definitions(vec![
@@ -929,4 +2723,398 @@ mod tests {
             Err(TypeCheckError::DuplicateFunctionNames("f".into()))
         );
     }
+
+    #[test]
+    fn check_mutually_recursive_definitions() {
+        let function_type = types::Function::new(Type::Number, Type::Number);
+        let module = create_module_from_definitions(vec![Definition::new(
+            "f",
+            vec![Argument::new("x", Type::Number)],
+            LetRecursive::with_definitions(
+                vec![
+                    Definition::new(
+                        "g",
+                        vec![Argument::new("y", Type::Number)],
+                        FunctionApplication::new(
+                            function_type.clone(),
+                            Variable::new("h"),
+                            Variable::new("y"),
+                        ),
+                        Type::Number,
+                    ),
+                    Definition::new(
+                        "h",
+                        vec![Argument::new("y", Type::Number)],
+                        FunctionApplication::new(
+                            function_type.clone(),
+                            Variable::new("g"),
+                            Variable::new("y"),
+                        ),
+                        Type::Number,
+                    ),
+                ],
+                FunctionApplication::new(function_type, Variable::new("g"), Variable::new("x")),
+            ),
+            Type::Number,
+        )]);
+
+        assert_eq!(check_types(&module), Ok(()));
+    }
+
+    #[test]
+    fn fail_to_check_types_of_thunk_in_mutually_recursive_definitions() {
+        let function_type = types::Function::new(Type::Number, Type::Number);
+        let module = create_module_from_definitions(vec![Definition::new(
+            "f",
+            vec![Argument::new("x", Type::Number)],
+            LetRecursive::with_definitions(
+                vec![
+                    Definition::new(
+                        "g",
+                        vec![Argument::new("y", Type::Number)],
+                        FunctionApplication::new(
+                            function_type.clone(),
+                            Variable::new("h"),
+                            Variable::new("y"),
+                        ),
+                        Type::Number,
+                    ),
+                    Definition::thunk(
+                        "h",
+                        vec![Argument::new("y", Type::Number)],
+                        Variable::new("x"),
+                        Type::Number,
+                    ),
+                ],
+                FunctionApplication::new(function_type, Variable::new("g"), Variable::new("x")),
+            ),
+            Type::Number,
+        )]);
+
+        assert_eq!(
+            check_types(&module),
+            Err(TypeCheckError::ThunkInRecursiveGroup("h".into()))
+        );
+    }
+
+    #[test]
+    fn check_duplicate_names_in_recursive_definitions() {
+        let definition = Definition::new(
+            "g",
+            vec![Argument::new("y", Type::Number)],
+            Variable::new("y"),
+            Type::Number,
+        );
+        let module = create_module_from_definitions(vec![Definition::new(
+            "f",
+            vec![Argument::new("x", Type::Number)],
+            LetRecursive::with_definitions(
+                vec![definition.clone(), definition],
+                Variable::new("x"),
+            ),
+            Type::Number,
+        )]);
+
+        assert_eq!(
+            check_types(&module),
+            Err(TypeCheckError::DuplicateFunctionNames("g".into()))
+        );
+    }
+
+    mod polymorphism {
+        use super::*;
+
+        fn create_identity_definition() -> Definition {
+            Definition::with_type_parameters(
+                "id",
+                vec!["a".into()],
+                vec![Argument::new("x", Type::Variable("a".into()))],
+                Variable::new("x"),
+                Type::Variable("a".into()),
+            )
+        }
+
+        #[test]
+        fn check_polymorphic_function_application() {
+            let module = create_module_from_definitions(vec![
+                create_identity_definition(),
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    FunctionApplication::with_type_arguments(
+                        types::Function::new(Type::Number, Type::Number),
+                        Variable::new("id"),
+                        vec![Type::Number],
+                        Variable::new("x"),
+                    ),
+                    Type::Number,
+                ),
+            ]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn check_polymorphic_record() {
+            let record_type =
+                types::Record::with_arguments("box", vec![Type::Variable("a".into())]);
+            let module = create_module_with_records(
+                vec![TypeDefinition::with_parameters(
+                    "box",
+                    vec!["a".into()],
+                    types::RecordBody::new(vec![Type::Variable("a".into())]),
+                )],
+                vec![Definition::with_type_parameters(
+                    "f",
+                    vec!["a".into()],
+                    vec![Argument::new("x", Type::Variable("a".into()))],
+                    RecordElement::new(
+                        record_type.clone(),
+                        0,
+                        Record::new(record_type, vec![Variable::new("x").into()]),
+                    ),
+                    Type::Variable("a".into()),
+                )],
+            );
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_polymorphic_function_without_type_arguments() {
+            let module = create_module_from_definitions(vec![
+                create_identity_definition(),
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    FunctionApplication::new(
+                        types::Function::new(Type::Number, Type::Number),
+                        Variable::new("id"),
+                        Variable::new("x"),
+                    ),
+                    Type::Number,
+                ),
+            ]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::VariableNotFound(Variable::new("id")))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_wrong_type_argument_count() {
+            let module = create_module_from_definitions(vec![
+                create_identity_definition(),
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    FunctionApplication::with_type_arguments(
+                        types::Function::new(Type::Number, Type::Number),
+                        Variable::new("id"),
+                        vec![Type::Number, Type::Number],
+                        Variable::new("x"),
+                    ),
+                    Type::Number,
+                ),
+            ]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::WrongTypeArgumentCount("id".into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_unknown_type_variable() {
+            let module = create_module_from_definitions(vec![Definition::new(
+                "f",
+                vec![Argument::new("x", Type::Variable("a".into()))],
+                Variable::new("x"),
+                Type::Variable("a".into()),
+            )]);
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::TypeVariableNotFound("a".into()))
+            );
+        }
+
+        fn create_box_definition() -> TypeDefinition {
+            TypeDefinition::with_parameters(
+                "box",
+                vec!["a".into()],
+                types::RecordBody::new(vec![Type::Variable("a".into())]),
+            )
+        }
+
+        fn create_recursive_definition(type_argument: impl Into<Type>) -> Definition {
+            let type_argument = type_argument.into();
+
+            Definition::with_type_parameters(
+                "f",
+                vec!["a".into()],
+                vec![Argument::new("x", Type::Variable("a".into()))],
+                FunctionApplication::with_type_arguments(
+                    types::Function::new(type_argument.clone(), Type::Number),
+                    Variable::new("f"),
+                    vec![type_argument.clone()],
+                    if type_argument == Type::Variable("a".into()) {
+                        Expression::from(Variable::new("x"))
+                    } else {
+                        Record::new(type_argument, vec![Variable::new("x").into()]).into()
+                    },
+                ),
+                Type::Number,
+            )
+        }
+
+        fn create_caller_definition() -> Definition {
+            Definition::new(
+                "g",
+                vec![Argument::new("x", Type::Number)],
+                FunctionApplication::with_type_arguments(
+                    types::Function::new(Type::Number, Type::Number),
+                    Variable::new("f"),
+                    vec![Type::Number],
+                    Variable::new("x"),
+                ),
+                Type::Number,
+            )
+        }
+
+        #[test]
+        fn check_recursive_polymorphic_definition() {
+            let module = create_module_with_records(
+                vec![create_box_definition()],
+                vec![
+                    create_recursive_definition(Type::Variable("a".into())),
+                    create_caller_definition(),
+                ],
+            );
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn check_recursive_polymorphic_definition_with_monomorphic_type() {
+            let module = create_module_from_definitions(vec![
+                Definition::with_type_parameters(
+                    "f",
+                    vec!["a".into()],
+                    vec![Argument::new("x", Type::Variable("a".into()))],
+                    FunctionApplication::with_type_arguments(
+                        types::Function::new(Type::Number, Type::Number),
+                        Variable::new("f"),
+                        vec![Type::Number],
+                        42.0,
+                    ),
+                    Type::Number,
+                ),
+                create_caller_definition(),
+            ]);
+
+            assert_eq!(check_types(&module), Ok(()));
+        }
+
+        #[test]
+        fn fail_to_check_polymorphic_recursion() {
+            let module = create_module_with_records(
+                vec![create_box_definition()],
+                vec![
+                    create_recursive_definition(types::Record::with_arguments(
+                        "box",
+                        vec![Type::Variable("a".into())],
+                    )),
+                    create_caller_definition(),
+                ],
+            );
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::PolymorphicRecursion("f".into()))
+            );
+        }
+
+        #[test]
+        fn fail_to_check_mutual_polymorphic_recursion() {
+            let box_type = types::Record::with_arguments("box", vec![Type::Variable("a".into())]);
+            let module = create_module_with_records(
+                vec![create_box_definition()],
+                vec![
+                    Definition::with_type_parameters(
+                        "f",
+                        vec!["a".into()],
+                        vec![Argument::new("x", Type::Variable("a".into()))],
+                        FunctionApplication::with_type_arguments(
+                            types::Function::new(box_type.clone(), Type::Number),
+                            Variable::new("h"),
+                            vec![box_type.clone().into()],
+                            Record::new(box_type, vec![Variable::new("x").into()]),
+                        ),
+                        Type::Number,
+                    ),
+                    Definition::with_type_parameters(
+                        "h",
+                        vec!["a".into()],
+                        vec![Argument::new("x", Type::Variable("a".into()))],
+                        FunctionApplication::with_type_arguments(
+                            types::Function::new(Type::Variable("a".into()), Type::Number),
+                            Variable::new("f"),
+                            vec![Type::Variable("a".into())],
+                            Variable::new("x"),
+                        ),
+                        Type::Number,
+                    ),
+                    create_caller_definition(),
+                ],
+            );
+
+            assert_eq!(
+                check_types(&module),
+                Err(TypeCheckError::PolymorphicRecursion("f".into()))
+            );
+        }
+    }
+
+    #[test]
+    fn display_error() {
+        assert_eq!(
+            TypeCheckError::TypesNotMatched(
+                types::Function::new(Type::Number, Type::Boolean).into(),
+                types::Record::new("foo").into()
+            )
+            .to_string(),
+            "types not matched: number -> boolean and foo"
+        );
+        assert_eq!(
+            TypeCheckError::FunctionExpected(
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Add,
+                    1.0,
+                    Variable::new("x")
+                )
+                .into()
+            )
+            .to_string(),
+            "function expected: 1.0 + x"
+        );
+        assert_eq!(
+            TypeCheckError::ElementIndexOutOfBounds(
+                RecordElement::with_field_name(types::Record::new("foo"), "z", Variable::new("x")),
+                vec!["x".into(), "y".into()]
+            )
+            .to_string(),
+            "element index out of bounds: x.foo.z (fields: x, y)"
+        );
+        assert_eq!(
+            TypeCheckError::DuplicateAlternative(
+                types::Sum::new("shape"),
+                types::Record::new("circle")
+            )
+            .to_string(),
+            "duplicate alternative in case of sum shape: circle"
+        );
+    }
 }
@@ -1,23 +1,70 @@
 use super::TypeCheckError;
-use crate::ir::*;
+use crate::{ir::*, types};
 use std::collections::HashSet;
 
 pub fn check_names(module: &Module) -> Result<(), TypeCheckError> {
-    check_types(module.type_definitions())?;
+    check_types(module.type_definitions(), module.sum_definitions())?;
     check_functions(module)?;
 
     Ok(())
 }
 
-fn check_types(definitions: &[TypeDefinition]) -> Result<(), TypeCheckError> {
+// Record and sum types share a namespace.
+fn check_types(
+    type_definitions: &[TypeDefinition],
+    sum_definitions: &[SumDefinition],
+) -> Result<(), TypeCheckError> {
     let mut names = HashSet::new();
 
-    for definition in definitions {
-        if names.contains(definition.name()) {
-            return Err(TypeCheckError::DuplicateTypeNames(definition.name().into()));
+    for name in type_definitions
+        .iter()
+        .map(|definition| definition.name())
+        .chain(sum_definitions.iter().map(|definition| definition.name()))
+    {
+        if names.contains(name) {
+            return Err(TypeCheckError::DuplicateTypeNames(name.into()));
         }
 
-        names.insert(definition.name());
+        names.insert(name);
+    }
+
+    for definition in type_definitions {
+        check_field_names(definition.type_().field_names().unwrap_or_default())?;
+    }
+
+    for definition in sum_definitions {
+        check_constructors(definition)?;
+    }
+
+    Ok(())
+}
+
+pub fn check_field_names(field_names: &[String]) -> Result<(), TypeCheckError> {
+    let mut names = HashSet::new();
+
+    for name in field_names {
+        if names.contains(name) {
+            return Err(TypeCheckError::DuplicateFieldNames(name.into()));
+        }
+
+        names.insert(name);
+    }
+
+    Ok(())
+}
+
+fn check_constructors(definition: &SumDefinition) -> Result<(), TypeCheckError> {
+    let mut records = HashSet::new();
+
+    for record in definition.constructors() {
+        if records.contains(record) {
+            return Err(TypeCheckError::DuplicateSumConstructors(
+                types::Sum::new(definition.name()),
+                record.clone(),
+            ));
+        }
+
+        records.insert(record);
     }
 
     Ok(())
@@ -85,6 +132,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_duplicate_field_name() {
+        let module = Module::new(
+            vec![TypeDefinition::new(
+                "foo",
+                types::RecordBody::with_fields(vec![
+                    ("x".into(), Type::Number),
+                    ("x".into(), Type::Boolean),
+                ]),
+            )],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            check_names(&module),
+            Err(TypeCheckError::DuplicateFieldNames("x".into()))
+        );
+    }
+
+    #[test]
+    fn check_duplicate_type_name_of_sum() {
+        let module = Module::with_sum_definitions(
+            vec![TypeDefinition::new("foo", types::RecordBody::new(vec![]))],
+            vec![SumDefinition::new("foo", vec![types::Record::new("foo")])],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            check_names(&module),
+            Err(TypeCheckError::DuplicateTypeNames("foo".into()))
+        );
+    }
+
+    #[test]
+    fn check_duplicate_sum_constructor() {
+        let module = Module::with_sum_definitions(
+            vec![TypeDefinition::new("foo", types::RecordBody::new(vec![]))],
+            vec![SumDefinition::new(
+                "bar",
+                vec![types::Record::new("foo"), types::Record::new("foo")],
+            )],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            check_names(&module),
+            Err(TypeCheckError::DuplicateSumConstructors(
+                types::Sum::new("bar"),
+                types::Record::new("foo")
+            ))
+        );
+    }
+
     #[test]
     fn check_duplicate_function_name_in_definition() {
         let module = Module::new(
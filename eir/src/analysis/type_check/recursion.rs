@@ -0,0 +1,89 @@
+use super::{check_type_variables, TypeCheckError};
+use crate::{ir::*, types::Type};
+use std::collections::{HashMap, HashSet};
+
+// Polymorphic definitions can refer to ones in the same recursive group only
+// with type variables or types without them as type arguments. Otherwise,
+// monomorphization would create infinitely many instances of them.
+pub fn check_polymorphic_recursion(module: &Module) -> Result<(), TypeCheckError> {
+    let references = module
+        .definitions()
+        .iter()
+        .filter(|definition| !definition.type_parameters().is_empty())
+        .map(|definition| (definition.name(), collect_references(definition)))
+        .collect::<HashMap<_, _>>();
+
+    for definition in module.definitions() {
+        for (other, type_arguments) in references.get(definition.name()).into_iter().flatten() {
+            if type_arguments.iter().any(is_growing_type)
+                && is_reachable(other, definition.name(), &references)
+            {
+                return Err(TypeCheckError::PolymorphicRecursion(
+                    definition.name().into(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_references(definition: &Definition) -> Vec<(String, Vec<Type>)> {
+    let mut collector = ReferenceCollector { references: vec![] };
+
+    collector.visit_definition(definition);
+
+    collector.references
+}
+
+fn is_growing_type(type_: &Type) -> bool {
+    !matches!(type_, Type::Variable(_)) && check_type_variables(type_, &[]).is_err()
+}
+
+fn is_reachable(
+    from: &str,
+    to: &str,
+    references: &HashMap<&str, Vec<(String, Vec<Type>)>>,
+) -> bool {
+    let mut names = vec![from];
+    let mut visited = HashSet::new();
+
+    while let Some(name) = names.pop() {
+        if name == to {
+            return true;
+        } else if visited.insert(name) {
+            names.extend(
+                references
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, _)| name.as_str()),
+            );
+        }
+    }
+
+    false
+}
+
+// Polymorphic definitions are referred to only by function applications with
+// type arguments.
+struct ReferenceCollector {
+    references: Vec<(String, Vec<Type>)>,
+}
+
+impl Visitor for ReferenceCollector {
+    fn visit_expression(&mut self, expression: &Expression) {
+        if let Expression::FunctionApplication(application) = expression {
+            if let Expression::Variable(variable) = application.function() {
+                if !application.type_arguments().is_empty() {
+                    self.references.push((
+                        variable.name().into(),
+                        application.type_arguments().to_vec(),
+                    ));
+                }
+            }
+        }
+
+        walk_expression(self, expression)
+    }
+}
@@ -0,0 +1,104 @@
+use super::TypeCheckError;
+use crate::ir::*;
+
+// Try operations are allowed only in let-bound or tail positions where no
+// other operands have been evaluated before them. Otherwise, values consumed
+// by those operands would leak when the try operations return early.
+pub fn check_try_operation_positions(module: &Module) -> Result<(), TypeCheckError> {
+    let mut checker = PositionChecker {
+        allowed: true,
+        invalid_operation: None,
+    };
+
+    checker.visit_module(module);
+
+    if let Some(operation) = checker.invalid_operation {
+        Err(TypeCheckError::InvalidTryOperationPosition(operation))
+    } else {
+        Ok(())
+    }
+}
+
+struct PositionChecker {
+    allowed: bool,
+    invalid_operation: Option<Expression>,
+}
+
+impl PositionChecker {
+    fn visit_with(&mut self, expression: &Expression, allowed: bool) {
+        let outer = self.allowed;
+
+        self.allowed = allowed;
+        self.visit_expression(expression);
+        self.allowed = outer;
+    }
+}
+
+impl Visitor for PositionChecker {
+    fn visit_definition(&mut self, definition: &Definition) {
+        self.visit_with(definition.body(), true);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Case(case) => {
+                self.visit_with(case.argument(), false);
+
+                for alternative in case.alternatives() {
+                    self.visit_expression(alternative.expression());
+                }
+
+                if let Some(alternative) = case.default_alternative() {
+                    self.visit_expression(alternative.expression());
+                }
+            }
+            Expression::If(if_) => {
+                self.visit_with(if_.condition(), false);
+                self.visit_expression(if_.then());
+                self.visit_expression(if_.else_());
+            }
+            Expression::LiteralCase(case) => {
+                self.visit_with(case.argument(), false);
+
+                for alternative in case.alternatives() {
+                    self.visit_expression(alternative.expression());
+                }
+
+                if let Some(alternative) = case.default_alternative() {
+                    self.visit_expression(alternative.expression());
+                }
+            }
+            Expression::SumCase(case) => {
+                self.visit_with(case.argument(), false);
+
+                for alternative in case.alternatives() {
+                    self.visit_expression(alternative.expression());
+                }
+
+                if let Some(alternative) = case.default_alternative() {
+                    self.visit_expression(alternative.expression());
+                }
+            }
+            Expression::TryOperation(operation) => {
+                if !self.allowed && self.invalid_operation.is_none() {
+                    self.invalid_operation = Some(expression.clone());
+                }
+
+                self.visit_with(operation.operand(), false);
+                self.visit_with(operation.then(), true);
+            }
+            Expression::CloneVariables(_)
+            | Expression::DropVariables(_)
+            | Expression::Let(_)
+            | Expression::LetRecord(_)
+            | Expression::LetRecursive(_) => walk_expression(self, expression),
+            _ => {
+                let outer = self.allowed;
+
+                self.allowed = false;
+                walk_expression(self, expression);
+                self.allowed = outer;
+            }
+        }
+    }
+}
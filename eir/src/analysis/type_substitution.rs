@@ -0,0 +1,66 @@
+use crate::types::{self, Type};
+use std::collections::HashMap;
+
+pub fn substitute_type(type_: &Type, substitutions: &HashMap<String, Type>) -> Type {
+    match type_ {
+        Type::Function(function) => substitute_function_type(function, substitutions).into(),
+        Type::Lazy(lazy) => types::Lazy::new(substitute_type(lazy.type_(), substitutions)).into(),
+        Type::Record(record) => types::Record::with_arguments(
+            record.name(),
+            record
+                .arguments()
+                .iter()
+                .map(|type_| substitute_type(type_, substitutions))
+                .collect(),
+        )
+        .into(),
+        Type::Tuple(tuple) => {
+            types::Tuple::from(substitute_record_body(&tuple.body(), substitutions)).into()
+        }
+        Type::Variable(name) => substitutions
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| type_.clone()),
+        Type::Boolean
+        | Type::ByteString
+        | Type::Integer(_)
+        | Type::Number
+        | Type::Sum(_)
+        | Type::Variant => type_.clone(),
+    }
+}
+
+pub fn substitute_function_type(
+    function: &types::Function,
+    substitutions: &HashMap<String, Type>,
+) -> types::Function {
+    types::Function::new(
+        substitute_type(function.argument(), substitutions),
+        substitute_type(function.result(), substitutions),
+    )
+}
+
+pub fn substitute_record_body(
+    body: &types::RecordBody,
+    substitutions: &HashMap<String, Type>,
+) -> types::RecordBody {
+    let elements = body
+        .elements()
+        .iter()
+        .map(|type_| substitute_type(type_, substitutions))
+        .collect::<Vec<_>>();
+
+    if let Some(names) = body.field_names() {
+        types::RecordBody::with_fields(names.iter().cloned().zip(elements).collect())
+    } else {
+        types::RecordBody::new(elements)
+    }
+}
+
+pub fn create_substitutions(parameters: &[String], arguments: &[Type]) -> HashMap<String, Type> {
+    parameters
+        .iter()
+        .cloned()
+        .zip(arguments.iter().cloned())
+        .collect()
+}
@@ -0,0 +1,28 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    Bincode(String),
+    InvalidHeader,
+    UnsupportedVersion(u32),
+}
+
+impl Display for EncodingError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Bincode(message) => write!(formatter, "{}", message),
+            Self::InvalidHeader => write!(formatter, "invalid header of encoded module"),
+            Self::UnsupportedVersion(version) => {
+                write!(formatter, "unsupported encoding version: {}", version)
+            }
+        }
+    }
+}
+
+impl Error for EncodingError {}
+
+impl From<bincode::Error> for EncodingError {
+    fn from(error: bincode::Error) -> Self {
+        Self::Bincode(error.to_string())
+    }
+}
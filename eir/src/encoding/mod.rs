@@ -0,0 +1,146 @@
+//! Binary encoding of modules.
+//!
+//! An encoded module starts with a header of the magic bytes `EIR\0` and a
+//! format version as a little-endian 32-bit integer followed by a module
+//! encoded by bincode. The version is bumped whenever the structure of
+//! modules changes.
+
+mod error;
+
+use crate::ir::Module;
+pub use error::EncodingError;
+use std::convert::TryInto;
+
+const MAGIC_BYTES: &[u8] = b"EIR\0";
+pub const VERSION: u32 = 18;
+
+pub fn encode(module: &Module) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = MAGIC_BYTES.to_vec();
+
+    bytes.extend(VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, module)?;
+
+    Ok(bytes)
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Module, EncodingError> {
+    let header_size = MAGIC_BYTES.len() + std::mem::size_of::<u32>();
+
+    if bytes.len() < header_size || &bytes[..MAGIC_BYTES.len()] != MAGIC_BYTES {
+        return Err(EncodingError::InvalidHeader);
+    }
+
+    let version = u32::from_le_bytes(bytes[MAGIC_BYTES.len()..header_size].try_into().unwrap());
+
+    if version != VERSION {
+        return Err(EncodingError::UnsupportedVersion(version));
+    }
+
+    Ok(bincode::deserialize(&bytes[header_size..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ir::*,
+        types::{self, Type},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn create_module() -> Module {
+        Module::new(
+            vec![TypeDefinition::new(
+                "foo",
+                types::RecordBody::new(vec![Type::Number, Type::ByteString]),
+            )],
+            vec![ForeignDeclaration::new(
+                "f",
+                "g",
+                types::Function::new(Type::Number, Type::Number),
+                CallingConvention::Target,
+            )],
+            vec![ForeignDefinition::new("h", "h")],
+            vec![Declaration::new(
+                "i",
+                types::Function::new(Type::Number, Type::Variant),
+            )],
+            vec![Definition::with_environment(
+                "h",
+                vec![Argument::new("y", Type::ByteString)],
+                vec![Argument::new("x", Type::Number)],
+                Case::new(
+                    Variant::new(
+                        types::Record::new("foo"),
+                        Record::new(
+                            types::Record::new("foo"),
+                            vec![Variable::new("x").into(), Variable::new("y").into()],
+                        ),
+                    ),
+                    vec![Alternative::new(
+                        types::Record::new("foo"),
+                        "z",
+                        RecordElement::new(types::Record::new("foo"), 0, Variable::new("z")),
+                    )],
+                    Some(DefaultAlternative::new("z", 42.0)),
+                ),
+                Type::Number,
+            )],
+        )
+    }
+
+    #[test]
+    fn encode_and_decode_module() {
+        let module = create_module();
+
+        assert_eq!(decode(&encode(&module).unwrap()), Ok(module));
+    }
+
+    #[test]
+    fn encode_and_decode_module_in_json() {
+        let module = create_module();
+
+        assert_eq!(
+            serde_json::from_str::<Module>(&serde_json::to_string(&module).unwrap()).unwrap(),
+            module
+        );
+    }
+
+    #[test]
+    fn preserve_environments_of_definitions() {
+        let module = decode(&encode(&create_module()).unwrap()).unwrap();
+
+        assert_eq!(
+            module.definitions()[0].environment(),
+            &[Argument::new("y", Type::ByteString)]
+        );
+    }
+
+    #[test]
+    fn fail_to_decode_invalid_header() {
+        assert_eq!(decode(b"foo"), Err(EncodingError::InvalidHeader));
+        assert_eq!(
+            decode(b"FOO\0\x01\0\0\0"),
+            Err(EncodingError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn fail_to_decode_unsupported_version() {
+        let mut bytes = encode(&create_module()).unwrap();
+
+        bytes[MAGIC_BYTES.len()] = 42;
+
+        assert_eq!(decode(&bytes), Err(EncodingError::UnsupportedVersion(42)));
+    }
+
+    #[test]
+    fn fail_to_decode_truncated_module() {
+        let bytes = encode(&create_module()).unwrap();
+
+        assert!(matches!(
+            decode(&bytes[..bytes.len() - 1]),
+            Err(EncodingError::Bincode(_))
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+use super::options::FormatOptions;
+
+// Documents are laid out in the style of Wadler's "A prettier printer".
+// Lines in a group are broken only if the group does not fit in the rest of
+// the current line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Document {
+    Text(String),
+    Line,
+    SoftLine,
+    Indent(Box<Document>),
+    Group(Box<Document>),
+    Sequence(Vec<Document>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub fn text(text: impl Into<String>) -> Document {
+    Document::Text(text.into())
+}
+
+pub fn line() -> Document {
+    Document::Line
+}
+
+pub fn soft_line() -> Document {
+    Document::SoftLine
+}
+
+pub fn indent(document: impl Into<Document>) -> Document {
+    Document::Indent(document.into().into())
+}
+
+pub fn group(document: impl Into<Document>) -> Document {
+    Document::Group(document.into().into())
+}
+
+pub fn sequence(documents: impl IntoIterator<Item = Document>) -> Document {
+    Document::Sequence(documents.into_iter().collect())
+}
+
+pub fn join(documents: impl IntoIterator<Item = Document>, separator: Document) -> Document {
+    let mut joined = vec![];
+
+    for (index, document) in documents.into_iter().enumerate() {
+        if index != 0 {
+            joined.push(separator.clone());
+        }
+
+        joined.push(document);
+    }
+
+    sequence(joined)
+}
+
+impl From<&str> for Document {
+    fn from(string: &str) -> Self {
+        text(string)
+    }
+}
+
+impl From<Vec<Document>> for Document {
+    fn from(documents: Vec<Document>) -> Self {
+        sequence(documents)
+    }
+}
+
+pub fn render(document: &Document, options: &FormatOptions) -> String {
+    let mut string = String::new();
+    let mut column = 0;
+    let mut stack = vec![(0, Mode::Break, document)];
+
+    while let Some((indent, mode, document)) = stack.pop() {
+        match document {
+            Document::Text(text) => {
+                string.push_str(text);
+                column += text.chars().count();
+            }
+            Document::Line | Document::SoftLine => match mode {
+                Mode::Flat => {
+                    if document == &Document::Line {
+                        string.push(' ');
+                        column += 1;
+                    }
+                }
+                Mode::Break => {
+                    string.push('\n');
+                    string.push_str(&" ".repeat(indent));
+                    column = indent;
+                }
+            },
+            Document::Indent(document) => {
+                stack.push((indent + options.indent(), mode, document));
+            }
+            Document::Group(document) => {
+                let mode = if mode == Mode::Flat
+                    || fits(
+                        options.width() as isize - column as isize,
+                        (indent, Mode::Flat, document),
+                        &stack,
+                    ) {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+
+                stack.push((indent, mode, document));
+            }
+            Document::Sequence(documents) => {
+                stack.extend(
+                    documents
+                        .iter()
+                        .rev()
+                        .map(|document| (indent, mode, document)),
+                );
+            }
+        }
+    }
+
+    string
+}
+
+// Checks if a document fits in the given width until the next line break
+// including the documents following it.
+fn fits(
+    mut width: isize,
+    next: (usize, Mode, &Document),
+    rest: &[(usize, Mode, &Document)],
+) -> bool {
+    let mut stack = vec![next];
+    let mut rest = rest.iter().rev();
+
+    while width >= 0 {
+        let (indent, mode, document) = if let Some(element) = stack.pop() {
+            element
+        } else if let Some(&element) = rest.next() {
+            element
+        } else {
+            return true;
+        };
+
+        match document {
+            Document::Text(text) => width -= text.chars().count() as isize,
+            Document::Line | Document::SoftLine => match mode {
+                Mode::Flat => {
+                    if document == &Document::Line {
+                        width -= 1;
+                    }
+                }
+                Mode::Break => return true,
+            },
+            Document::Indent(document) | Document::Group(document) => {
+                stack.push((indent, mode, document))
+            }
+            Document::Sequence(documents) => {
+                stack.extend(
+                    documents
+                        .iter()
+                        .rev()
+                        .map(|document| (indent, mode, document)),
+                );
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn list(width: usize) -> String {
+        render(
+            &group(vec![
+                "[".into(),
+                indent(vec![
+                    soft_line(),
+                    join(
+                        vec!["foo".into(), "bar".into()],
+                        vec![",".into(), line()].into(),
+                    ),
+                ]),
+                soft_line(),
+                "]".into(),
+            ]),
+            &FormatOptions::new(width, 2),
+        )
+    }
+
+    #[test]
+    fn render_flat_group() {
+        assert_eq!(list(10), "[foo, bar]");
+    }
+
+    #[test]
+    fn render_broken_group() {
+        assert_eq!(list(9), "[\n  foo,\n  bar\n]");
+    }
+
+    #[test]
+    fn break_group_followed_by_text() {
+        assert_eq!(
+            render(
+                &vec![
+                    group(vec!["foo".into(), line(), "bar".into()]),
+                    "baz".into()
+                ]
+                .into(),
+                &FormatOptions::new(8, 2),
+            ),
+            "foo\nbarbaz"
+        );
+    }
+}
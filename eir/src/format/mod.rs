@@ -0,0 +1,385 @@
+//! Pretty-printer for the textual syntax of eir modules.
+//!
+//! Its output can be parsed back by the [`parse`](crate::parse) module into
+//! equal modules. `Display` implementations of modules, definitions,
+//! expressions and types use the default options and take a line width from
+//! a format specifier if any, e.g. `format!("{:40}", module)`.
+
+mod document;
+mod options;
+mod printer;
+
+use crate::{ir::*, types::Type};
+use document::*;
+pub use options::FormatOptions;
+use std::fmt::{self, Display, Formatter};
+
+pub fn format_module(module: &Module, options: &FormatOptions) -> String {
+    printer::module(module)
+        .into_iter()
+        .enumerate()
+        .map(|(index, section)| {
+            section
+                .into_iter()
+                .map(|document| render(&document, options))
+                .collect::<Vec<_>>()
+                // Definitions are separated by empty lines.
+                .join(if index == 5 { "\n\n" } else { "\n" })
+        })
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+pub fn format_definition(definition: &Definition, options: &FormatOptions) -> String {
+    render(
+        &sequence([
+            text(if definition.is_thunk() {
+                "thunk "
+            } else {
+                "define "
+            }),
+            printer::definition_body(definition),
+        ]),
+        options,
+    )
+}
+
+pub fn format_expression(expression: &Expression, options: &FormatOptions) -> String {
+    render(&printer::expression(expression), options)
+}
+
+pub fn format_type(type_: &Type, options: &FormatOptions) -> String {
+    render(&printer::type_(type_), options)
+}
+
+fn options(formatter: &Formatter) -> FormatOptions {
+    let options = FormatOptions::default();
+
+    FormatOptions::new(
+        formatter.width().unwrap_or_else(|| options.width()),
+        options.indent(),
+    )
+}
+
+impl Display for Module {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", format_module(self, &options(formatter)))
+    }
+}
+
+impl Display for Definition {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}",
+            format_definition(self, &options(formatter))
+        )
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}",
+            format_expression(self, &options(formatter))
+        )
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", format_type(self, &options(formatter)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::{parse, parse_expression},
+        types,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn format_with_width(expression: impl Into<Expression>, width: usize) -> String {
+        format_expression(&expression.into(), &FormatOptions::new(width, 2))
+    }
+
+    #[test]
+    fn format_types() {
+        assert_eq!(
+            Type::from(types::Function::new(
+                types::Function::new(Type::Number, Type::Boolean),
+                types::Function::new(types::Record::new("foo"), Type::Variant)
+            ))
+            .to_string(),
+            "(number -> boolean) -> foo -> variant"
+        );
+        assert_eq!(
+            Type::from(types::Record::new("foo.bar")).to_string(),
+            "`foo.bar`"
+        );
+        assert_eq!(Type::ByteString.to_string(), "string");
+    }
+
+    #[test]
+    fn format_literals() {
+        assert_eq!(Expression::from(42.0).to_string(), "42.0");
+        assert_eq!(Expression::from(-1e-7).to_string(), "-1e-7");
+        assert_eq!(Expression::from(f64::NAN).to_string(), "nan");
+        assert_eq!(Expression::from(f64::NEG_INFINITY).to_string(), "-infinity");
+        assert_eq!(
+            Expression::from(ByteString::new(b"a\"\\\n\x00\xff".to_vec())).to_string(),
+            r#""a\"\\\n\x00\xff""#
+        );
+    }
+
+    #[test]
+    fn format_operations_with_parentheses() {
+        assert_eq!(
+            Expression::from(ArithmeticOperation::new(
+                Type::Number,
+                ArithmeticOperator::Multiply,
+                ArithmeticOperation::new(Type::Number, ArithmeticOperator::Add, 1.0, 2.0),
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Subtract,
+                    Variable::new("x"),
+                    -1.0
+                ),
+            ))
+            .to_string(),
+            "(1.0 + 2.0) * (x - -1.0)"
+        );
+        assert_eq!(
+            Expression::from(ComparisonOperation::new(
+                Type::Number,
+                ComparisonOperator::Equal,
+                If::new(true, 1.0, 2.0),
+                Let::new("x", Type::Number, 1.0, Variable::new("x"))
+            ))
+            .to_string(),
+            "(if true then 1.0 else 2.0) == (let x: number = 1.0 in x)"
+        );
+    }
+
+    #[test]
+    fn format_let_with_line_breaks() {
+        assert_eq!(
+            format_with_width(
+                Let::new(
+                    "x",
+                    Type::Number,
+                    42.0,
+                    Let::new("y", Type::Number, Variable::new("x"), Variable::new("y"))
+                ),
+                30
+            ),
+            "let x: number = 42.0 in\nlet y: number = x in\ny"
+        );
+    }
+
+    #[test]
+    fn format_case_with_line_breaks() {
+        assert_eq!(
+            format_with_width(
+                Case::new(
+                    Variable::new("x"),
+                    vec![Alternative::new(Type::Number, "y", Variable::new("y"))],
+                    Some(DefaultAlternative::new("z", 42.0))
+                ),
+                20
+            ),
+            "case x of {\n  y: number => y,\n  z => 42.0\n}"
+        );
+    }
+
+    #[test]
+    fn format_definition_with_line_breaks() {
+        assert_eq!(
+            format_definition(
+                &Definition::new(
+                    "f",
+                    vec![
+                        Argument::new("x", Type::Number),
+                        Argument::new("y", Type::Number)
+                    ],
+                    ArithmeticOperation::new(
+                        Type::Number,
+                        ArithmeticOperator::Add,
+                        Variable::new("x"),
+                        Variable::new("y")
+                    ),
+                    Type::Number
+                ),
+                &FormatOptions::new(20, 2)
+            ),
+            "define f(\n  x: number,\n  y: number\n): number =\n  x + y"
+        );
+    }
+
+    #[test]
+    fn format_with_width_from_formatter() {
+        let expression = Expression::from(Record::new(
+            types::Record::new("foo"),
+            vec![1.0.into(), 2.0.into()],
+        ));
+
+        assert_eq!(format!("{}", expression), "foo{1.0, 2.0}");
+        assert_eq!(format!("{:10}", expression), "foo{\n  1.0,\n  2.0\n}");
+    }
+
+    #[test]
+    fn round_trip_module() {
+        let source = r#"
+            type foo = {number, boolean, `type`}
+            foreign declare source f: number -> number = "f"
+            foreign declare target g: (number -> number) -> string = "\x01"
+            foreign define h = "h"
+            declare i: foo -> number -> foo
+
+            define h[z: number](x: number, y: string): number =
+              let a: number = x + y * z - (x - -1) in
+              letrec thunk b(w: number): variant = variant[number](w) in
+              if a == 0 then
+                case b[number -> variant](a) of {
+                  c: number => c / 2,
+                  d: foo => d.foo.0,
+                  e => f[number -> number](e)
+                }
+              else
+                clone {x: number, y: string} in drop {z: number} in foo{x, true, y}.foo.0
+
+            thunk j(x: number): string = "foo\n\"bar\""
+
+            type bar = {x: number, `y.z`: box<number>}
+            type box<'a> = {'a, boolean}
+            define id<'a>(x: 'a): 'a = x
+            define k(x: number): box<number> =
+              box<number>{id<number>[number -> number](x), true}
+
+            sum shape = foo | box<number>
+            define l(x: sum shape): sum shape =
+              case[shape] x of {
+                y: foo => sum[shape, foo](y),
+                z => z
+              }
+            define m(x: string): u64 =
+              case[string] x of { "foo" => 1u64, "" => 0u64, y => length(y) }
+            define n(x: boolean): i8 =
+              case[boolean] x of { true => -1i8, false => 0i8 }
+            define o(x: bar): number =
+              let bar{y: number, z: box<number>} = x in
+              let {number, string}{a: number, b: string} = {number, string}{y, "b"} in a
+            define p(x: variant): variant =
+              try x of { e: foo => variant[foo](e) }
+            define q: number = h[number -> string -> number](42)[string -> number]("")
+            define r(x: lazy number): lazy number = lazy[number](force(x) + 1)
+            define s(x: {a: number, b: string}): string = x.{a: number, b: string}.b
+        "#;
+        let module = parse(source).unwrap();
+
+        for width in [0, 20, 40, 80, 1000] {
+            let formatted = format_module(&module, &FormatOptions::new(width, 4));
+
+            assert_eq!(parse(&formatted), Ok(module.clone()), "{}", formatted);
+        }
+    }
+
+    #[test]
+    fn round_trip_nested_expressions() {
+        for source in [
+            "f[number -> number -> number](1)[number -> number](2)",
+            "(let x: number = 1 in x).foo.0",
+            "(if x then 1 else 2)[number -> number](3)",
+            "1 - (2 - 3)",
+            "1 / (2 * 3)",
+            "(1 < 2) == (3 > 4)",
+            "-0.0",
+            "case case x of {} of {}",
+            "(try x of { e: number => e }).foo.0",
+            "x +[i32] -2147483648i32 *[i32] 255i32",
+            "(x <[u8] 255u8) == (y >=[i64] -1i64)",
+            "convert[number, i64, round](x).foo.0",
+            "x || y && !(z || w)",
+            "(x || y) && !!z",
+            "x && (y && z)",
+            "(x && y) == !z",
+            "-1 * !x",
+            "x ^ y ^ z",
+            "(x ^ y) ^ z",
+            "(-x) ^ -y",
+            "-1 ^ 2 % 3",
+            "-(1) - -(-1)",
+            "--[i32] x ^[i32] 2i32",
+            "-[u8] (255u8) %[u8] 2u8",
+            "convert[u8, number](convert[i32, u8](x)) * 2",
+            "slice(concat(x, \"foo\"), 1u64, length(x)).foo.0",
+            "length(x) ==[u64] 0u64 || x >=[string] y",
+            "(x <[boolean] y) !=[boolean] !z",
+            "letrec f(x: number): number = g[number -> number](x) \
+             and thunk g(x: number): number = f[number -> number](x) in f",
+            "id<number>[number -> number](x) < y",
+            "{number, {}}{1, {}{}}.{number, {}}.1",
+            "x.bar.`y.z`.box<number>.0",
+            "(if x then y else z).bar{x = 1 + 2, `y.z` = w}.{}{}",
+            "box<number -> number>{f}.box<number -> number>.0[number -> number](x)",
+            "force(lazy[lazy (number -> number)](lazy[number -> number](f)))[number -> number](x)",
+            "lazy[{}](force(x).{}{})",
+        ] {
+            let expression = parse_expression(source).unwrap();
+
+            assert_eq!(
+                parse_expression(&expression.to_string()),
+                Ok(expression.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn format_integers() {
+        assert_eq!(
+            Expression::from(Integer::new(types::Integer::Signed8, -128i64 as u64)).to_string(),
+            "-128i8"
+        );
+        assert_eq!(
+            Expression::from(Integer::new(types::Integer::Unsigned64, u64::MAX)).to_string(),
+            "18446744073709551615u64"
+        );
+        assert_eq!(
+            Expression::from(ArithmeticOperation::new(
+                types::Integer::Unsigned32,
+                ArithmeticOperator::Add,
+                Variable::new("x"),
+                Integer::new(types::Integer::Unsigned32, 1)
+            ))
+            .to_string(),
+            "x +[u32] 1u32"
+        );
+    }
+
+    #[test]
+    fn format_byte_string_operations_with_line_breaks() {
+        assert_eq!(
+            format_with_width(
+                ByteStringSlice::new(
+                    ByteStringConcatenation::new(Variable::new("x"), Variable::new("y")),
+                    Variable::new("i"),
+                    ByteStringLength::new(Variable::new("x"))
+                ),
+                20
+            ),
+            "slice(\n  concat(x, y),\n  i,\n  length(x)\n)"
+        );
+    }
+
+    #[test]
+    fn format_empty_module() {
+        assert_eq!(
+            Module::new(vec![], vec![], vec![], vec![], vec![]).to_string(),
+            ""
+        );
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    width: usize,
+    indent: usize,
+}
+
+impl FormatOptions {
+    pub fn new(width: usize, indent: usize) -> Self {
+        Self { width, indent }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn indent(&self) -> usize {
+        self.indent
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new(80, 2)
+    }
+}
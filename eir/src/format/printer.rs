@@ -0,0 +1,804 @@
+use super::document::*;
+use crate::{
+    ir::*,
+    parse::is_name,
+    types::{self, Type},
+};
+use std::collections::HashMap;
+
+// Precedences of expressions from the loosest to the tightest.
+const EXPRESSION: usize = 0;
+const DISJUNCTION: usize = 1;
+const CONJUNCTION: usize = 2;
+const COMPARISON: usize = 3;
+const ADDITIVE: usize = 4;
+const MULTIPLICATIVE: usize = 5;
+const PREFIX: usize = 6;
+const POWER: usize = 7;
+const POSTFIX: usize = 8;
+
+pub fn module(module: &Module) -> Vec<Vec<Document>> {
+    vec![
+        module
+            .type_definitions()
+            .iter()
+            .map(type_definition)
+            .collect(),
+        module
+            .sum_definitions()
+            .iter()
+            .map(sum_definition)
+            .collect(),
+        module
+            .foreign_declarations()
+            .iter()
+            .map(foreign_declaration)
+            .collect(),
+        module
+            .foreign_definitions()
+            .iter()
+            .map(foreign_definition)
+            .collect(),
+        module.declarations().iter().map(declaration).collect(),
+        module
+            .definitions()
+            .iter()
+            .map(|definition| {
+                sequence([
+                    text(if definition.is_thunk() {
+                        "thunk "
+                    } else {
+                        "define "
+                    }),
+                    definition_body(definition),
+                ])
+            })
+            .collect(),
+    ]
+}
+
+fn type_definition(definition: &TypeDefinition) -> Document {
+    group(vec![
+        text(format!("type {}", name(definition.name()))),
+        type_parameters(definition.parameters()),
+        " = {".into(),
+        indent(vec![
+            soft_line(),
+            join(
+                definition
+                    .type_()
+                    .elements()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, element)| match definition.type_().field_names() {
+                        Some(names) => {
+                            sequence([text(format!("{}: ", name(&names[index]))), type_(element)])
+                        }
+                        None => type_(element),
+                    }),
+                vec![",".into(), line()].into(),
+            ),
+        ]),
+        soft_line(),
+        "}".into(),
+    ])
+}
+
+fn sum_definition(definition: &SumDefinition) -> Document {
+    group(vec![
+        text(format!("sum {} =", name(definition.name()))),
+        indent(vec![
+            line(),
+            join(
+                definition.constructors().iter().map(record_type),
+                vec![line(), "| ".into()].into(),
+            ),
+        ]),
+    ])
+}
+
+fn foreign_declaration(declaration: &ForeignDeclaration) -> Document {
+    sequence([
+        text(format!(
+            "foreign declare {} {}: ",
+            match declaration.calling_convention() {
+                CallingConvention::Source => "source",
+                CallingConvention::Target => "target",
+            },
+            name(declaration.name())
+        )),
+        function_type(declaration.type_()),
+        text(format!(
+            " = {}",
+            byte_string(declaration.foreign_name().as_bytes())
+        )),
+    ])
+}
+
+fn foreign_definition(definition: &ForeignDefinition) -> Document {
+    text(format!(
+        "foreign define {} = {}",
+        name(definition.name()),
+        byte_string(definition.foreign_name().as_bytes())
+    ))
+}
+
+fn declaration(declaration: &Declaration) -> Document {
+    sequence([
+        text(format!("declare {}: ", name(declaration.name()))),
+        function_type(declaration.type_()),
+    ])
+}
+
+pub fn definition_body(definition: &Definition) -> Document {
+    group(vec![
+        text(name(definition.name())),
+        type_parameters(definition.type_parameters()),
+        if definition.environment().is_empty() {
+            sequence([])
+        } else {
+            arguments("[", definition.environment(), "]")
+        },
+        if definition.is_constant() {
+            sequence([])
+        } else {
+            arguments("(", definition.arguments(), ")")
+        },
+        ": ".into(),
+        type_(definition.result_type()),
+        " =".into(),
+        indent(vec![line(), expression(definition.body())]),
+    ])
+}
+
+fn arguments(open: &str, arguments: &[Argument], close: &str) -> Document {
+    group(vec![
+        open.into(),
+        indent(vec![
+            soft_line(),
+            join(
+                arguments.iter().map(|argument| {
+                    sequence([
+                        text(format!("{}: ", name(argument.name()))),
+                        type_(argument.type_()),
+                    ])
+                }),
+                vec![",".into(), line()].into(),
+            ),
+        ]),
+        soft_line(),
+        close.into(),
+    ])
+}
+
+fn type_parameters(parameters: &[String]) -> Document {
+    if parameters.is_empty() {
+        sequence([])
+    } else {
+        text(format!(
+            "<{}>",
+            parameters
+                .iter()
+                .map(|parameter| type_variable(parameter))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+fn type_arguments(arguments: &[Type]) -> Document {
+    if arguments.is_empty() {
+        sequence([])
+    } else {
+        sequence([
+            "<".into(),
+            join(arguments.iter().map(type_), ", ".into()),
+            ">".into(),
+        ])
+    }
+}
+
+fn record_type(record: &types::Record) -> Document {
+    sequence([
+        text(name(record.name())),
+        type_arguments(record.arguments()),
+    ])
+}
+
+fn tuple_type(tuple: &types::Tuple) -> Document {
+    sequence([
+        "{".into(),
+        join(
+            tuple
+                .elements()
+                .iter()
+                .enumerate()
+                .map(|(index, element)| match tuple.field_names() {
+                    Some(names) => {
+                        sequence([text(format!("{}: ", name(&names[index]))), type_(element)])
+                    }
+                    None => type_(element),
+                }),
+            ", ".into(),
+        ),
+        "}".into(),
+    ])
+}
+
+fn type_variable(name: &str) -> String {
+    format!("'{}", self::name(name))
+}
+
+pub fn type_(type_: &Type) -> Document {
+    match type_ {
+        Type::Boolean => "boolean".into(),
+        Type::ByteString => "string".into(),
+        Type::Function(function) => function_type(function),
+        Type::Integer(integer) => integer_type(*integer).into(),
+        Type::Lazy(lazy) => sequence([
+            "lazy ".into(),
+            match lazy.type_() {
+                Type::Function(function) => {
+                    sequence(["(".into(), function_type(function), ")".into()])
+                }
+                type_ => self::type_(type_),
+            },
+        ]),
+        Type::Number => "number".into(),
+        Type::Record(record) => record_type(record),
+        Type::Sum(sum) => text(format!("sum {}", name(sum.name()))),
+        Type::Tuple(tuple) => tuple_type(tuple),
+        Type::Variable(name) => text(type_variable(name)),
+        Type::Variant => "variant".into(),
+    }
+}
+
+fn function_type(function: &types::Function) -> Document {
+    sequence([
+        match function.argument() {
+            Type::Function(argument) => sequence(["(".into(), function_type(argument), ")".into()]),
+            argument => type_(argument),
+        },
+        " -> ".into(),
+        type_(function.result()),
+    ])
+}
+
+pub fn expression(expression: &Expression) -> Document {
+    match expression {
+        Expression::ArithmeticOperation(operation) => {
+            let (operator, lhs_precedence, rhs_precedence) = match operation.operator() {
+                ArithmeticOperator::Add => ("+", ADDITIVE, MULTIPLICATIVE),
+                ArithmeticOperator::Subtract => ("-", ADDITIVE, MULTIPLICATIVE),
+                ArithmeticOperator::Multiply => ("*", MULTIPLICATIVE, PREFIX),
+                ArithmeticOperator::Divide => ("/", MULTIPLICATIVE, PREFIX),
+                ArithmeticOperator::Remainder => ("%", MULTIPLICATIVE, PREFIX),
+                // Powers are right-associative.
+                ArithmeticOperator::Power => ("^", POSTFIX, PREFIX),
+            };
+
+            binary_operation(
+                operation.lhs(),
+                typed_operator(operator, operation.type_()),
+                operation.rhs(),
+                lhs_precedence,
+                rhs_precedence,
+            )
+        }
+        Expression::Boolean(boolean) => text(boolean.to_string()),
+        Expression::BooleanOperation(operation) => {
+            let (operator, precedence) = match operation.operator() {
+                BooleanOperator::And => ("&&", CONJUNCTION),
+                BooleanOperator::Or => ("||", DISJUNCTION),
+            };
+
+            binary_operation(
+                operation.lhs(),
+                text(operator),
+                operation.rhs(),
+                precedence,
+                precedence + 1,
+            )
+        }
+        Expression::ByteString(string) => text(byte_string(string.value())),
+        Expression::ByteStringConcatenation(concatenation) => {
+            call("concat", [concatenation.lhs(), concatenation.rhs()])
+        }
+        Expression::ByteStringLength(length) => call("length", [length.string()]),
+        Expression::ByteStringSlice(slice) => {
+            call("slice", [slice.string(), slice.start(), slice.end()])
+        }
+        Expression::Case(case) => self::case(
+            "case".into(),
+            case.argument(),
+            alternatives(case.alternatives(), case.default_alternative()),
+        ),
+        Expression::CloneVariables(_) => group(binding(expression)),
+        Expression::ComparisonOperation(operation) => binary_operation(
+            operation.lhs(),
+            typed_operator(
+                match operation.operator() {
+                    ComparisonOperator::Equal => "==",
+                    ComparisonOperator::NotEqual => "!=",
+                    ComparisonOperator::LessThan => "<",
+                    ComparisonOperator::LessThanOrEqual => "<=",
+                    ComparisonOperator::GreaterThan => ">",
+                    ComparisonOperator::GreaterThanOrEqual => ">=",
+                },
+                operation.type_(),
+            ),
+            operation.rhs(),
+            ADDITIVE,
+            ADDITIVE,
+        ),
+        Expression::DropVariables(_) => group(binding(expression)),
+        Expression::Force(force) => call("force", [force.expression()]),
+        Expression::FunctionApplication(application) => sequence([
+            operand(application.function(), POSTFIX),
+            type_arguments(application.type_arguments()),
+            "[".into(),
+            function_type(application.type_()),
+            "]".into(),
+            parenthesized(application.argument()),
+        ]),
+        Expression::If(if_) => group(vec![
+            group(vec![
+                "if".into(),
+                indent(vec![line(), self::expression(if_.condition())]),
+                line(),
+                "then".into(),
+            ]),
+            indent(vec![line(), self::expression(if_.then())]),
+            line(),
+            "else".into(),
+            indent(vec![line(), self::expression(if_.else_())]),
+        ]),
+        Expression::Lazy(lazy) => sequence([
+            "lazy[".into(),
+            type_(lazy.type_()),
+            "]".into(),
+            parenthesized(lazy.expression()),
+        ]),
+        Expression::Let(_) | Expression::LetRecord(_) | Expression::LetRecursive(_) => {
+            group(binding(expression))
+        }
+        Expression::Integer(integer) => text(self::integer(integer)),
+        Expression::LiteralCase(case) => self::case(
+            sequence(["case[".into(), type_(case.type_()), "]".into()]),
+            case.argument(),
+            literal_alternatives(case.alternatives(), case.default_alternative()),
+        ),
+        Expression::Negation(negation) => sequence([
+            typed_operator("-", negation.type_()),
+            if negation.type_() == &Type::Number {
+                sequence([])
+            } else {
+                " ".into()
+            },
+            match negation.expression() {
+                // Negated literals are distinguished from negative ones.
+                expression @ (Expression::Integer(_) | Expression::Number(_)) => {
+                    sequence(["(".into(), self::expression(expression), ")".into()])
+                }
+                expression => operand(expression, PREFIX),
+            },
+        ]),
+        Expression::Not(not) => sequence(["!".into(), operand(not.expression(), PREFIX)]),
+        Expression::Number(number) => text(self::number(*number)),
+        Expression::NumericConversion(conversion) => sequence([
+            "convert[".into(),
+            type_(conversion.from()),
+            ", ".into(),
+            type_(conversion.to()),
+            match conversion.rounding() {
+                Rounding::Ceiling => ", ceiling".into(),
+                Rounding::Floor => ", floor".into(),
+                Rounding::Round => ", round".into(),
+                Rounding::Truncate => sequence([]),
+            },
+            "]".into(),
+            parenthesized(conversion.argument()),
+        ]),
+        Expression::Record(record) => group(vec![
+            type_(record.type_()),
+            "{".into(),
+            indent(vec![
+                soft_line(),
+                join(
+                    record.elements().iter().map(self::expression),
+                    vec![",".into(), line()].into(),
+                ),
+            ]),
+            soft_line(),
+            "}".into(),
+        ]),
+        Expression::RecordElement(element) => sequence([
+            operand(element.record(), POSTFIX),
+            ".".into(),
+            type_(element.type_()),
+            text(format!(".{}", record_field(element.field()))),
+        ]),
+        Expression::RecordUpdate(update) => group(vec![
+            operand(update.record(), POSTFIX),
+            ".".into(),
+            type_(update.type_()),
+            "{".into(),
+            indent(vec![
+                soft_line(),
+                join(
+                    update.fields().iter().map(|field| {
+                        sequence([
+                            text(format!("{} = ", record_field(field.field()))),
+                            self::expression(field.expression()),
+                        ])
+                    }),
+                    vec![",".into(), line()].into(),
+                ),
+            ]),
+            soft_line(),
+            "}".into(),
+        ]),
+        Expression::Sum(sum) => sequence([
+            text(format!("sum[{}, ", name(sum.type_().name()))),
+            record_type(sum.constructor()),
+            "]".into(),
+            parenthesized(sum.payload()),
+        ]),
+        Expression::SumCase(case) => self::case(
+            text(format!("case[{}]", name(case.type_().name()))),
+            case.argument(),
+            alternatives(case.alternatives(), case.default_alternative()),
+        ),
+        Expression::TryOperation(operation) => self::case(
+            "try".into(),
+            operation.operand(),
+            vec![alternative_body(
+                sequence([
+                    text(format!("{}: ", name(operation.name()))),
+                    type_(operation.type_()),
+                ]),
+                operation.then(),
+            )],
+        ),
+        Expression::Variable(variable) => text(name(variable.name())),
+        Expression::Variant(variant) => sequence([
+            "variant[".into(),
+            type_(variant.type_()),
+            "]".into(),
+            parenthesized(variant.payload()),
+        ]),
+    }
+}
+
+fn case(keyword: Document, argument: &Expression, alternatives: Vec<Document>) -> Document {
+    group(vec![
+        group(vec![
+            keyword,
+            indent(vec![line(), self::expression(argument)]),
+            line(),
+            "of {".into(),
+        ]),
+        if alternatives.is_empty() {
+            sequence([])
+        } else {
+            sequence([
+                indent(vec![
+                    line(),
+                    join(alternatives, vec![",".into(), line()].into()),
+                ]),
+                line(),
+            ])
+        },
+        "}".into(),
+    ])
+}
+
+fn alternatives(
+    alternatives: &[Alternative],
+    default_alternative: Option<&DefaultAlternative>,
+) -> Vec<Document> {
+    alternatives
+        .iter()
+        .map(|alternative| {
+            alternative_body(
+                sequence([
+                    text(format!("{}: ", name(alternative.name()))),
+                    type_(alternative.type_()),
+                ]),
+                alternative.expression(),
+            )
+        })
+        .chain(default_alternative.map(self::default_alternative))
+        .collect()
+}
+
+fn literal_alternatives(
+    alternatives: &[LiteralAlternative],
+    default_alternative: Option<&DefaultAlternative>,
+) -> Vec<Document> {
+    alternatives
+        .iter()
+        .map(|alternative| {
+            alternative_body(
+                self::expression(&alternative.literal().clone().into()),
+                alternative.expression(),
+            )
+        })
+        .chain(default_alternative.map(self::default_alternative))
+        .collect()
+}
+
+fn default_alternative(alternative: &DefaultAlternative) -> Document {
+    alternative_body(text(name(alternative.name())), alternative.expression())
+}
+
+fn record_field(field: &RecordField) -> String {
+    match field {
+        RecordField::Index(index) => index.to_string(),
+        RecordField::Name(field_name) => name(field_name),
+    }
+}
+
+fn precedence(expression: &Expression) -> usize {
+    match expression {
+        Expression::ArithmeticOperation(operation) => match operation.operator() {
+            ArithmeticOperator::Add | ArithmeticOperator::Subtract => ADDITIVE,
+            ArithmeticOperator::Multiply
+            | ArithmeticOperator::Divide
+            | ArithmeticOperator::Remainder => MULTIPLICATIVE,
+            ArithmeticOperator::Power => POWER,
+        },
+        Expression::BooleanOperation(operation) => match operation.operator() {
+            BooleanOperator::And => CONJUNCTION,
+            BooleanOperator::Or => DISJUNCTION,
+        },
+        Expression::ComparisonOperation(_) => COMPARISON,
+        Expression::Negation(_) | Expression::Not(_) => PREFIX,
+        Expression::CloneVariables(_)
+        | Expression::DropVariables(_)
+        | Expression::If(_)
+        | Expression::Let(_)
+        | Expression::LetRecord(_)
+        | Expression::LetRecursive(_) => EXPRESSION,
+        Expression::Boolean(_)
+        | Expression::ByteString(_)
+        | Expression::ByteStringConcatenation(_)
+        | Expression::ByteStringLength(_)
+        | Expression::ByteStringSlice(_)
+        | Expression::Case(_)
+        | Expression::Force(_)
+        | Expression::FunctionApplication(_)
+        | Expression::Integer(_)
+        | Expression::Lazy(_)
+        | Expression::LiteralCase(_)
+        | Expression::Number(_)
+        | Expression::NumericConversion(_)
+        | Expression::Record(_)
+        | Expression::RecordElement(_)
+        | Expression::RecordUpdate(_)
+        | Expression::Sum(_)
+        | Expression::SumCase(_)
+        | Expression::TryOperation(_)
+        | Expression::Variable(_)
+        | Expression::Variant(_) => POSTFIX,
+    }
+}
+
+// Formats an expression as an operand wrapping it with parentheses if its
+// precedence is lower than the given one.
+fn operand(expression: &Expression, precedence: usize) -> Document {
+    if self::precedence(expression) < precedence {
+        sequence(["(".into(), self::expression(expression), ")".into()])
+    } else {
+        self::expression(expression)
+    }
+}
+
+fn binary_operation(
+    lhs: &Expression,
+    operator: Document,
+    rhs: &Expression,
+    lhs_precedence: usize,
+    rhs_precedence: usize,
+) -> Document {
+    group(vec![
+        operand(lhs, lhs_precedence),
+        indent(vec![
+            line(),
+            operator,
+            " ".into(),
+            operand(rhs, rhs_precedence),
+        ]),
+    ])
+}
+
+// Operand types other than a number type are annotated.
+fn typed_operator(operator: &str, type_: &Type) -> Document {
+    if type_ == &Type::Number {
+        text(operator)
+    } else {
+        sequence([text(operator), "[".into(), self::type_(type_), "]".into()])
+    }
+}
+
+fn call<'a>(name: &str, arguments: impl IntoIterator<Item = &'a Expression>) -> Document {
+    group(vec![
+        text(format!("{}(", name)),
+        indent(vec![
+            soft_line(),
+            join(
+                arguments.into_iter().map(self::expression),
+                vec![",".into(), line()].into(),
+            ),
+        ]),
+        soft_line(),
+        ")".into(),
+    ])
+}
+
+fn parenthesized(expression: &Expression) -> Document {
+    group(vec![
+        "(".into(),
+        indent(vec![soft_line(), self::expression(expression)]),
+        soft_line(),
+        ")".into(),
+    ])
+}
+
+fn alternative_body(pattern: Document, expression: &Expression) -> Document {
+    group(vec![
+        pattern,
+        " =>".into(),
+        indent(vec![line(), self::expression(expression)]),
+    ])
+}
+
+// Formats a chain of bindings so that its bodies are broken into lines
+// altogether.
+fn binding(expression: &Expression) -> Document {
+    let (header, body) = match expression {
+        Expression::CloneVariables(clone) => {
+            (variables("clone", clone.variables()), clone.expression())
+        }
+        Expression::DropVariables(drop) => (variables("drop", drop.variables()), drop.expression()),
+        Expression::Let(let_) => (
+            group(vec![
+                text(format!("let {}: ", name(let_.name()))),
+                type_(let_.type_()),
+                " =".into(),
+                indent(vec![line(), self::expression(let_.bound_expression())]),
+                line(),
+                "in".into(),
+            ]),
+            let_.expression(),
+        ),
+        Expression::LetRecord(let_) => (
+            group(vec![
+                "let ".into(),
+                type_(let_.type_()),
+                arguments("{", let_.elements(), "}"),
+                " =".into(),
+                indent(vec![line(), self::expression(let_.bound_expression())]),
+                line(),
+                "in".into(),
+            ]),
+            let_.expression(),
+        ),
+        Expression::LetRecursive(let_) => (
+            group(vec![
+                join(
+                    let_.definitions()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, definition)| {
+                            sequence([
+                                text(match (index, definition.is_thunk()) {
+                                    (0, false) => "letrec ",
+                                    (0, true) => "letrec thunk ",
+                                    (_, false) => "and ",
+                                    (_, true) => "and thunk ",
+                                }),
+                                definition_body(definition),
+                            ])
+                        }),
+                    line(),
+                ),
+                line(),
+                "in".into(),
+            ]),
+            let_.expression(),
+        ),
+        _ => return self::expression(expression),
+    };
+
+    sequence([header, line(), binding(body)])
+}
+
+fn variables(keyword: &str, variables: &HashMap<String, Type>) -> Document {
+    let mut variables = variables.iter().collect::<Vec<_>>();
+
+    variables.sort_by_key(|(name, _)| *name);
+
+    group(vec![
+        text(format!("{} {{", keyword)),
+        indent(vec![
+            soft_line(),
+            join(
+                variables.into_iter().map(|(variable, type_)| {
+                    sequence([text(format!("{}: ", name(variable))), self::type_(type_)])
+                }),
+                vec![",".into(), line()].into(),
+            ),
+        ]),
+        soft_line(),
+        "} in".into(),
+    ])
+}
+
+fn integer_type(integer: types::Integer) -> &'static str {
+    match integer {
+        types::Integer::Signed8 => "i8",
+        types::Integer::Signed32 => "i32",
+        types::Integer::Signed64 => "i64",
+        types::Integer::Unsigned8 => "u8",
+        types::Integer::Unsigned32 => "u32",
+        types::Integer::Unsigned64 => "u64",
+    }
+}
+
+fn integer(integer: &Integer) -> String {
+    let type_ = integer.type_();
+    let shift = 64 - type_.bits();
+
+    if type_.is_signed() {
+        format!(
+            "{}{}",
+            ((integer.value() << shift) as i64) >> shift,
+            integer_type(type_)
+        )
+    } else {
+        format!("{}{}", integer.value(), integer_type(type_))
+    }
+}
+
+fn number(number: f64) -> String {
+    if number.is_nan() {
+        "nan".into()
+    } else if number.is_infinite() {
+        if number.is_sign_positive() {
+            "infinity".into()
+        } else {
+            "-infinity".into()
+        }
+    } else {
+        format!("{:?}", number)
+    }
+}
+
+fn byte_string(bytes: &[u8]) -> String {
+    let mut string = String::from("\"");
+
+    for &byte in bytes {
+        match byte {
+            b'"' => string.push_str("\\\""),
+            b'\\' => string.push_str("\\\\"),
+            b'\n' => string.push_str("\\n"),
+            b'\r' => string.push_str("\\r"),
+            b'\t' => string.push_str("\\t"),
+            0x20..=0x7e => string.push(byte as char),
+            _ => string.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+
+    string.push('"');
+
+    string
+}
+
+fn name(name: &str) -> String {
+    if is_name(name) {
+        name.into()
+    } else {
+        format!("`{}`", name.replace('\\', "\\\\").replace('`', "\\`"))
+    }
+}
@@ -2,6 +2,7 @@ use super::expression::Expression;
 use crate::types::Type;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Alternative {
     type_: Type,
     name: String,
@@ -1,6 +1,7 @@
 use crate::types::Type;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Argument {
     name: String,
     type_: Type,
@@ -1,8 +1,11 @@
 use super::{arithmetic_operator::ArithmeticOperator, expression::Expression};
+use crate::types::Type;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ArithmeticOperation {
+    type_: Type,
     operator: ArithmeticOperator,
     lhs: Arc<Expression>,
     rhs: Arc<Expression>,
@@ -10,17 +13,23 @@ pub struct ArithmeticOperation {
 
 impl ArithmeticOperation {
     pub fn new(
+        type_: impl Into<Type>,
         operator: ArithmeticOperator,
         lhs: impl Into<Expression>,
         rhs: impl Into<Expression>,
     ) -> Self {
         Self {
+            type_: type_.into(),
             operator,
             lhs: Arc::new(lhs.into()),
             rhs: Arc::new(rhs.into()),
         }
     }
 
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
     pub fn operator(&self) -> ArithmeticOperator {
         self.operator
     }
@@ -1,7 +1,11 @@
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ArithmeticOperator {
     Add,
     Subtract,
     Multiply,
     Divide,
+    // Remainders have the same signs as dividends.
+    Remainder,
+    Power,
 }
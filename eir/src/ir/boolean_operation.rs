@@ -0,0 +1,38 @@
+use super::{boolean_operator::BooleanOperator, expression::Expression};
+use std::sync::Arc;
+
+// Right-hand sides are evaluated only if left-hand sides do not determine
+// results.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct BooleanOperation {
+    operator: BooleanOperator,
+    lhs: Arc<Expression>,
+    rhs: Arc<Expression>,
+}
+
+impl BooleanOperation {
+    pub fn new(
+        operator: BooleanOperator,
+        lhs: impl Into<Expression>,
+        rhs: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            operator,
+            lhs: Arc::new(lhs.into()),
+            rhs: Arc::new(rhs.into()),
+        }
+    }
+
+    pub fn operator(&self) -> BooleanOperator {
+        self.operator
+    }
+
+    pub fn lhs(&self) -> &Expression {
+        &self.lhs
+    }
+
+    pub fn rhs(&self) -> &Expression {
+        &self.rhs
+    }
+}
@@ -0,0 +1,26 @@
+use super::expression::Expression;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ByteStringConcatenation {
+    lhs: Arc<Expression>,
+    rhs: Arc<Expression>,
+}
+
+impl ByteStringConcatenation {
+    pub fn new(lhs: impl Into<Expression>, rhs: impl Into<Expression>) -> Self {
+        Self {
+            lhs: lhs.into().into(),
+            rhs: rhs.into().into(),
+        }
+    }
+
+    pub fn lhs(&self) -> &Expression {
+        &self.lhs
+    }
+
+    pub fn rhs(&self) -> &Expression {
+        &self.rhs
+    }
+}
@@ -0,0 +1,20 @@
+use super::expression::Expression;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ByteStringLength {
+    string: Arc<Expression>,
+}
+
+impl ByteStringLength {
+    pub fn new(string: impl Into<Expression>) -> Self {
+        Self {
+            string: string.into().into(),
+        }
+    }
+
+    pub fn string(&self) -> &Expression {
+        &self.string
+    }
+}
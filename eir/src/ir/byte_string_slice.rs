@@ -0,0 +1,38 @@
+use super::expression::Expression;
+use std::sync::Arc;
+
+// End indices are clamped to lengths of strings and slices are empty if
+// their start indices are not less than their end indices.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ByteStringSlice {
+    string: Arc<Expression>,
+    start: Arc<Expression>,
+    end: Arc<Expression>,
+}
+
+impl ByteStringSlice {
+    pub fn new(
+        string: impl Into<Expression>,
+        start: impl Into<Expression>,
+        end: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            string: string.into().into(),
+            start: start.into().into(),
+            end: end.into().into(),
+        }
+    }
+
+    pub fn string(&self) -> &Expression {
+        &self.string
+    }
+
+    pub fn start(&self) -> &Expression {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Expression {
+        &self.end
+    }
+}
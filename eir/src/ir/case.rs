@@ -4,6 +4,7 @@ use super::{
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Case {
     argument: Arc<Expression>,
     alternatives: Vec<Alternative>,
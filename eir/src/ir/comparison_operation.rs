@@ -1,8 +1,11 @@
 use super::{comparison_operator::ComparisonOperator, expression::Expression};
+use crate::types::Type;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ComparisonOperation {
+    type_: Type,
     operator: ComparisonOperator,
     lhs: Arc<Expression>,
     rhs: Arc<Expression>,
@@ -10,17 +13,23 @@ pub struct ComparisonOperation {
 
 impl ComparisonOperation {
     pub fn new(
+        type_: impl Into<Type>,
         operator: ComparisonOperator,
         lhs: impl Into<Expression>,
         rhs: impl Into<Expression>,
     ) -> Self {
         Self {
+            type_: type_.into(),
             operator,
             lhs: Arc::new(lhs.into()),
             rhs: Arc::new(rhs.into()),
         }
     }
 
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
     pub fn operator(&self) -> ComparisonOperator {
         self.operator
     }
@@ -1,4 +1,5 @@
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ComparisonOperator {
     Equal,
     NotEqual,
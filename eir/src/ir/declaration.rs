@@ -1,6 +1,7 @@
 use crate::types;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Declaration {
     name: String,
     type_: types::Function,
@@ -2,6 +2,7 @@ use super::expression::Expression;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DefaultAlternative {
     name: String,
     expression: Arc<Expression>,
@@ -2,8 +2,10 @@ use super::{argument::Argument, expression::Expression};
 use crate::types::{self, Type};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Definition {
     name: String,
+    type_parameters: Vec<String>,
     // Environment is inferred on module creation and this field is used just
     // as its cache.  So it must be safe to clone definitions inside a
     // module and use it on creation of another module.
@@ -11,6 +13,8 @@ pub struct Definition {
     arguments: Vec<Argument>,
     body: Expression,
     result_type: Type,
+    // Constants without arguments are typed as functions of empty tuples as
+    // they are converted into thunks before compilation.
     type_: types::Function,
     is_thunk: bool,
 }
@@ -22,7 +26,25 @@ impl Definition {
         body: impl Into<Expression>,
         result_type: impl Into<Type> + Clone,
     ) -> Self {
-        Self::with_options(name, vec![], arguments, body, result_type, false)
+        Self::with_options(name, vec![], vec![], arguments, body, result_type, false)
+    }
+
+    pub fn with_type_parameters(
+        name: impl Into<String>,
+        type_parameters: Vec<String>,
+        arguments: Vec<Argument>,
+        body: impl Into<Expression>,
+        result_type: impl Into<Type> + Clone,
+    ) -> Self {
+        Self::with_options(
+            name,
+            type_parameters,
+            vec![],
+            arguments,
+            body,
+            result_type,
+            false,
+        )
     }
 
     pub fn thunk(
@@ -31,7 +53,7 @@ impl Definition {
         body: impl Into<Expression>,
         result_type: impl Into<Type> + Clone,
     ) -> Self {
-        Self::with_options(name, vec![], arguments, body, result_type, true)
+        Self::with_options(name, vec![], vec![], arguments, body, result_type, true)
     }
 
     #[cfg(test)]
@@ -42,11 +64,20 @@ impl Definition {
         body: impl Into<Expression>,
         result_type: impl Into<Type> + Clone,
     ) -> Self {
-        Self::with_options(name, environment, arguments, body, result_type, false)
+        Self::with_options(
+            name,
+            vec![],
+            environment,
+            arguments,
+            body,
+            result_type,
+            false,
+        )
     }
 
     pub(crate) fn with_options(
         name: impl Into<String>,
+        type_parameters: Vec<String>,
         environment: Vec<Argument>,
         arguments: Vec<Argument>,
         body: impl Into<Expression>,
@@ -56,12 +87,17 @@ impl Definition {
         Self {
             type_: arguments.iter().rev().skip(1).fold(
                 types::Function::new(
-                    arguments.iter().last().unwrap().type_().clone(),
+                    arguments
+                        .iter()
+                        .last()
+                        .map(|argument| argument.type_().clone())
+                        .unwrap_or_else(|| types::Tuple::new(vec![]).into()),
                     result_type.clone(),
                 ),
                 |result, argument| types::Function::new(argument.type_().clone(), result),
             ),
             name: name.into(),
+            type_parameters,
             environment,
             arguments,
             body: body.into(),
@@ -74,6 +110,10 @@ impl Definition {
         &self.name
     }
 
+    pub fn type_parameters(&self) -> &[String] {
+        &self.type_parameters
+    }
+
     pub fn environment(&self) -> &[Argument] {
         &self.environment
     }
@@ -97,4 +137,8 @@ impl Definition {
     pub fn is_thunk(&self) -> bool {
         self.is_thunk
     }
+
+    pub fn is_constant(&self) -> bool {
+        self.arguments.is_empty()
+    }
 }
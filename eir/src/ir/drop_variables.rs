@@ -3,6 +3,7 @@ use crate::types::Type;
 use std::{collections::HashMap, sync::Arc};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct DropVariables {
     variables: HashMap<String, Type>,
     expression: Arc<Expression>,
@@ -1,27 +1,49 @@
 use super::{
-    arithmetic_operation::ArithmeticOperation, byte_string::ByteString, case::Case,
+    arithmetic_operation::ArithmeticOperation, boolean_operation::BooleanOperation,
+    byte_string::ByteString, byte_string_concatenation::ByteStringConcatenation,
+    byte_string_length::ByteStringLength, byte_string_slice::ByteStringSlice, case::Case,
     clone_variables::CloneVariables, comparison_operation::ComparisonOperation,
-    drop_variables::DropVariables, function_application::FunctionApplication, if_::If, let_::Let,
-    let_recursive::LetRecursive, record::Record, record_element::RecordElement, variable::Variable,
-    variant::Variant,
+    drop_variables::DropVariables, force::Force, function_application::FunctionApplication,
+    if_::If, integer::Integer, lazy::Lazy, let_::Let, let_record::LetRecord,
+    let_recursive::LetRecursive, literal::Literal, literal_case::LiteralCase, negation::Negation,
+    not::Not, numeric_conversion::NumericConversion, record::Record, record_element::RecordElement,
+    record_update::RecordUpdate, sum::Sum, sum_case::SumCase, try_operation::TryOperation,
+    variable::Variable, variant::Variant,
 };
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Expression {
     ArithmeticOperation(ArithmeticOperation),
     Boolean(bool),
+    BooleanOperation(BooleanOperation),
     ByteString(ByteString),
+    ByteStringConcatenation(ByteStringConcatenation),
+    ByteStringLength(ByteStringLength),
+    ByteStringSlice(ByteStringSlice),
     Case(Case),
     CloneVariables(CloneVariables),
     ComparisonOperation(ComparisonOperation),
     DropVariables(DropVariables),
+    Force(Force),
     FunctionApplication(FunctionApplication),
     If(If),
+    Integer(Integer),
+    Lazy(Lazy),
     Let(Let),
+    LetRecord(LetRecord),
     LetRecursive(LetRecursive),
+    LiteralCase(LiteralCase),
+    Negation(Negation),
+    Not(Not),
     Number(f64),
+    NumericConversion(NumericConversion),
     Record(Record),
     RecordElement(RecordElement),
+    RecordUpdate(RecordUpdate),
+    Sum(Sum),
+    SumCase(SumCase),
+    TryOperation(TryOperation),
     Variable(Variable),
     Variant(Variant),
 }
@@ -38,6 +60,12 @@ impl From<bool> for Expression {
     }
 }
 
+impl From<BooleanOperation> for Expression {
+    fn from(operation: BooleanOperation) -> Self {
+        Self::BooleanOperation(operation)
+    }
+}
+
 impl From<ComparisonOperation> for Expression {
     fn from(operation: ComparisonOperation) -> Self {
         Self::ComparisonOperation(operation)
@@ -50,6 +78,12 @@ impl From<CloneVariables> for Expression {
     }
 }
 
+impl From<Force> for Expression {
+    fn from(force: Force) -> Self {
+        Self::Force(force)
+    }
+}
+
 impl From<DropVariables> for Expression {
     fn from(drop: DropVariables) -> Self {
         Self::DropVariables(drop)
@@ -68,30 +102,101 @@ impl From<If> for Expression {
     }
 }
 
+impl From<Integer> for Expression {
+    fn from(integer: Integer) -> Self {
+        Self::Integer(integer)
+    }
+}
+
 impl From<LetRecursive> for Expression {
     fn from(let_recursive: LetRecursive) -> Self {
         Self::LetRecursive(let_recursive)
     }
 }
 
+impl From<Lazy> for Expression {
+    fn from(lazy: Lazy) -> Self {
+        Self::Lazy(lazy)
+    }
+}
+
 impl From<Let> for Expression {
     fn from(let_: Let) -> Self {
         Self::Let(let_)
     }
 }
 
+impl From<LetRecord> for Expression {
+    fn from(let_: LetRecord) -> Self {
+        Self::LetRecord(let_)
+    }
+}
+
+impl From<Literal> for Expression {
+    fn from(literal: Literal) -> Self {
+        match literal {
+            Literal::Boolean(boolean) => Self::Boolean(boolean),
+            Literal::ByteString(string) => Self::ByteString(string),
+            Literal::Integer(integer) => Self::Integer(integer),
+            Literal::Number(number) => Self::Number(number),
+        }
+    }
+}
+
+impl From<LiteralCase> for Expression {
+    fn from(case: LiteralCase) -> Self {
+        Self::LiteralCase(case)
+    }
+}
+
+impl From<Negation> for Expression {
+    fn from(negation: Negation) -> Self {
+        Self::Negation(negation)
+    }
+}
+
+impl From<Not> for Expression {
+    fn from(not: Not) -> Self {
+        Self::Not(not)
+    }
+}
+
 impl From<f64> for Expression {
     fn from(number: f64) -> Self {
         Self::Number(number)
     }
 }
 
+impl From<NumericConversion> for Expression {
+    fn from(conversion: NumericConversion) -> Self {
+        Self::NumericConversion(conversion)
+    }
+}
+
 impl From<ByteString> for Expression {
     fn from(string: ByteString) -> Self {
         Self::ByteString(string)
     }
 }
 
+impl From<ByteStringConcatenation> for Expression {
+    fn from(concatenation: ByteStringConcatenation) -> Self {
+        Self::ByteStringConcatenation(concatenation)
+    }
+}
+
+impl From<ByteStringLength> for Expression {
+    fn from(length: ByteStringLength) -> Self {
+        Self::ByteStringLength(length)
+    }
+}
+
+impl From<ByteStringSlice> for Expression {
+    fn from(slice: ByteStringSlice) -> Self {
+        Self::ByteStringSlice(slice)
+    }
+}
+
 impl From<Record> for Expression {
     fn from(record: Record) -> Self {
         Self::Record(record)
@@ -104,6 +209,30 @@ impl From<RecordElement> for Expression {
     }
 }
 
+impl From<RecordUpdate> for Expression {
+    fn from(update: RecordUpdate) -> Self {
+        Self::RecordUpdate(update)
+    }
+}
+
+impl From<Sum> for Expression {
+    fn from(sum: Sum) -> Self {
+        Self::Sum(sum)
+    }
+}
+
+impl From<SumCase> for Expression {
+    fn from(case: SumCase) -> Self {
+        Self::SumCase(case)
+    }
+}
+
+impl From<TryOperation> for Expression {
+    fn from(operation: TryOperation) -> Self {
+        Self::TryOperation(operation)
+    }
+}
+
 impl From<Variable> for Expression {
     fn from(variable: Variable) -> Self {
         Self::Variable(variable)
@@ -0,0 +1,342 @@
+use super::*;
+
+// Folds rebuild modules bottom-up. Their default methods rebuild nodes with
+// their children folded, so implementations override only methods for nodes
+// they transform and call fold functions of children to continue traversal.
+//
+// There is no mutable visitor. IR nodes are immutable and only expose their
+// children by reference, so passes transforming them build new nodes with
+// folds instead.
+pub trait Fold {
+    fn fold_module(&mut self, module: &Module) -> Module {
+        fold_module_children(self, module)
+    }
+
+    fn fold_definition(&mut self, definition: &Definition) -> Definition {
+        fold_definition_children(self, definition)
+    }
+
+    fn fold_expression(&mut self, expression: &Expression) -> Expression {
+        fold_expression_children(self, expression)
+    }
+}
+
+pub fn fold_module_children<F: Fold + ?Sized>(folder: &mut F, module: &Module) -> Module {
+    Module::with_sum_definitions(
+        module.type_definitions().to_vec(),
+        module.sum_definitions().to_vec(),
+        module.foreign_declarations().to_vec(),
+        module.foreign_definitions().to_vec(),
+        module.declarations().to_vec(),
+        module
+            .definitions()
+            .iter()
+            .map(|definition| folder.fold_definition(definition))
+            .collect(),
+    )
+}
+
+pub fn fold_definition_children<F: Fold + ?Sized>(
+    folder: &mut F,
+    definition: &Definition,
+) -> Definition {
+    Definition::with_options(
+        definition.name(),
+        definition.type_parameters().to_vec(),
+        definition.environment().to_vec(),
+        definition.arguments().to_vec(),
+        folder.fold_expression(definition.body()),
+        definition.result_type().clone(),
+        definition.is_thunk(),
+    )
+}
+
+pub fn fold_expression_children<F: Fold + ?Sized>(
+    folder: &mut F,
+    expression: &Expression,
+) -> Expression {
+    match expression {
+        Expression::ArithmeticOperation(operation) => ArithmeticOperation::new(
+            operation.type_().clone(),
+            operation.operator(),
+            folder.fold_expression(operation.lhs()),
+            folder.fold_expression(operation.rhs()),
+        )
+        .into(),
+        Expression::BooleanOperation(operation) => BooleanOperation::new(
+            operation.operator(),
+            folder.fold_expression(operation.lhs()),
+            folder.fold_expression(operation.rhs()),
+        )
+        .into(),
+        Expression::ByteStringConcatenation(concatenation) => ByteStringConcatenation::new(
+            folder.fold_expression(concatenation.lhs()),
+            folder.fold_expression(concatenation.rhs()),
+        )
+        .into(),
+        Expression::ByteStringLength(length) => {
+            ByteStringLength::new(folder.fold_expression(length.string())).into()
+        }
+        Expression::ByteStringSlice(slice) => ByteStringSlice::new(
+            folder.fold_expression(slice.string()),
+            folder.fold_expression(slice.start()),
+            folder.fold_expression(slice.end()),
+        )
+        .into(),
+        Expression::Case(case) => Case::new(
+            folder.fold_expression(case.argument()),
+            fold_alternatives(folder, case.alternatives()),
+            fold_default_alternative(folder, case.default_alternative()),
+        )
+        .into(),
+        Expression::CloneVariables(clone) => CloneVariables::new(
+            clone.variables().clone(),
+            folder.fold_expression(clone.expression()),
+        )
+        .into(),
+        Expression::ComparisonOperation(operation) => ComparisonOperation::new(
+            operation.type_().clone(),
+            operation.operator(),
+            folder.fold_expression(operation.lhs()),
+            folder.fold_expression(operation.rhs()),
+        )
+        .into(),
+        Expression::DropVariables(drop) => DropVariables::new(
+            drop.variables().clone(),
+            folder.fold_expression(drop.expression()),
+        )
+        .into(),
+        Expression::Force(force) => Force::new(folder.fold_expression(force.expression())).into(),
+        Expression::FunctionApplication(application) => FunctionApplication::with_options(
+            application.type_().clone(),
+            folder.fold_expression(application.function()),
+            application.type_arguments().to_vec(),
+            folder.fold_expression(application.argument()),
+            application.is_tail(),
+        )
+        .into(),
+        Expression::If(if_) => If::new(
+            folder.fold_expression(if_.condition()),
+            folder.fold_expression(if_.then()),
+            folder.fold_expression(if_.else_()),
+        )
+        .into(),
+        Expression::Lazy(lazy) => {
+            Lazy::with_definition(folder.fold_definition(lazy.definition())).into()
+        }
+        Expression::Let(let_) => Let::new(
+            let_.name(),
+            let_.type_().clone(),
+            folder.fold_expression(let_.bound_expression()),
+            folder.fold_expression(let_.expression()),
+        )
+        .into(),
+        Expression::LetRecord(let_) => LetRecord::new(
+            let_.type_().clone(),
+            let_.elements().to_vec(),
+            folder.fold_expression(let_.bound_expression()),
+            folder.fold_expression(let_.expression()),
+        )
+        .into(),
+        Expression::LetRecursive(let_) => LetRecursive::with_definitions(
+            let_.definitions()
+                .iter()
+                .map(|definition| folder.fold_definition(definition))
+                .collect(),
+            folder.fold_expression(let_.expression()),
+        )
+        .into(),
+        Expression::LiteralCase(case) => LiteralCase::new(
+            case.type_().clone(),
+            folder.fold_expression(case.argument()),
+            case.alternatives()
+                .iter()
+                .map(|alternative| {
+                    LiteralAlternative::new(
+                        alternative.literal().clone(),
+                        folder.fold_expression(alternative.expression()),
+                    )
+                })
+                .collect(),
+            fold_default_alternative(folder, case.default_alternative()),
+        )
+        .into(),
+        Expression::Negation(negation) => Negation::new(
+            negation.type_().clone(),
+            folder.fold_expression(negation.expression()),
+        )
+        .into(),
+        Expression::Not(not) => Not::new(folder.fold_expression(not.expression())).into(),
+        Expression::NumericConversion(conversion) => NumericConversion::new(
+            conversion.from().clone(),
+            conversion.to().clone(),
+            conversion.rounding(),
+            folder.fold_expression(conversion.argument()),
+        )
+        .into(),
+        Expression::Record(record) => Record::new(
+            record.type_().clone(),
+            record
+                .elements()
+                .iter()
+                .map(|element| folder.fold_expression(element))
+                .collect(),
+        )
+        .into(),
+        Expression::RecordElement(element) => RecordElement::with_field(
+            element.type_().clone(),
+            element.field().clone(),
+            folder.fold_expression(element.record()),
+        )
+        .into(),
+        Expression::RecordUpdate(update) => RecordUpdate::new(
+            update.type_().clone(),
+            folder.fold_expression(update.record()),
+            update
+                .fields()
+                .iter()
+                .map(|field| {
+                    RecordUpdateField::with_field(
+                        field.field().clone(),
+                        folder.fold_expression(field.expression()),
+                    )
+                })
+                .collect(),
+        )
+        .into(),
+        Expression::Sum(sum) => Sum::new(
+            sum.type_().clone(),
+            sum.constructor().clone(),
+            folder.fold_expression(sum.payload()),
+        )
+        .into(),
+        Expression::SumCase(case) => SumCase::new(
+            case.type_().clone(),
+            folder.fold_expression(case.argument()),
+            fold_alternatives(folder, case.alternatives()),
+            fold_default_alternative(folder, case.default_alternative()),
+        )
+        .into(),
+        Expression::TryOperation(operation) => TryOperation::new(
+            folder.fold_expression(operation.operand()),
+            operation.name(),
+            operation.type_().clone(),
+            folder.fold_expression(operation.then()),
+        )
+        .into(),
+        Expression::Variant(variant) => Variant::new(
+            variant.type_().clone(),
+            folder.fold_expression(variant.payload()),
+        )
+        .into(),
+        Expression::Boolean(_)
+        | Expression::ByteString(_)
+        | Expression::Integer(_)
+        | Expression::Number(_)
+        | Expression::Variable(_) => expression.clone(),
+    }
+}
+
+pub(crate) fn fold_alternatives<F: Fold + ?Sized>(
+    folder: &mut F,
+    alternatives: &[Alternative],
+) -> Vec<Alternative> {
+    alternatives
+        .iter()
+        .map(|alternative| {
+            Alternative::new(
+                alternative.type_().clone(),
+                alternative.name(),
+                folder.fold_expression(alternative.expression()),
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn fold_default_alternative<F: Fold + ?Sized>(
+    folder: &mut F,
+    alternative: Option<&DefaultAlternative>,
+) -> Option<DefaultAlternative> {
+    alternative.map(|alternative| {
+        DefaultAlternative::new(
+            alternative.name(),
+            folder.fold_expression(alternative.expression()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type;
+    use pretty_assertions::assert_eq;
+
+    struct VariableRenamer;
+
+    impl Fold for VariableRenamer {
+        fn fold_expression(&mut self, expression: &Expression) -> Expression {
+            match expression {
+                Expression::Variable(variable) => {
+                    Variable::new(format!("{}'", variable.name())).into()
+                }
+                _ => fold_expression_children(self, expression),
+            }
+        }
+    }
+
+    #[test]
+    fn fold_expressions() {
+        assert_eq!(
+            VariableRenamer.fold_expression(
+                &If::new(Variable::new("x"), Variable::new("y"), Variable::new("z"),).into()
+            ),
+            If::new(
+                Variable::new("x'"),
+                Variable::new("y'"),
+                Variable::new("z'"),
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn fold_definitions_in_let_recursive() {
+        assert_eq!(
+            VariableRenamer.fold_expression(
+                &LetRecursive::new(
+                    Definition::thunk("f", vec![], Variable::new("x"), Type::Number),
+                    Variable::new("f"),
+                )
+                .into()
+            ),
+            LetRecursive::new(
+                Definition::thunk("f", vec![], Variable::new("x'"), Type::Number),
+                Variable::new("f'"),
+            )
+            .into()
+        );
+    }
+
+    #[test]
+    fn keep_tail_call_marks() {
+        let expression = FunctionApplication::with_options(
+            crate::types::Function::new(Type::Number, Type::Number),
+            Variable::new("f"),
+            vec![],
+            42.0,
+            true,
+        )
+        .into();
+
+        assert_eq!(VariableRenamer.fold_expression(&expression), {
+            FunctionApplication::with_options(
+                crate::types::Function::new(Type::Number, Type::Number),
+                Variable::new("f'"),
+                vec![],
+                42.0,
+                true,
+            )
+            .into()
+        });
+    }
+}
@@ -0,0 +1,20 @@
+use super::expression::Expression;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Force {
+    expression: Arc<Expression>,
+}
+
+impl Force {
+    pub fn new(expression: impl Into<Expression>) -> Self {
+        Self {
+            expression: expression.into().into(),
+        }
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
@@ -2,6 +2,7 @@ use super::calling_convention::CallingConvention;
 use crate::types;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct ForeignDeclaration {
     name: String,
     foreign_name: String,
@@ -3,10 +3,13 @@ use crate::types::{self, Type};
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct FunctionApplication {
     type_: types::Function,
     function: Arc<Expression>,
+    type_arguments: Vec<Type>,
     argument: Arc<Expression>,
+    is_tail: bool,
 }
 
 impl FunctionApplication {
@@ -14,11 +17,35 @@ impl FunctionApplication {
         type_: types::Function,
         function: impl Into<Expression>,
         argument: impl Into<Expression>,
+    ) -> Self {
+        Self::with_type_arguments(type_, function, vec![], argument)
+    }
+
+    // Type arguments instantiate a polymorphic global definition referred to
+    // by a function variable.
+    pub fn with_type_arguments(
+        type_: types::Function,
+        function: impl Into<Expression>,
+        type_arguments: Vec<Type>,
+        argument: impl Into<Expression>,
+    ) -> Self {
+        Self::with_options(type_, function, type_arguments, argument, false)
+    }
+
+    // Tail calls are marked by the `mark_tail_calls` analysis.
+    pub fn with_options(
+        type_: types::Function,
+        function: impl Into<Expression>,
+        type_arguments: Vec<Type>,
+        argument: impl Into<Expression>,
+        is_tail: bool,
     ) -> Self {
         Self {
             type_,
             function: function.into().into(),
+            type_arguments,
             argument: argument.into().into(),
+            is_tail,
         }
     }
 
@@ -30,10 +57,18 @@ impl FunctionApplication {
         &self.function
     }
 
+    pub fn type_arguments(&self) -> &[Type] {
+        &self.type_arguments
+    }
+
     pub fn argument(&self) -> &Expression {
         &self.argument
     }
 
+    pub fn is_tail(&self) -> bool {
+        self.is_tail
+    }
+
     pub fn first_function(&self) -> &Expression {
         let mut function: &Expression = &self.function;
 
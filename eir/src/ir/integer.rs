@@ -0,0 +1,49 @@
+use crate::types;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Integer {
+    type_: types::Integer,
+    value: u64,
+}
+
+impl Integer {
+    // Values are truncated into bit widths of their types. Signed integers
+    // are represented in two's complement.
+    pub fn new(type_: types::Integer, value: u64) -> Self {
+        Self {
+            type_,
+            value: if type_.bits() < 64 {
+                value & ((1 << type_.bits()) - 1)
+            } else {
+                value
+            },
+        }
+    }
+
+    pub fn type_(&self) -> types::Integer {
+        self.type_
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_value() {
+        assert_eq!(Integer::new(types::Integer::Unsigned8, 256).value(), 0);
+        assert_eq!(
+            Integer::new(types::Integer::Signed32, -1i64 as u64).value(),
+            u32::MAX as u64
+        );
+        assert_eq!(
+            Integer::new(types::Integer::Unsigned64, u64::MAX).value(),
+            u64::MAX
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use super::{argument::Argument, definition::Definition, expression::Expression};
+use crate::{
+    analysis::find_free_variables,
+    types::{self, Type},
+};
+use std::sync::Arc;
+
+// Lazy values are thunks of definitions which take empty tuples.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Lazy {
+    definition: Arc<Definition>,
+}
+
+impl Lazy {
+    pub fn new(type_: impl Into<Type> + Clone, expression: impl Into<Expression>) -> Self {
+        let expression = expression.into();
+        let free_variables = find_free_variables(&expression);
+        let generate_name = |name: &str| {
+            let mut name = name.to_owned();
+
+            while free_variables.contains(&name) {
+                name.push('\'');
+            }
+
+            name
+        };
+        let name = generate_name("lazy");
+        let argument_name = generate_name(&format!("{}'", name));
+
+        Self::with_definition(Definition::with_options(
+            name,
+            vec![],
+            vec![],
+            vec![Argument::new(argument_name, types::Tuple::new(vec![]))],
+            expression,
+            type_,
+            true,
+        ))
+    }
+
+    pub fn with_definition(definition: Definition) -> Self {
+        Self {
+            definition: definition.into(),
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        self.definition.result_type()
+    }
+
+    pub fn expression(&self) -> &Expression {
+        self.definition.body()
+    }
+
+    pub fn definition(&self) -> &Definition {
+        &self.definition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            arithmetic_operation::ArithmeticOperation, arithmetic_operator::ArithmeticOperator,
+            variable::Variable,
+        },
+        *,
+    };
+
+    #[test]
+    fn avoid_free_variables_in_names() {
+        let lazy = Lazy::new(
+            Type::Number,
+            ArithmeticOperation::new(
+                Type::Number,
+                ArithmeticOperator::Add,
+                Variable::new("lazy"),
+                Variable::new("lazy''"),
+            ),
+        );
+
+        assert_eq!(lazy.definition().name(), "lazy'");
+        assert_eq!(lazy.definition().arguments()[0].name(), "lazy'''");
+    }
+}
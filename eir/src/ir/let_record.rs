@@ -0,0 +1,45 @@
+use super::{argument::Argument, expression::Expression};
+use crate::types::Type;
+use std::sync::Arc;
+
+// Let expressions which bind all elements of records at once.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct LetRecord {
+    type_: Type,
+    elements: Vec<Argument>,
+    bound_expression: Arc<Expression>,
+    expression: Arc<Expression>,
+}
+
+impl LetRecord {
+    pub fn new(
+        type_: impl Into<Type>,
+        elements: Vec<Argument>,
+        bound_expression: impl Into<Expression>,
+        expression: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            type_: type_.into(),
+            elements,
+            bound_expression: bound_expression.into().into(),
+            expression: expression.into().into(),
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn elements(&self) -> &[Argument] {
+        &self.elements
+    }
+
+    pub fn bound_expression(&self) -> &Expression {
+        &self.bound_expression
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
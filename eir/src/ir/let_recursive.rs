@@ -2,21 +2,29 @@ use super::{definition::Definition, expression::Expression};
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct LetRecursive {
-    definition: Arc<Definition>,
+    definitions: Vec<Definition>,
     expression: Arc<Expression>,
 }
 
 impl LetRecursive {
     pub fn new(definition: Definition, expression: impl Into<Expression>) -> Self {
+        Self::with_definitions(vec![definition], expression)
+    }
+
+    pub fn with_definitions(
+        definitions: Vec<Definition>,
+        expression: impl Into<Expression>,
+    ) -> Self {
         Self {
-            definition: definition.into(),
+            definitions,
             expression: Arc::new(expression.into()),
         }
     }
 
-    pub fn definition(&self) -> &Definition {
-        &self.definition
+    pub fn definitions(&self) -> &[Definition] {
+        &self.definitions
     }
 
     pub fn expression(&self) -> &Expression {
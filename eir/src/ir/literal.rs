@@ -0,0 +1,46 @@
+use super::{byte_string::ByteString, integer::Integer};
+use crate::types::Type;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Literal {
+    Boolean(bool),
+    ByteString(ByteString),
+    Integer(Integer),
+    Number(f64),
+}
+
+impl Literal {
+    pub fn type_(&self) -> Type {
+        match self {
+            Self::Boolean(_) => Type::Boolean,
+            Self::ByteString(_) => Type::ByteString,
+            Self::Integer(integer) => Type::Integer(integer.type_()),
+            Self::Number(_) => Type::Number,
+        }
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(boolean: bool) -> Self {
+        Self::Boolean(boolean)
+    }
+}
+
+impl From<ByteString> for Literal {
+    fn from(string: ByteString) -> Self {
+        Self::ByteString(string)
+    }
+}
+
+impl From<Integer> for Literal {
+    fn from(integer: Integer) -> Self {
+        Self::Integer(integer)
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(number: f64) -> Self {
+        Self::Number(number)
+    }
+}
@@ -0,0 +1,26 @@
+use super::{expression::Expression, literal::Literal};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct LiteralAlternative {
+    literal: Literal,
+    expression: Arc<Expression>,
+}
+
+impl LiteralAlternative {
+    pub fn new(literal: impl Into<Literal>, expression: impl Into<Expression>) -> Self {
+        Self {
+            literal: literal.into(),
+            expression: expression.into().into(),
+        }
+    }
+
+    pub fn literal(&self) -> &Literal {
+        &self.literal
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
@@ -0,0 +1,49 @@
+use super::{
+    default_alternative::DefaultAlternative, expression::Expression,
+    literal_alternative::LiteralAlternative,
+};
+use crate::types::Type;
+use std::sync::Arc;
+
+// Alternatives are matched by equality of their literals to arguments in
+// order. Default alternatives bind arguments themselves.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct LiteralCase {
+    type_: Type,
+    argument: Arc<Expression>,
+    alternatives: Vec<LiteralAlternative>,
+    default_alternative: Option<DefaultAlternative>,
+}
+
+impl LiteralCase {
+    pub fn new(
+        type_: impl Into<Type>,
+        argument: impl Into<Expression>,
+        alternatives: Vec<LiteralAlternative>,
+        default_alternative: Option<DefaultAlternative>,
+    ) -> Self {
+        Self {
+            type_: type_.into(),
+            argument: Arc::new(argument.into()),
+            alternatives,
+            default_alternative,
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn argument(&self) -> &Expression {
+        &self.argument
+    }
+
+    pub fn alternatives(&self) -> &[LiteralAlternative] {
+        &self.alternatives
+    }
+
+    pub fn default_alternative(&self) -> Option<&DefaultAlternative> {
+        self.default_alternative.as_ref()
+    }
+}
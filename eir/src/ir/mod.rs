@@ -2,7 +2,12 @@ mod alternative;
 mod argument;
 mod arithmetic_operation;
 mod arithmetic_operator;
+mod boolean_operation;
+mod boolean_operator;
 mod byte_string;
+mod byte_string_concatenation;
+mod byte_string_length;
+mod byte_string_slice;
 mod calling_convention;
 mod case;
 mod clone_variables;
@@ -13,24 +18,49 @@ mod default_alternative;
 mod definition;
 mod drop_variables;
 mod expression;
+mod fold;
+mod force;
 mod foreign_declaration;
 mod foreign_definition;
 mod function_application;
 mod if_;
+mod integer;
+mod lazy;
 mod let_;
+mod let_record;
 mod let_recursive;
+mod literal;
+mod literal_alternative;
+mod literal_case;
 mod module;
+mod negation;
+mod not;
+mod numeric_conversion;
 mod record;
 mod record_element;
+mod record_field;
+mod record_update;
+mod record_update_field;
+mod rounding;
+mod sum;
+mod sum_case;
+mod sum_definition;
+mod try_operation;
 mod type_definition;
 mod variable;
 mod variant;
+mod visitor;
 
 pub use alternative::*;
 pub use argument::*;
 pub use arithmetic_operation::*;
 pub use arithmetic_operator::*;
+pub use boolean_operation::*;
+pub use boolean_operator::*;
 pub use byte_string::*;
+pub use byte_string_concatenation::*;
+pub use byte_string_length::*;
+pub use byte_string_slice::*;
 pub use calling_convention::*;
 pub use case::*;
 pub use clone_variables::*;
@@ -41,15 +71,35 @@ pub use default_alternative::*;
 pub use definition::*;
 pub use drop_variables::*;
 pub use expression::*;
+pub use fold::*;
+pub use force::*;
 pub use foreign_declaration::*;
 pub use foreign_definition::*;
 pub use function_application::*;
 pub use if_::*;
+pub use integer::*;
+pub use lazy::*;
 pub use let_::*;
+pub use let_record::*;
 pub use let_recursive::*;
+pub use literal::*;
+pub use literal_alternative::*;
+pub use literal_case::*;
 pub use module::*;
+pub use negation::*;
+pub use not::*;
+pub use numeric_conversion::*;
 pub use record::*;
 pub use record_element::*;
+pub use record_field::*;
+pub use record_update::*;
+pub use record_update_field::*;
+pub use rounding::*;
+pub use sum::*;
+pub use sum_case::*;
+pub use sum_definition::*;
+pub use try_operation::*;
 pub use type_definition::*;
 pub use variable::*;
 pub use variant::*;
+pub use visitor::*;
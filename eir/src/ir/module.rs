@@ -1,11 +1,14 @@
 use super::{
     declaration::Declaration, definition::Definition, foreign_declaration::ForeignDeclaration,
-    foreign_definition::ForeignDefinition, type_definition::TypeDefinition,
+    foreign_definition::ForeignDefinition, sum_definition::SumDefinition,
+    type_definition::TypeDefinition,
 };
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Module {
     type_definitions: Vec<TypeDefinition>,
+    sum_definitions: Vec<SumDefinition>,
     foreign_declarations: Vec<ForeignDeclaration>,
     foreign_definitions: Vec<ForeignDefinition>,
     declarations: Vec<Declaration>,
@@ -19,9 +22,28 @@ impl Module {
         foreign_definitions: Vec<ForeignDefinition>,
         declarations: Vec<Declaration>,
         definitions: Vec<Definition>,
+    ) -> Self {
+        Self::with_sum_definitions(
+            type_definitions,
+            vec![],
+            foreign_declarations,
+            foreign_definitions,
+            declarations,
+            definitions,
+        )
+    }
+
+    pub fn with_sum_definitions(
+        type_definitions: Vec<TypeDefinition>,
+        sum_definitions: Vec<SumDefinition>,
+        foreign_declarations: Vec<ForeignDeclaration>,
+        foreign_definitions: Vec<ForeignDefinition>,
+        declarations: Vec<Declaration>,
+        definitions: Vec<Definition>,
     ) -> Self {
         Self {
             type_definitions,
+            sum_definitions,
             foreign_declarations,
             foreign_definitions,
             declarations,
@@ -33,6 +55,10 @@ impl Module {
         &self.type_definitions
     }
 
+    pub fn sum_definitions(&self) -> &[SumDefinition] {
+        &self.sum_definitions
+    }
+
     pub fn foreign_declarations(&self) -> &[ForeignDeclaration] {
         &self.foreign_declarations
     }
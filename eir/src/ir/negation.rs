@@ -0,0 +1,27 @@
+use super::expression::Expression;
+use crate::types::Type;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Negation {
+    type_: Type,
+    expression: Arc<Expression>,
+}
+
+impl Negation {
+    pub fn new(type_: impl Into<Type>, expression: impl Into<Expression>) -> Self {
+        Self {
+            type_: type_.into(),
+            expression: expression.into().into(),
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
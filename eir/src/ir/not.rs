@@ -0,0 +1,20 @@
+use super::expression::Expression;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Not {
+    expression: Arc<Expression>,
+}
+
+impl Not {
+    pub fn new(expression: impl Into<Expression>) -> Self {
+        Self {
+            expression: expression.into().into(),
+        }
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
@@ -0,0 +1,47 @@
+use super::{expression::Expression, rounding::Rounding};
+use crate::types::Type;
+use std::sync::Arc;
+
+// Numbers are rounded by rounding modes before conversion into integers. NaN
+// is converted into zero and out-of-range values saturate. Integers are
+// sign-extended, zero-extended or truncated into other integers.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct NumericConversion {
+    from: Type,
+    to: Type,
+    rounding: Rounding,
+    argument: Arc<Expression>,
+}
+
+impl NumericConversion {
+    pub fn new(
+        from: impl Into<Type>,
+        to: impl Into<Type>,
+        rounding: Rounding,
+        argument: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            rounding,
+            argument: argument.into().into(),
+        }
+    }
+
+    pub fn from(&self) -> &Type {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Type {
+        &self.to
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    pub fn argument(&self) -> &Expression {
+        &self.argument
+    }
+}
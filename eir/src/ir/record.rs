@@ -1,18 +1,22 @@
 use super::expression::Expression;
-use crate::types;
+use crate::types::Type;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Record {
-    type_: types::Record,
+    type_: Type,
     elements: Vec<Expression>,
 }
 
 impl Record {
-    pub fn new(type_: types::Record, elements: Vec<Expression>) -> Self {
-        Self { type_, elements }
+    pub fn new(type_: impl Into<Type>, elements: Vec<Expression>) -> Self {
+        Self {
+            type_: type_.into(),
+            elements,
+        }
     }
 
-    pub fn type_(&self) -> &types::Record {
+    pub fn type_(&self) -> &Type {
         &self.type_
     }
 
@@ -1,29 +1,48 @@
-use super::expression::Expression;
-use crate::types;
+use super::{expression::Expression, record_field::RecordField};
+use crate::types::Type;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RecordElement {
-    type_: types::Record,
-    index: usize,
+    type_: Type,
+    field: RecordField,
     record: Arc<Expression>,
 }
 
 impl RecordElement {
-    pub fn new(type_: types::Record, index: usize, record: impl Into<Expression>) -> Self {
+    pub fn new(type_: impl Into<Type>, index: usize, record: impl Into<Expression>) -> Self {
+        Self::with_field(type_, RecordField::Index(index), record)
+    }
+
+    // Field names are resolved into indices with record types during type
+    // checking.
+    pub fn with_field_name(
+        type_: impl Into<Type>,
+        name: impl Into<String>,
+        record: impl Into<Expression>,
+    ) -> Self {
+        Self::with_field(type_, RecordField::Name(name.into()), record)
+    }
+
+    pub fn with_field(
+        type_: impl Into<Type>,
+        field: RecordField,
+        record: impl Into<Expression>,
+    ) -> Self {
         Self {
-            type_,
-            index,
+            type_: type_.into(),
+            field,
             record: record.into().into(),
         }
     }
 
-    pub fn type_(&self) -> &types::Record {
+    pub fn type_(&self) -> &Type {
         &self.type_
     }
 
-    pub fn index(&self) -> usize {
-        self.index
+    pub fn field(&self) -> &RecordField {
+        &self.field
     }
 
     pub fn record(&self) -> &Expression {
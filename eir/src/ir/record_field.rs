@@ -0,0 +1,6 @@
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RecordField {
+    Index(usize),
+    Name(String),
+}
@@ -0,0 +1,39 @@
+use super::{expression::Expression, record_update_field::RecordUpdateField};
+use crate::types::Type;
+use std::sync::Arc;
+
+// Record updates copy records with some fields replaced. Records are updated
+// in place if they are not shared.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RecordUpdate {
+    type_: Type,
+    record: Arc<Expression>,
+    fields: Vec<RecordUpdateField>,
+}
+
+impl RecordUpdate {
+    pub fn new(
+        type_: impl Into<Type>,
+        record: impl Into<Expression>,
+        fields: Vec<RecordUpdateField>,
+    ) -> Self {
+        Self {
+            type_: type_.into(),
+            record: record.into().into(),
+            fields,
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn record(&self) -> &Expression {
+        &self.record
+    }
+
+    pub fn fields(&self) -> &[RecordUpdateField] {
+        &self.fields
+    }
+}
@@ -0,0 +1,33 @@
+use super::{expression::Expression, record_field::RecordField};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RecordUpdateField {
+    field: RecordField,
+    expression: Expression,
+}
+
+impl RecordUpdateField {
+    pub fn new(index: usize, expression: impl Into<Expression>) -> Self {
+        Self::with_field(RecordField::Index(index), expression)
+    }
+
+    pub fn with_field_name(name: impl Into<String>, expression: impl Into<Expression>) -> Self {
+        Self::with_field(RecordField::Name(name.into()), expression)
+    }
+
+    pub fn with_field(field: RecordField, expression: impl Into<Expression>) -> Self {
+        Self {
+            field,
+            expression: expression.into(),
+        }
+    }
+
+    pub fn field(&self) -> &RecordField {
+        &self.field
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Rounding {
+    Ceiling,
+    Floor,
+    Round,
+    Truncate,
+}
@@ -0,0 +1,37 @@
+use super::expression::Expression;
+use crate::types;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Sum {
+    type_: types::Sum,
+    constructor: types::Record,
+    payload: Arc<Expression>,
+}
+
+impl Sum {
+    pub fn new(
+        type_: types::Sum,
+        constructor: types::Record,
+        payload: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            type_,
+            constructor,
+            payload: payload.into().into(),
+        }
+    }
+
+    pub fn type_(&self) -> &types::Sum {
+        &self.type_
+    }
+
+    pub fn constructor(&self) -> &types::Record {
+        &self.constructor
+    }
+
+    pub fn payload(&self) -> &Expression {
+        &self.payload
+    }
+}
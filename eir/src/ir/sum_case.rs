@@ -0,0 +1,46 @@
+use super::{
+    alternative::Alternative, default_alternative::DefaultAlternative, expression::Expression,
+};
+use crate::types;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SumCase {
+    type_: types::Sum,
+    argument: Arc<Expression>,
+    alternatives: Vec<Alternative>,
+    default_alternative: Option<DefaultAlternative>,
+}
+
+impl SumCase {
+    pub fn new(
+        type_: types::Sum,
+        argument: impl Into<Expression>,
+        alternatives: Vec<Alternative>,
+        default_alternative: Option<DefaultAlternative>,
+    ) -> Self {
+        Self {
+            type_,
+            argument: Arc::new(argument.into()),
+            alternatives,
+            default_alternative,
+        }
+    }
+
+    pub fn type_(&self) -> &types::Sum {
+        &self.type_
+    }
+
+    pub fn argument(&self) -> &Expression {
+        &self.argument
+    }
+
+    pub fn alternatives(&self) -> &[Alternative] {
+        &self.alternatives
+    }
+
+    pub fn default_alternative(&self) -> Option<&DefaultAlternative> {
+        self.default_alternative.as_ref()
+    }
+}
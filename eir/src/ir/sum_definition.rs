@@ -0,0 +1,25 @@
+use crate::types;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SumDefinition {
+    name: String,
+    constructors: Vec<types::Record>,
+}
+
+impl SumDefinition {
+    pub fn new(name: impl Into<String>, constructors: Vec<types::Record>) -> Self {
+        Self {
+            name: name.into(),
+            constructors,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn constructors(&self) -> &[types::Record] {
+        &self.constructors
+    }
+}
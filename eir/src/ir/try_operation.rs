@@ -0,0 +1,47 @@
+use super::expression::Expression;
+use crate::types::Type;
+use std::sync::Arc;
+
+// Try operations return values of `then` expressions from functions if
+// operands are variants of given types binding their payloads to names.
+// Otherwise, they evaluate to operands.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TryOperation {
+    operand: Arc<Expression>,
+    name: String,
+    type_: Type,
+    then: Arc<Expression>,
+}
+
+impl TryOperation {
+    pub fn new(
+        operand: impl Into<Expression>,
+        name: impl Into<String>,
+        type_: impl Into<Type>,
+        then: impl Into<Expression>,
+    ) -> Self {
+        Self {
+            operand: operand.into().into(),
+            name: name.into(),
+            type_: type_.into(),
+            then: then.into().into(),
+        }
+    }
+
+    pub fn operand(&self) -> &Expression {
+        &self.operand
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+
+    pub fn then(&self) -> &Expression {
+        &self.then
+    }
+}
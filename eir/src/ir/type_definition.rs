@@ -1,15 +1,26 @@
 use crate::types;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TypeDefinition {
     name: String,
+    parameters: Vec<String>,
     type_: types::RecordBody,
 }
 
 impl TypeDefinition {
     pub fn new(name: impl Into<String>, type_: impl Into<types::RecordBody>) -> Self {
+        Self::with_parameters(name, vec![], type_)
+    }
+
+    pub fn with_parameters(
+        name: impl Into<String>,
+        parameters: Vec<String>,
+        type_: impl Into<types::RecordBody>,
+    ) -> Self {
         Self {
             name: name.into(),
+            parameters,
             type_: type_.into(),
         }
     }
@@ -18,6 +29,10 @@ impl TypeDefinition {
         &self.name
     }
 
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
     pub fn type_(&self) -> &types::RecordBody {
         &self.type_
     }
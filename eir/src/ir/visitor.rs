@@ -0,0 +1,242 @@
+use super::*;
+
+// Visitors traverse modules in pre-order. Their default methods visit all
+// children of nodes, so implementations override only methods for nodes they
+// are interested in and call walk functions to continue traversal.
+pub trait Visitor {
+    fn visit_module(&mut self, module: &Module) {
+        walk_module(self, module)
+    }
+
+    fn visit_definition(&mut self, definition: &Definition) {
+        walk_definition(self, definition)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+}
+
+pub fn walk_module<V: Visitor + ?Sized>(visitor: &mut V, module: &Module) {
+    for definition in module.definitions() {
+        visitor.visit_definition(definition);
+    }
+}
+
+pub fn walk_definition<V: Visitor + ?Sized>(visitor: &mut V, definition: &Definition) {
+    visitor.visit_expression(definition.body());
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::ArithmeticOperation(operation) => {
+            visitor.visit_expression(operation.lhs());
+            visitor.visit_expression(operation.rhs());
+        }
+        Expression::BooleanOperation(operation) => {
+            visitor.visit_expression(operation.lhs());
+            visitor.visit_expression(operation.rhs());
+        }
+        Expression::ByteStringConcatenation(concatenation) => {
+            visitor.visit_expression(concatenation.lhs());
+            visitor.visit_expression(concatenation.rhs());
+        }
+        Expression::ByteStringLength(length) => visitor.visit_expression(length.string()),
+        Expression::ByteStringSlice(slice) => {
+            visitor.visit_expression(slice.string());
+            visitor.visit_expression(slice.start());
+            visitor.visit_expression(slice.end());
+        }
+        Expression::Case(case) => {
+            visitor.visit_expression(case.argument());
+
+            for alternative in case.alternatives() {
+                visitor.visit_expression(alternative.expression());
+            }
+
+            walk_default_alternative(visitor, case.default_alternative());
+        }
+        Expression::CloneVariables(clone) => visitor.visit_expression(clone.expression()),
+        Expression::ComparisonOperation(operation) => {
+            visitor.visit_expression(operation.lhs());
+            visitor.visit_expression(operation.rhs());
+        }
+        Expression::DropVariables(drop) => visitor.visit_expression(drop.expression()),
+        Expression::Force(force) => visitor.visit_expression(force.expression()),
+        Expression::FunctionApplication(application) => {
+            visitor.visit_expression(application.function());
+            visitor.visit_expression(application.argument());
+        }
+        Expression::If(if_) => {
+            visitor.visit_expression(if_.condition());
+            visitor.visit_expression(if_.then());
+            visitor.visit_expression(if_.else_());
+        }
+        Expression::Lazy(lazy) => visitor.visit_definition(lazy.definition()),
+        Expression::Let(let_) => {
+            visitor.visit_expression(let_.bound_expression());
+            visitor.visit_expression(let_.expression());
+        }
+        Expression::LetRecord(let_) => {
+            visitor.visit_expression(let_.bound_expression());
+            visitor.visit_expression(let_.expression());
+        }
+        Expression::LetRecursive(let_) => {
+            for definition in let_.definitions() {
+                visitor.visit_definition(definition);
+            }
+
+            visitor.visit_expression(let_.expression());
+        }
+        Expression::LiteralCase(case) => {
+            visitor.visit_expression(case.argument());
+
+            for alternative in case.alternatives() {
+                visitor.visit_expression(alternative.expression());
+            }
+
+            walk_default_alternative(visitor, case.default_alternative());
+        }
+        Expression::Negation(negation) => visitor.visit_expression(negation.expression()),
+        Expression::Not(not) => visitor.visit_expression(not.expression()),
+        Expression::NumericConversion(conversion) => {
+            visitor.visit_expression(conversion.argument())
+        }
+        Expression::Record(record) => {
+            for element in record.elements() {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::RecordElement(element) => visitor.visit_expression(element.record()),
+        Expression::RecordUpdate(update) => {
+            visitor.visit_expression(update.record());
+
+            for field in update.fields() {
+                visitor.visit_expression(field.expression());
+            }
+        }
+        Expression::Sum(sum) => visitor.visit_expression(sum.payload()),
+        Expression::SumCase(case) => {
+            visitor.visit_expression(case.argument());
+
+            for alternative in case.alternatives() {
+                visitor.visit_expression(alternative.expression());
+            }
+
+            walk_default_alternative(visitor, case.default_alternative());
+        }
+        Expression::TryOperation(operation) => {
+            visitor.visit_expression(operation.operand());
+            visitor.visit_expression(operation.then());
+        }
+        Expression::Variant(variant) => visitor.visit_expression(variant.payload()),
+        Expression::Boolean(_)
+        | Expression::ByteString(_)
+        | Expression::Integer(_)
+        | Expression::Number(_)
+        | Expression::Variable(_) => {}
+    }
+}
+
+fn walk_default_alternative<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    alternative: Option<&DefaultAlternative>,
+) {
+    if let Some(alternative) = alternative {
+        visitor.visit_expression(alternative.expression());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Type;
+    use pretty_assertions::assert_eq;
+
+    struct VariableCollector {
+        names: Vec<String>,
+    }
+
+    impl Visitor for VariableCollector {
+        fn visit_expression(&mut self, expression: &Expression) {
+            if let Expression::Variable(variable) = expression {
+                self.names.push(variable.name().into());
+            }
+
+            walk_expression(self, expression)
+        }
+    }
+
+    fn collect_variables(module: &Module) -> Vec<String> {
+        let mut collector = VariableCollector { names: vec![] };
+
+        collector.visit_module(module);
+
+        collector.names
+    }
+
+    #[test]
+    fn visit_expressions_in_order() {
+        assert_eq!(
+            collect_variables(&Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    If::new(Variable::new("x"), Variable::new("y"), Variable::new("z"),),
+                    Type::Number,
+                )],
+            )),
+            vec!["x", "y", "z"]
+        );
+    }
+
+    #[test]
+    fn visit_definitions_in_let_recursive() {
+        assert_eq!(
+            collect_variables(&Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    LetRecursive::new(
+                        Definition::new(
+                            "g",
+                            vec![Argument::new("y", Type::Number)],
+                            Variable::new("y"),
+                            Type::Number,
+                        ),
+                        Variable::new("g"),
+                    ),
+                    Type::Number,
+                )],
+            )),
+            vec!["y", "g"]
+        );
+    }
+
+    #[test]
+    fn visit_definitions_in_lazy_values() {
+        assert_eq!(
+            collect_variables(&Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    Force::new(Lazy::new(Type::Number, Variable::new("x"))),
+                    Type::Number,
+                )],
+            )),
+            vec!["x"]
+        );
+    }
+}
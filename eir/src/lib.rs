@@ -1,3 +1,7 @@
 pub mod analysis;
+#[cfg(feature = "serde")]
+pub mod encoding;
+pub mod format;
 pub mod ir;
+pub mod parse;
 pub mod types;
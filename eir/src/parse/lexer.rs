@@ -0,0 +1,425 @@
+use super::error::{ParseError, Position};
+use std::fmt::Display;
+
+pub const KEYWORDS: &[&str] = &[
+    "and", "boolean", "case", "clone", "concat", "convert", "declare", "define", "drop", "else",
+    "false", "force", "foreign", "i32", "i64", "i8", "if", "in", "infinity", "lazy", "length",
+    "let", "letrec", "nan", "number", "of", "slice", "string", "sum", "then", "thunk", "true",
+    "try", "type", "u32", "u64", "u8", "variant",
+];
+
+const SYMBOLS: &[&str] = &[
+    "=>", "->", "==", "!=", "<=", ">=", "&&", "||", "!", "(", ")", "{", "}", "[", "]", ",", ":",
+    ".", "=", "+", "-", "*", "/", "%", "^", "<", ">", "'", "|",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    ByteString(Vec<u8>),
+    End,
+    Integer(String),
+    Keyword(&'static str),
+    Name(String),
+    Number(String),
+    Symbol(&'static str),
+}
+
+impl Display for Token {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::ByteString(_) => write!(formatter, "string literal"),
+            Self::End => write!(formatter, "end of input"),
+            Self::Integer(integer) => write!(formatter, "integer `{}`", integer),
+            Self::Keyword(keyword) => write!(formatter, "`{}`", keyword),
+            Self::Name(name) => write!(formatter, "name `{}`", name),
+            Self::Number(number) => write!(formatter, "number `{}`", number),
+            Self::Symbol(symbol) => write!(formatter, "`{}`", symbol),
+        }
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, ParseError> {
+    Lexer::new(source).tokenize()
+}
+
+struct Lexer {
+    characters: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn new(source: &str) -> Self {
+        Self {
+            characters: source.chars().collect(),
+            index: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(Token, Position)>, ParseError> {
+        let mut tokens = vec![];
+
+        loop {
+            self.skip_blanks();
+
+            let position = self.position();
+            let character = if let Some(character) = self.peek(0) {
+                character
+            } else {
+                tokens.push((Token::End, position));
+                return Ok(tokens);
+            };
+
+            tokens.push((
+                if character.is_ascii_digit() {
+                    self.number()
+                } else if character == '"' {
+                    self.byte_string()?
+                } else if character == '`' {
+                    Token::Name(self.quoted_name()?)
+                } else if is_name_head(character) {
+                    self.name()
+                } else {
+                    self.symbol()?
+                },
+                position,
+            ));
+        }
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(character) = self.peek(0) {
+            if character == '#' {
+                while !matches!(self.peek(0), Some('\n') | None) {
+                    self.advance();
+                }
+            } else if character.is_whitespace() {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn number(&mut self) -> Token {
+        let mut number = self.digits();
+
+        // Integer literals have type suffixes like `42i32`.
+        if matches!(self.peek(0), Some('i') | Some('u'))
+            && self.peek(1).map(|c| c.is_ascii_digit()) == Some(true)
+        {
+            number.push(self.advance());
+            number.push_str(&self.digits());
+
+            return Token::Integer(number);
+        }
+
+        if self.peek(0) == Some('.') && self.peek(1).map(|c| c.is_ascii_digit()) == Some(true) {
+            self.advance();
+            number.push('.');
+            number.push_str(&self.digits());
+        }
+
+        if matches!(self.peek(0), Some('e') | Some('E')) {
+            let offset = if matches!(self.peek(1), Some('+') | Some('-')) {
+                2
+            } else {
+                1
+            };
+
+            if self.peek(offset).map(|c| c.is_ascii_digit()) == Some(true) {
+                for _ in 0..offset {
+                    number.push(self.advance());
+                }
+
+                number.push_str(&self.digits());
+            }
+        }
+
+        Token::Number(number)
+    }
+
+    fn digits(&mut self) -> String {
+        let mut digits = String::new();
+
+        while let Some(character) = self.peek(0).filter(char::is_ascii_digit) {
+            self.advance();
+            digits.push(character);
+        }
+
+        digits
+    }
+
+    fn byte_string(&mut self) -> Result<Token, ParseError> {
+        let start = self.position();
+        let mut bytes = vec![];
+
+        self.advance();
+
+        loop {
+            match self.peek(0) {
+                None => return Err(ParseError::new("unterminated string literal", start)),
+                Some('"') => {
+                    self.advance();
+                    return Ok(Token::ByteString(bytes));
+                }
+                Some('\\') => {
+                    let position = self.position();
+
+                    self.advance();
+
+                    bytes.push(match self.peek(0).map(|_| self.advance()) {
+                        Some('"') => b'"',
+                        Some('\\') => b'\\',
+                        Some('n') => b'\n',
+                        Some('r') => b'\r',
+                        Some('t') => b'\t',
+                        Some('x') => self.hexadecimal_byte(position)?,
+                        _ => return Err(ParseError::new("invalid escape sequence", position)),
+                    });
+                }
+                Some(_) => {
+                    let character = self.advance();
+                    let mut buffer = [0; 4];
+
+                    bytes.extend(character.encode_utf8(&mut buffer).as_bytes());
+                }
+            }
+        }
+    }
+
+    fn hexadecimal_byte(&mut self, position: Position) -> Result<u8, ParseError> {
+        let digits = (0..2)
+            .map(|_| {
+                self.peek(0)
+                    .filter(char::is_ascii_hexdigit)
+                    .map(|_| self.advance())
+            })
+            .collect::<Option<String>>()
+            .ok_or_else(|| ParseError::new("invalid escape sequence", position))?;
+
+        Ok(u8::from_str_radix(&digits, 16).unwrap())
+    }
+
+    fn quoted_name(&mut self) -> Result<String, ParseError> {
+        let start = self.position();
+        let mut name = String::new();
+
+        self.advance();
+
+        loop {
+            match self.peek(0) {
+                None => return Err(ParseError::new("unterminated quoted name", start)),
+                Some('`') => {
+                    self.advance();
+                    return Ok(name);
+                }
+                Some('\\') => {
+                    let position = self.position();
+
+                    self.advance();
+
+                    name.push(match self.peek(0).map(|_| self.advance()) {
+                        Some(character @ ('`' | '\\')) => character,
+                        _ => return Err(ParseError::new("invalid escape sequence", position)),
+                    });
+                }
+                Some(_) => name.push(self.advance()),
+            }
+        }
+    }
+
+    fn name(&mut self) -> Token {
+        let mut name = String::new();
+
+        while let Some(character) = self.peek(0).filter(|&c| is_name_tail(c)) {
+            self.advance();
+            name.push(character);
+        }
+
+        if let Some(keyword) = KEYWORDS.iter().find(|&&keyword| keyword == name) {
+            Token::Keyword(keyword)
+        } else {
+            Token::Name(name)
+        }
+    }
+
+    fn symbol(&mut self) -> Result<Token, ParseError> {
+        let position = self.position();
+
+        for symbol in SYMBOLS {
+            if symbol
+                .chars()
+                .enumerate()
+                .all(|(index, character)| self.peek(index) == Some(character))
+            {
+                for _ in 0..symbol.len() {
+                    self.advance();
+                }
+
+                return Ok(Token::Symbol(symbol));
+            }
+        }
+
+        Err(ParseError::new(
+            format!("unexpected character `{}`", self.advance()),
+            position,
+        ))
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.characters.get(self.index + offset).copied()
+    }
+
+    fn advance(&mut self) -> char {
+        let character = self.characters[self.index];
+
+        self.index += 1;
+
+        if character == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        character
+    }
+
+    fn position(&self) -> Position {
+        Position::new(self.line, self.column)
+    }
+}
+
+pub fn is_name(name: &str) -> bool {
+    let mut characters = name.chars();
+
+    characters.next().map(is_name_head) == Some(true)
+        && characters.all(is_name_tail)
+        && !KEYWORDS.contains(&name)
+}
+
+fn is_name_head(character: char) -> bool {
+    character.is_ascii_alphabetic() || character == '_'
+}
+
+fn is_name_tail(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tokenize_without_positions(source: &str) -> Vec<Token> {
+        tokenize(source)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_names_and_keywords() {
+        assert_eq!(
+            tokenize_without_positions("foo let `let` `a\\`b`"),
+            vec![
+                Token::Name("foo".into()),
+                Token::Keyword("let"),
+                Token::Name("let".into()),
+                Token::Name("a`b".into()),
+                Token::End
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_numbers() {
+        assert_eq!(
+            tokenize_without_positions("42 4.2 1e10 2.5e-3 42u8 x.foo.0"),
+            vec![
+                Token::Number("42".into()),
+                Token::Number("4.2".into()),
+                Token::Number("1e10".into()),
+                Token::Number("2.5e-3".into()),
+                Token::Integer("42u8".into()),
+                Token::Name("x".into()),
+                Token::Symbol("."),
+                Token::Name("foo".into()),
+                Token::Symbol("."),
+                Token::Number("0".into()),
+                Token::End
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_byte_strings() {
+        assert_eq!(
+            tokenize_without_positions(r#""foo\n\"\x00\xff" "あ""#),
+            vec![
+                Token::ByteString(b"foo\n\"\x00\xff".to_vec()),
+                Token::ByteString("あ".as_bytes().to_vec()),
+                Token::End
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_symbols() {
+        assert_eq!(
+            tokenize_without_positions("=>=-> <= < # comment\n)"),
+            vec![
+                Token::Symbol("=>"),
+                Token::Symbol("="),
+                Token::Symbol("->"),
+                Token::Symbol("<="),
+                Token::Symbol("<"),
+                Token::Symbol(")"),
+                Token::End
+            ]
+        );
+    }
+
+    #[test]
+    fn track_positions() {
+        assert_eq!(
+            tokenize("foo\n  bar")
+                .unwrap()
+                .into_iter()
+                .map(|(_, position)| position)
+                .collect::<Vec<_>>(),
+            vec![
+                Position::new(1, 1),
+                Position::new(2, 3),
+                Position::new(2, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_to_tokenize_unterminated_string() {
+        assert_eq!(
+            tokenize("x \"foo"),
+            Err(ParseError::new(
+                "unterminated string literal",
+                Position::new(1, 3)
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_tokenize_unknown_character() {
+        assert_eq!(
+            tokenize("x $"),
+            Err(ParseError::new(
+                "unexpected character `$`",
+                Position::new(1, 3)
+            ))
+        );
+    }
+}
@@ -0,0 +1,1098 @@
+//! Textual syntax of eir modules.
+//!
+//! ```text
+//! module              = { item } ;
+//! item                = type_definition | sum_definition | foreign_declaration
+//!                     | foreign_definition | declaration | definition ;
+//! type_definition     = "type" name [ type_parameters ] "=" "{" [ fields | types ] "}" ;
+//! fields              = name ":" type { "," name ":" type } ;
+//! types               = type { "," type } ;
+//! sum_definition      = "sum" name "=" record_type { "|" record_type } ;
+//! foreign_declaration = "foreign" "declare" ( "source" | "target" ) name ":" type "=" string ;
+//! foreign_definition  = "foreign" "define" name "=" string ;
+//! declaration         = "declare" name ":" type ;
+//! definition          = ( "define" | "thunk" ) ( definition_body | constant_body ) ;
+//! definition_body     = name [ type_parameters ] [ "[" arguments "]" ] "(" arguments ")" ":" type
+//!                       "=" expression ;
+//! constant_body       = name ":" type "=" expression ;
+//! arguments           = argument { "," argument } ;
+//! argument            = name ":" type ;
+//! local_definition    = [ "thunk" ] definition_body ;
+//! type_parameters     = "<" type_variable { "," type_variable } ">" ;
+//! type_variable       = "'" name ;
+//!
+//! type                = atomic_type [ "->" type ] ;
+//! atomic_type         = "boolean" | "number" | "string" | "variant" | integer_type
+//!                     | record_type | tuple_type | sum_type | lazy_type | type_variable
+//!                     | "(" type ")" ;
+//! record_type         = name [ type_arguments ] ;
+//! tuple_type          = "{" [ fields | types ] "}" ;
+//! sum_type            = "sum" name ;
+//! lazy_type           = "lazy" atomic_type ;
+//! type_arguments      = "<" type { "," type } ">" ;
+//! integer_type        = "i8" | "i32" | "i64" | "u8" | "u32" | "u64" ;
+//!
+//! expression          = "let" name ":" type "=" expression "in" expression
+//!                     | "let" ( record_type | tuple_type ) "{" [ arguments ] "}" "=" expression
+//!                       "in" expression
+//!                     | "letrec" local_definition { "and" local_definition } "in" expression
+//!                     | "if" expression "then" expression "else" expression
+//!                     | "clone" "{" [ arguments ] "}" "in" expression
+//!                     | "drop" "{" [ arguments ] "}" "in" expression
+//!                     | disjunction ;
+//! disjunction         = conjunction { "||" conjunction } ;
+//! conjunction         = comparison { "&&" comparison } ;
+//! comparison          = additive [ ( "==" | "!=" | "<" | ">" | "<=" | ">=" ) [ operand_type ]
+//!                       additive ] ;
+//! additive            = multiplicative { ( "+" | "-" ) [ operand_type ] multiplicative } ;
+//! multiplicative      = prefix { ( "*" | "/" | "%" ) [ operand_type ] prefix } ;
+//! prefix              = "!" prefix | "-" [ operand_type ] prefix | power ;
+//! power               = postfix [ "^" [ operand_type ] prefix ] ;
+//! operand_type        = "[" type "]" ;
+//! postfix             = primary { [ type_arguments ] "[" type "]" "(" expression ")"
+//!                     | "." ( record_type | tuple_type ) "." field
+//!                     | "." ( record_type | tuple_type ) "{" [ field_update { "," field_update } ] "}" } ;
+//! field               = integer | name ;
+//! field_update        = field "=" expression ;
+//! primary             = [ "-" ] ( number | "nan" | "infinity" | integer_literal )
+//!                     | "true" | "false" | string | name
+//!                     | ( record_type | tuple_type ) "{" [ expression { "," expression } ] "}"
+//!                     | "case" [ "[" ( name | type ) "]" ] expression "of" "{" alternatives "}"
+//!                     | "try" expression "of" "{" name ":" type "=>" expression [ "," ] "}"
+//!                     | "sum" "[" name "," record_type "]" "(" expression ")"
+//!                     | "variant" "[" type "]" "(" expression ")"
+//!                     | "lazy" "[" type "]" "(" expression ")"
+//!                     | "force" "(" expression ")"
+//!                     | "convert" "[" type "," type [ "," rounding ] "]" "(" expression ")"
+//!                     | "length" "(" expression ")"
+//!                     | "concat" "(" expression "," expression ")"
+//!                     | "slice" "(" expression "," expression "," expression ")"
+//!                     | "(" expression ")" ;
+//! alternatives        = [ alternative { "," alternative } ] ;
+//! alternative         = name ":" type "=>" expression | literal "=>" expression
+//!                     | name "=>" expression ;
+//! literal             = [ "-" ] ( number | "nan" | "infinity" | integer_literal )
+//!                     | "true" | "false" | string ;
+//! rounding            = "ceiling" | "floor" | "round" | "truncate" ;
+//! ```
+//!
+//! - Function applications are written as `f[number -> number](x)` where the
+//!   type in brackets is the type of the applied function.
+//! - Operand types of arithmetic and comparison operators default to `number`
+//!   and are written in brackets like `x +[i32] y`.
+//! - Comparison operators accept booleans, strings, integers and numbers.
+//!   Booleans are ordered with `false` less than `true` and strings are
+//!   ordered lexicographically by their bytes.
+//! - Type definitions and global definitions can be polymorphic over type
+//!   variables written like `'a`. Polymorphic definitions are applied with
+//!   type arguments like `id<number>[number -> number](x)` and records of
+//!   polymorphic types are written like `box<number>{x}`. Recursive
+//!   references cannot instantiate polymorphic definitions with types
+//!   containing type variables other than bare ones like `box<'a>`.
+//! - Definitions joined by `and` in a `letrec` expression can refer to each
+//!   other. Thunks cannot be joined with other definitions.
+//! - Minus signs followed immediately by number or integer literals make
+//!   negative literals rather than negations.
+//! - Integer literals are written with suffixes of their types like `42u8`.
+//!   Values out of the ranges of their types are rejected.
+//! - Numeric conversions are written as `convert[number, i32, floor](x)` where
+//!   the types in brackets are source and target types. Rounding modes default
+//!   to `truncate`.
+//! - Byte string operations are written as `length(x)`, `concat(x, y)` and
+//!   `slice(x, start, end)` where lengths and indices are `u64` integers.
+//! - Record elements are written as `x.foo.0` where `foo` is a record type and
+//!   `0` is an element index. Elements of records with named fields can also be
+//!   written as `x.foo.bar` where `bar` is a field name.
+//! - Record updates are written as `x.foo{0 = 42, bar = true}` and copy
+//!   records with the given fields replaced. Records not shared by others are
+//!   updated in place.
+//! - Records are destructured like `let foo{x: number, y: boolean} = r in x`
+//!   where all elements are bound to variables of the given names in order.
+//!   Elements are moved out of records not shared by others.
+//! - Tuple types like `{number, boolean}` are anonymous record types which
+//!   need no type definitions and are equal if their element types are
+//!   equal. Tuples are written like `{number, boolean}{1, true}` and their
+//!   elements like `x.{number, boolean}.0`.
+//! - Anonymous record types with named fields are written like
+//!   `{x: number, y: boolean}` and are equal if their field names and element
+//!   types are equal. Their elements can be accessed by names like
+//!   `r.{x: number, y: boolean}.y`.
+//! - An alternative without a type is a default alternative and must come last.
+//! - Sums are closed unions of record types declared like
+//!   `sum shape = circle | square` and written as types like `sum shape`.
+//!   Values of sums are written like `sum[shape, circle](x)` and their case
+//!   expressions like `case[shape] x of { c: circle => ..., s: square => ... }`
+//!   where alternatives must cover all constructors unless default
+//!   alternatives are given.
+//! - Case expressions over booleans, strings, integers and numbers are written
+//!   like `case[string] x of { "foo" => ..., y => ... }` where alternatives
+//!   match literals equal to arguments. They need default alternatives
+//!   unless they cover both `true` and `false`.
+//! - Try operations are written like `try x of { e: error => ... }` and return
+//!   values of alternatives from the enclosing functions if operands are
+//!   variants of the given types. Otherwise, they evaluate to their operands.
+//!   They are allowed only as bound expressions of `let` or in tail positions.
+//! - Global definitions without argument lists like `define c: number = 42`
+//!   are constants evaluated at most once when they are referred to first.
+//!   They are referred to by their names as values of their result types.
+//! - Lazy values are written like `lazy[number](x + 1)` where the type in
+//!   brackets is the type of the suspended expression, and evaluated like
+//!   `force(y)` at most once. Their types are written like `lazy number`.
+//! - Environments of definitions are optional and written in brackets between
+//!   names and arguments.
+//! - Names are either identifiers of `[A-Za-z_][A-Za-z0-9_]*` which are not
+//!   keywords or arbitrary strings quoted by backquotes like `` `foo.bar` ``.
+//! - Strings are byte strings supporting `\"`, `\\`, `\n`, `\r`, `\t` and
+//!   `\xHH` escapes.
+//! - Comments start with `#` and continue until the ends of lines.
+//! - Trailing commas are allowed in lists.
+
+mod error;
+mod lexer;
+mod parser;
+
+use crate::{ir::*, types::Type};
+pub use error::{ParseError, Position};
+pub(crate) use lexer::is_name;
+use parser::Parser;
+
+pub fn parse(source: &str) -> Result<Module, ParseError> {
+    let mut parser = Parser::new(source)?;
+    let module = parser.module()?;
+
+    parser.end()?;
+
+    Ok(module)
+}
+
+pub fn parse_expression(source: &str) -> Result<Expression, ParseError> {
+    let mut parser = Parser::new(source)?;
+    let expression = parser.expression()?;
+
+    parser.end()?;
+
+    Ok(expression)
+}
+
+pub fn parse_type(source: &str) -> Result<Type, ParseError> {
+    let mut parser = Parser::new(source)?;
+    let type_ = parser.type_()?;
+
+    parser.end()?;
+
+    Ok(type_)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_empty_module() {
+        assert_eq!(
+            parse(""),
+            Ok(Module::new(vec![], vec![], vec![], vec![], vec![]))
+        );
+    }
+
+    #[test]
+    fn parse_module() {
+        assert_eq!(
+            parse(
+                r#"
+                # Records
+                type foo = {number, boolean}
+                type bar = {}
+
+                foreign declare target f: number -> number = "g"
+                foreign define h = "h"
+                declare i: foo -> number -> foo
+
+                define h(x: number): number = f[number -> number](x)
+                thunk j(x: number): bar = bar{}
+                "#
+            ),
+            Ok(Module::new(
+                vec![
+                    TypeDefinition::new(
+                        "foo",
+                        types::RecordBody::new(vec![Type::Number, Type::Boolean])
+                    ),
+                    TypeDefinition::new("bar", types::RecordBody::new(vec![])),
+                ],
+                vec![ForeignDeclaration::new(
+                    "f",
+                    "g",
+                    types::Function::new(Type::Number, Type::Number),
+                    CallingConvention::Target,
+                )],
+                vec![ForeignDefinition::new("h", "h")],
+                vec![Declaration::new(
+                    "i",
+                    types::Function::new(
+                        types::Record::new("foo"),
+                        types::Function::new(Type::Number, types::Record::new("foo"))
+                    ),
+                )],
+                vec![
+                    Definition::new(
+                        "h",
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            types::Function::new(Type::Number, Type::Number),
+                            Variable::new("f"),
+                            Variable::new("x")
+                        ),
+                        Type::Number
+                    ),
+                    Definition::thunk(
+                        "j",
+                        vec![Argument::new("x", Type::Number)],
+                        Record::new(types::Record::new("bar"), vec![]),
+                        types::Record::new("bar")
+                    ),
+                ],
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_definition_with_environment() {
+        assert_eq!(
+            parse("define f[y: number](x: number, z: string): number = y"),
+            Ok(Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::with_environment(
+                    "f",
+                    vec![Argument::new("y", Type::Number)],
+                    vec![
+                        Argument::new("x", Type::Number),
+                        Argument::new("z", Type::ByteString)
+                    ],
+                    Variable::new("y"),
+                    Type::Number
+                )]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_types() {
+        assert_eq!(parse_type("boolean"), Ok(Type::Boolean));
+        assert_eq!(parse_type("number"), Ok(Type::Number));
+        assert_eq!(parse_type("string"), Ok(Type::ByteString));
+        assert_eq!(parse_type("variant"), Ok(Type::Variant));
+        assert_eq!(parse_type("foo"), Ok(types::Record::new("foo").into()));
+        assert_eq!(
+            parse_type("`number`"),
+            Ok(types::Record::new("number").into())
+        );
+        assert_eq!(
+            parse_type("number -> boolean -> string"),
+            Ok(types::Function::new(
+                Type::Number,
+                types::Function::new(Type::Boolean, Type::ByteString)
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_type("lazy number -> lazy (number -> number)"),
+            Ok(types::Function::new(
+                types::Lazy::new(Type::Number),
+                types::Lazy::new(types::Function::new(Type::Number, Type::Number))
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_type("(number -> boolean) -> string"),
+            Ok(types::Function::new(
+                types::Function::new(Type::Number, Type::Boolean),
+                Type::ByteString
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_literals() {
+        assert_eq!(parse_expression("true"), Ok(true.into()));
+        assert_eq!(parse_expression("false"), Ok(false.into()));
+        assert_eq!(parse_expression("42"), Ok(42.0.into()));
+        assert_eq!(parse_expression("-4.2e1"), Ok((-42.0).into()));
+        assert_eq!(parse_expression("infinity"), Ok(f64::INFINITY.into()));
+        assert_eq!(parse_expression("-infinity"), Ok(f64::NEG_INFINITY.into()));
+        assert!(matches!(
+            parse_expression("nan"),
+            Ok(Expression::Number(number)) if number.is_nan()
+        ));
+        assert_eq!(
+            parse_expression(r#""foo\x00""#),
+            Ok(ByteString::new(b"foo\x00".to_vec()).into())
+        );
+    }
+
+    #[test]
+    fn parse_operations() {
+        assert_eq!(
+            parse_expression("1 + 2 * 3 - 4"),
+            Ok(ArithmeticOperation::new(
+                Type::Number,
+                ArithmeticOperator::Subtract,
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Add,
+                    1.0,
+                    ArithmeticOperation::new(Type::Number, ArithmeticOperator::Multiply, 2.0, 3.0)
+                ),
+                4.0
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("x / (y - -1) <= 2"),
+            Ok(ComparisonOperation::new(
+                Type::Number,
+                ComparisonOperator::LessThanOrEqual,
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Divide,
+                    Variable::new("x"),
+                    ArithmeticOperation::new(
+                        Type::Number,
+                        ArithmeticOperator::Subtract,
+                        Variable::new("y"),
+                        -1.0
+                    )
+                ),
+                2.0
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_integers() {
+        assert_eq!(
+            parse_expression("42i32"),
+            Ok(Integer::new(types::Integer::Signed32, 42).into())
+        );
+        assert_eq!(
+            parse_expression("-128i8"),
+            Ok(Integer::new(types::Integer::Signed8, 0x80).into())
+        );
+        assert_eq!(
+            parse_expression("255u8"),
+            Ok(Integer::new(types::Integer::Unsigned8, 255).into())
+        );
+        assert_eq!(
+            parse_type("u64 -> i64"),
+            Ok(types::Function::new(types::Integer::Unsigned64, types::Integer::Signed64).into())
+        );
+        assert_eq!(
+            parse_expression("x +[i64] 1i64 <[i64] y"),
+            Ok(ComparisonOperation::new(
+                types::Integer::Signed64,
+                ComparisonOperator::LessThan,
+                ArithmeticOperation::new(
+                    types::Integer::Signed64,
+                    ArithmeticOperator::Add,
+                    Variable::new("x"),
+                    Integer::new(types::Integer::Signed64, 1)
+                ),
+                Variable::new("y")
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_integers_out_of_range() {
+        for source in [
+            "128i8",
+            "-129i8",
+            "256u8",
+            "-1u8",
+            "18446744073709551616u64",
+        ] {
+            assert_eq!(
+                parse_expression(source),
+                Err(ParseError::new(
+                    "integer literal out of range",
+                    Position::new(1, if source.starts_with('-') { 2 } else { 1 })
+                ))
+            );
+        }
+
+        assert_eq!(
+            parse_expression("1i16"),
+            Err(ParseError::new("invalid integer type", Position::new(1, 1)))
+        );
+    }
+
+    #[test]
+    fn parse_powers_and_negations() {
+        assert_eq!(
+            parse_expression("-x ^ 2 ^ y % 3"),
+            Ok(ArithmeticOperation::new(
+                Type::Number,
+                ArithmeticOperator::Remainder,
+                Negation::new(
+                    Type::Number,
+                    ArithmeticOperation::new(
+                        Type::Number,
+                        ArithmeticOperator::Power,
+                        Variable::new("x"),
+                        ArithmeticOperation::new(
+                            Type::Number,
+                            ArithmeticOperator::Power,
+                            2.0,
+                            Variable::new("y")
+                        )
+                    )
+                ),
+                3.0
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("-1 ^ -[i32] x"),
+            Ok(ArithmeticOperation::new(
+                Type::Number,
+                ArithmeticOperator::Power,
+                -1.0,
+                Negation::new(types::Integer::Signed32, Variable::new("x"))
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_boolean_operations() {
+        assert_eq!(
+            parse_expression("!x || y && z == 1"),
+            Ok(BooleanOperation::new(
+                BooleanOperator::Or,
+                Not::new(Variable::new("x")),
+                BooleanOperation::new(
+                    BooleanOperator::And,
+                    Variable::new("y"),
+                    ComparisonOperation::new(
+                        Type::Number,
+                        ComparisonOperator::Equal,
+                        Variable::new("z"),
+                        1.0
+                    )
+                )
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("x && y && z"),
+            Ok(BooleanOperation::new(
+                BooleanOperator::And,
+                BooleanOperation::new(BooleanOperator::And, Variable::new("x"), Variable::new("y")),
+                Variable::new("z")
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_numeric_conversions() {
+        assert_eq!(
+            parse_expression("convert[number, i32](x)"),
+            Ok(NumericConversion::new(
+                Type::Number,
+                types::Integer::Signed32,
+                Rounding::Truncate,
+                Variable::new("x")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("convert[number, number, ceiling](x)"),
+            Ok(NumericConversion::new(
+                Type::Number,
+                Type::Number,
+                Rounding::Ceiling,
+                Variable::new("x")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("convert[number, u8, up](x)"),
+            Err(ParseError::new(
+                "unknown rounding mode",
+                Position::new(1, 21)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_byte_string_operations() {
+        assert_eq!(
+            parse_expression(r#"slice(concat(x, "foo"), 1u64, length(x),)"#),
+            Ok(ByteStringSlice::new(
+                ByteStringConcatenation::new(Variable::new("x"), ByteString::new("foo")),
+                Integer::new(types::Integer::Unsigned64, 1),
+                ByteStringLength::new(Variable::new("x"))
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("x <[string] y"),
+            Ok(ComparisonOperation::new(
+                Type::ByteString,
+                ComparisonOperator::LessThan,
+                Variable::new("x"),
+                Variable::new("y")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("concat(x)"),
+            Err(ParseError::new("expected 2 arguments", Position::new(1, 1)))
+        );
+    }
+
+    #[test]
+    fn parse_function_applications() {
+        assert_eq!(
+            parse_expression("f[number -> number -> number](1)[number -> number](2)"),
+            Ok(FunctionApplication::new(
+                types::Function::new(Type::Number, Type::Number),
+                FunctionApplication::new(
+                    types::Function::new(
+                        Type::Number,
+                        types::Function::new(Type::Number, Type::Number)
+                    ),
+                    Variable::new("f"),
+                    1.0
+                ),
+                2.0
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_records() {
+        assert_eq!(
+            parse_expression("foo{1, true,}.foo.1"),
+            Ok(RecordElement::new(
+                types::Record::new("foo"),
+                1,
+                Record::new(types::Record::new("foo"), vec![1.0.into(), true.into()])
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_polymorphic_definitions() {
+        assert_eq!(
+            parse(
+                "type box<'a> = {'a}
+                 define id<'a, 'b>(x: 'a, y: 'b): 'a = x"
+            ),
+            Ok(Module::new(
+                vec![TypeDefinition::with_parameters(
+                    "box",
+                    vec!["a".into()],
+                    types::RecordBody::new(vec![Type::Variable("a".into())])
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![Definition::with_type_parameters(
+                    "id",
+                    vec!["a".into(), "b".into()],
+                    vec![
+                        Argument::new("x", Type::Variable("a".into())),
+                        Argument::new("y", Type::Variable("b".into()))
+                    ],
+                    Variable::new("x"),
+                    Type::Variable("a".into())
+                )]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_type_arguments() {
+        let record_type = types::Record::with_arguments("box", vec![Type::Number]);
+
+        assert_eq!(
+            parse_expression(
+                "id<box<number>>[box<number> -> box<number>](box<number>{1}).box<number>.0"
+            ),
+            Ok(RecordElement::new(
+                record_type.clone(),
+                0,
+                FunctionApplication::with_type_arguments(
+                    types::Function::new(record_type.clone(), record_type.clone()),
+                    Variable::new("id"),
+                    vec![record_type.clone().into()],
+                    Record::new(record_type, vec![1.0.into()])
+                )
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("x < y"),
+            Ok(ComparisonOperation::new(
+                Type::Number,
+                ComparisonOperator::LessThan,
+                Variable::new("x"),
+                Variable::new("y")
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_named_fields() {
+        assert_eq!(
+            parse("type foo = {x: number, y: bar}"),
+            Ok(Module::new(
+                vec![TypeDefinition::new(
+                    "foo",
+                    types::RecordBody::with_fields(vec![
+                        ("x".into(), Type::Number),
+                        ("y".into(), types::Record::new("bar").into())
+                    ])
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![]
+            ))
+        );
+        assert_eq!(
+            parse_expression("x.foo.y"),
+            Ok(
+                RecordElement::with_field_name(types::Record::new("foo"), "y", Variable::new("x"))
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_record_updates() {
+        assert_eq!(
+            parse_expression("x.foo{0 = 1, y = true,}.foo.y"),
+            Ok(RecordElement::with_field_name(
+                types::Record::new("foo"),
+                "y",
+                RecordUpdate::new(
+                    types::Record::new("foo"),
+                    Variable::new("x"),
+                    vec![
+                        RecordUpdateField::new(0, 1.0),
+                        RecordUpdateField::with_field_name("y", true)
+                    ]
+                )
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_tuples() {
+        let tuple_type = types::Tuple::new(vec![Type::Number, Type::Boolean]);
+
+        assert_eq!(
+            parse_expression("{number, boolean}{1, true}.{number, boolean}.1"),
+            Ok(RecordElement::new(
+                tuple_type.clone(),
+                1,
+                Record::new(tuple_type, vec![1.0.into(), true.into()])
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_type("{} -> {foo, {}}"),
+            Ok(types::Function::new(
+                types::Tuple::new(vec![]),
+                types::Tuple::new(vec![
+                    types::Record::new("foo").into(),
+                    types::Tuple::new(vec![]).into()
+                ])
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_anonymous_records_with_field_names() {
+        let record_type = types::Tuple::with_fields(vec![
+            ("x".into(), Type::Number),
+            ("y".into(), Type::Boolean),
+        ]);
+
+        assert_eq!(
+            parse_expression("{x: number, y: boolean}{1, true}.{x: number, y: boolean}.y"),
+            Ok(RecordElement::with_field_name(
+                record_type.clone(),
+                "y",
+                Record::new(record_type, vec![1.0.into(), true.into()])
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_type("{x: {}} -> {foo, {}}"),
+            Ok(types::Function::new(
+                types::Tuple::with_fields(vec![("x".into(), types::Tuple::new(vec![]).into())]),
+                types::Tuple::new(vec![
+                    types::Record::new("foo").into(),
+                    types::Tuple::new(vec![]).into()
+                ])
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_let_expressions() {
+        assert_eq!(
+            parse_expression("let x: number = let y: number = 1 in y in x"),
+            Ok(Let::new(
+                "x",
+                Type::Number,
+                Let::new("y", Type::Number, 1.0, Variable::new("y")),
+                Variable::new("x")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("letrec f(x: number): number = f[number -> number](x) in f"),
+            Ok(LetRecursive::new(
+                Definition::new(
+                    "f",
+                    vec![Argument::new("x", Type::Number)],
+                    FunctionApplication::new(
+                        types::Function::new(Type::Number, Type::Number),
+                        Variable::new("f"),
+                        Variable::new("x")
+                    ),
+                    Type::Number
+                ),
+                Variable::new("f")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("let foo{x: number, y: boolean} = r in x"),
+            Ok(LetRecord::new(
+                types::Record::new("foo"),
+                vec![
+                    Argument::new("x", Type::Number),
+                    Argument::new("y", Type::Boolean)
+                ],
+                Variable::new("r"),
+                Variable::new("x")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("let {number}{x: number} = r in x"),
+            Ok(LetRecord::new(
+                types::Tuple::new(vec![Type::Number]),
+                vec![Argument::new("x", Type::Number)],
+                Variable::new("r"),
+                Variable::new("x")
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_mutually_recursive_definitions() {
+        let function_type = types::Function::new(Type::Number, Type::Number);
+
+        assert_eq!(
+            parse_expression(
+                "letrec f(x: number): number = g[number -> number](x) \
+                 and thunk g(x: number): number = f[number -> number](x) in f"
+            ),
+            Ok(LetRecursive::with_definitions(
+                vec![
+                    Definition::new(
+                        "f",
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            function_type.clone(),
+                            Variable::new("g"),
+                            Variable::new("x")
+                        ),
+                        Type::Number
+                    ),
+                    Definition::thunk(
+                        "g",
+                        vec![Argument::new("x", Type::Number)],
+                        FunctionApplication::new(
+                            function_type,
+                            Variable::new("f"),
+                            Variable::new("x")
+                        ),
+                        Type::Number
+                    ),
+                ],
+                Variable::new("f")
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_if() {
+        assert_eq!(
+            parse_expression("if x == 0 then 1 else 2"),
+            Ok(If::new(
+                ComparisonOperation::new(
+                    Type::Number,
+                    ComparisonOperator::Equal,
+                    Variable::new("x"),
+                    0.0
+                ),
+                1.0,
+                2.0
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_case() {
+        assert_eq!(
+            parse_expression("case x of { y: number => y, z: foo => 1, z => 2 }"),
+            Ok(Case::new(
+                Variable::new("x"),
+                vec![
+                    Alternative::new(Type::Number, "y", Variable::new("y")),
+                    Alternative::new(types::Record::new("foo"), "z", 1.0)
+                ],
+                Some(DefaultAlternative::new("z", 2.0))
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("case x of {}"),
+            Ok(Case::new(Variable::new("x"), vec![], None).into())
+        );
+    }
+
+    #[test]
+    fn parse_sums() {
+        assert_eq!(
+            parse("sum foo = bar | baz<number>"),
+            Ok(Module::with_sum_definitions(
+                vec![],
+                vec![SumDefinition::new(
+                    "foo",
+                    vec![
+                        types::Record::new("bar"),
+                        types::Record::with_arguments("baz", vec![Type::Number])
+                    ]
+                )],
+                vec![],
+                vec![],
+                vec![],
+                vec![]
+            ))
+        );
+        assert_eq!(parse_type("sum foo"), Ok(types::Sum::new("foo").into()));
+        assert_eq!(
+            parse_expression("sum[foo, bar](x)"),
+            Ok(Sum::new(
+                types::Sum::new("foo"),
+                types::Record::new("bar"),
+                Variable::new("x")
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("case[foo] x of { y: bar => 1, z => 2 }"),
+            Ok(SumCase::new(
+                types::Sum::new("foo"),
+                Variable::new("x"),
+                vec![Alternative::new(types::Record::new("bar"), "y", 1.0)],
+                Some(DefaultAlternative::new("z", 2.0))
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_try_operation() {
+        assert_eq!(
+            parse_expression("try x of { e: foo => variant[foo](e) }"),
+            Ok(TryOperation::new(
+                Variable::new("x"),
+                "e",
+                types::Record::new("foo"),
+                Variant::new(types::Record::new("foo"), Variable::new("e"))
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_literal_cases() {
+        assert_eq!(
+            parse_expression(r#"case[string] x of { "foo" => 1, "bar" => 2, y => 3 }"#),
+            Ok(LiteralCase::new(
+                Type::ByteString,
+                Variable::new("x"),
+                vec![
+                    LiteralAlternative::new(ByteString::new("foo"), 1.0),
+                    LiteralAlternative::new(ByteString::new("bar"), 2.0)
+                ],
+                Some(DefaultAlternative::new("y", 3.0))
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("case[i32] x of { -1i32 => true, 0i32 => false }"),
+            Ok(LiteralCase::new(
+                types::Integer::Signed32,
+                Variable::new("x"),
+                vec![
+                    LiteralAlternative::new(
+                        Integer::new(types::Integer::Signed32, -1i64 as u64),
+                        true
+                    ),
+                    LiteralAlternative::new(Integer::new(types::Integer::Signed32, 0), false)
+                ],
+                None
+            )
+            .into())
+        );
+        assert_eq!(
+            parse_expression("case[number] x of { y: number => y }"),
+            Err(ParseError::new(
+                "expected `=>`, found `:`",
+                Position::new(1, 22)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_lazy_and_force() {
+        assert_eq!(
+            parse_expression("lazy[number](force(x) + 1)"),
+            Ok(Lazy::new(
+                Type::Number,
+                ArithmeticOperation::new(
+                    Type::Number,
+                    ArithmeticOperator::Add,
+                    Force::new(Variable::new("x")),
+                    1.0
+                )
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn parse_variant() {
+        assert_eq!(
+            parse_expression("variant[number](42)"),
+            Ok(Variant::new(Type::Number, 42.0).into())
+        );
+    }
+
+    #[test]
+    fn parse_clone_and_drop_variables() {
+        assert_eq!(
+            parse_expression("clone {x: number} in drop {y: string, z: foo} in x"),
+            Ok(CloneVariables::new(
+                vec![("x".into(), Type::Number)].into_iter().collect(),
+                DropVariables::new(
+                    vec![
+                        ("y".into(), Type::ByteString),
+                        ("z".into(), types::Record::new("foo").into())
+                    ]
+                    .into_iter()
+                    .collect(),
+                    Variable::new("x")
+                )
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_unexpected_token() {
+        assert_eq!(
+            parse("define f(x: number): number =\n  let x: number = 1 x"),
+            Err(ParseError::new(
+                "expected `in`, found name `x`",
+                Position::new(2, 21)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_constant() {
+        assert_eq!(
+            parse("define c: number = 42 thunk d: number = c"),
+            Ok(Module::new(
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![
+                    Definition::new("c", vec![], 42.0, Type::Number),
+                    Definition::thunk("d", vec![], Variable::new("c"), Type::Number),
+                ],
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_definition_without_arguments() {
+        assert_eq!(
+            parse("define f(): number = 42"),
+            Err(ParseError::new(
+                "definition must have at least one argument",
+                Position::new(1, 10)
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_alternative_after_default_alternative() {
+        assert_eq!(
+            parse_expression("case x of { y => 1, z: number => 2 }"),
+            Err(ParseError::new(
+                "expected `}` after default alternative, found name `z`",
+                Position::new(1, 21)
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_trailing_tokens() {
+        assert_eq!(
+            parse_expression("x y"),
+            Err(ParseError::new(
+                "expected end of input, found name `y`",
+                Position::new(1, 3)
+            ))
+        );
+    }
+
+    #[test]
+    fn display_error() {
+        assert_eq!(
+            ParseError::new("foo", Position::new(1, 2)).to_string(),
+            "1:2: foo"
+        );
+    }
+}
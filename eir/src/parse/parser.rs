@@ -0,0 +1,1110 @@
+use super::{
+    error::{ParseError, Position},
+    lexer::{tokenize, Token},
+};
+use crate::{
+    ir::*,
+    types::{self, Type},
+};
+use std::{collections::HashMap, convert::TryInto};
+
+pub struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize,
+}
+
+impl Parser {
+    pub fn new(source: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            index: 0,
+        })
+    }
+
+    pub fn module(&mut self) -> Result<Module, ParseError> {
+        let mut type_definitions = vec![];
+        let mut sum_definitions = vec![];
+        let mut foreign_declarations = vec![];
+        let mut foreign_definitions = vec![];
+        let mut declarations = vec![];
+        let mut definitions = vec![];
+
+        loop {
+            match self.peek() {
+                Token::Keyword("type") => type_definitions.push(self.type_definition()?),
+                Token::Keyword("sum") => sum_definitions.push(self.sum_definition()?),
+                Token::Keyword("foreign") => {
+                    self.advance();
+
+                    match self.peek() {
+                        Token::Keyword("declare") => {
+                            foreign_declarations.push(self.foreign_declaration()?)
+                        }
+                        Token::Keyword("define") => {
+                            foreign_definitions.push(self.foreign_definition()?)
+                        }
+                        _ => return Err(self.unexpected("`declare` or `define`")),
+                    }
+                }
+                Token::Keyword("declare") => declarations.push(self.declaration()?),
+                Token::Keyword("define") | Token::Keyword("thunk") => {
+                    definitions.push(self.global_definition()?)
+                }
+                Token::End => break,
+                _ => return Err(self.unexpected("module item")),
+            }
+        }
+
+        Ok(Module::with_sum_definitions(
+            type_definitions,
+            sum_definitions,
+            foreign_declarations,
+            foreign_definitions,
+            declarations,
+            definitions,
+        ))
+    }
+
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.expect(&Token::End, "end of input")
+    }
+
+    fn type_definition(&mut self) -> Result<TypeDefinition, ParseError> {
+        self.keyword("type")?;
+        let name = self.name()?;
+        let parameters = self.type_parameters()?;
+        self.symbol("=")?;
+
+        Ok(TypeDefinition::with_parameters(
+            name,
+            parameters,
+            self.record_body()?,
+        ))
+    }
+
+    fn record_body(&mut self) -> Result<types::RecordBody, ParseError> {
+        self.symbol("{")?;
+
+        Ok(
+            if matches!(self.peek(), Token::Name(_)) && self.peek_next() == &Token::Symbol(":") {
+                types::RecordBody::with_fields(self.list("}", Self::field)?)
+            } else {
+                types::RecordBody::new(self.list("}", Self::type_)?)
+            },
+        )
+    }
+
+    fn field(&mut self) -> Result<(String, Type), ParseError> {
+        let name = self.name()?;
+        self.symbol(":")?;
+
+        Ok((name, self.type_()?))
+    }
+
+    fn sum_definition(&mut self) -> Result<SumDefinition, ParseError> {
+        self.keyword("sum")?;
+        let name = self.name()?;
+        self.symbol("=")?;
+
+        let mut constructors = vec![self.record_type()?];
+
+        while self.peek() == &Token::Symbol("|") {
+            self.advance();
+            constructors.push(self.record_type()?);
+        }
+
+        Ok(SumDefinition::new(name, constructors))
+    }
+
+    fn foreign_declaration(&mut self) -> Result<ForeignDeclaration, ParseError> {
+        self.keyword("declare")?;
+        let calling_convention = self.calling_convention()?;
+        let name = self.name()?;
+        self.symbol(":")?;
+        let type_ = self.function_type()?;
+        self.symbol("=")?;
+        let foreign_name = self.foreign_name()?;
+
+        Ok(ForeignDeclaration::new(
+            name,
+            foreign_name,
+            type_,
+            calling_convention,
+        ))
+    }
+
+    fn calling_convention(&mut self) -> Result<CallingConvention, ParseError> {
+        let calling_convention = match self.peek() {
+            Token::Name(name) if name == "source" => CallingConvention::Source,
+            Token::Name(name) if name == "target" => CallingConvention::Target,
+            _ => return Err(self.unexpected("`source` or `target`")),
+        };
+
+        self.advance();
+
+        Ok(calling_convention)
+    }
+
+    fn foreign_definition(&mut self) -> Result<ForeignDefinition, ParseError> {
+        self.keyword("define")?;
+        let name = self.name()?;
+        self.symbol("=")?;
+
+        Ok(ForeignDefinition::new(name, self.foreign_name()?))
+    }
+
+    fn foreign_name(&mut self) -> Result<String, ParseError> {
+        let position = self.position();
+
+        String::from_utf8(self.byte_string()?)
+            .map_err(|_| ParseError::new("foreign name must be valid UTF-8", position))
+    }
+
+    fn declaration(&mut self) -> Result<Declaration, ParseError> {
+        self.keyword("declare")?;
+        let name = self.name()?;
+        self.symbol(":")?;
+
+        Ok(Declaration::new(name, self.function_type()?))
+    }
+
+    fn global_definition(&mut self) -> Result<Definition, ParseError> {
+        let is_thunk = match self.peek() {
+            Token::Keyword("define") => false,
+            Token::Keyword("thunk") => true,
+            _ => return Err(self.unexpected("`define` or `thunk`")),
+        };
+
+        self.advance();
+
+        if self.peek_next() == &Token::Symbol(":") {
+            self.constant_body(is_thunk)
+        } else {
+            self.definition_body(is_thunk)
+        }
+    }
+
+    fn constant_body(&mut self, is_thunk: bool) -> Result<Definition, ParseError> {
+        let name = self.name()?;
+        self.symbol(":")?;
+        let result_type = self.type_()?;
+        self.symbol("=")?;
+        let body = self.expression()?;
+
+        Ok(Definition::with_options(
+            name,
+            vec![],
+            vec![],
+            vec![],
+            body,
+            result_type,
+            is_thunk,
+        ))
+    }
+
+    fn local_definition(&mut self) -> Result<Definition, ParseError> {
+        let is_thunk = self.peek() == &Token::Keyword("thunk");
+
+        if is_thunk {
+            self.advance();
+        }
+
+        self.definition_body(is_thunk)
+    }
+
+    fn definition_body(&mut self, is_thunk: bool) -> Result<Definition, ParseError> {
+        let name = self.name()?;
+        let type_parameters = self.type_parameters()?;
+
+        let environment = if self.peek() == &Token::Symbol("[") {
+            self.advance();
+            self.list("]", Self::argument)?
+        } else {
+            vec![]
+        };
+
+        self.symbol("(")?;
+        let position = self.position();
+        let arguments = self.list(")", Self::argument)?;
+
+        if arguments.is_empty() {
+            return Err(ParseError::new(
+                "definition must have at least one argument",
+                position,
+            ));
+        }
+
+        self.symbol(":")?;
+        let result_type = self.type_()?;
+        self.symbol("=")?;
+        let body = self.expression()?;
+
+        Ok(Definition::with_options(
+            name,
+            type_parameters,
+            environment,
+            arguments,
+            body,
+            result_type,
+            is_thunk,
+        ))
+    }
+
+    fn argument(&mut self) -> Result<Argument, ParseError> {
+        let name = self.name()?;
+        self.symbol(":")?;
+
+        Ok(Argument::new(name, self.type_()?))
+    }
+
+    fn type_parameters(&mut self) -> Result<Vec<String>, ParseError> {
+        Ok(if self.peek() == &Token::Symbol("<") {
+            self.advance();
+            self.list(">", Self::type_variable)?
+        } else {
+            vec![]
+        })
+    }
+
+    fn type_variable(&mut self) -> Result<String, ParseError> {
+        self.symbol("'")?;
+        self.name()
+    }
+
+    pub fn type_(&mut self) -> Result<Type, ParseError> {
+        let type_ = self.atomic_type()?;
+
+        Ok(if self.peek() == &Token::Symbol("->") {
+            self.advance();
+            types::Function::new(type_, self.type_()?).into()
+        } else {
+            type_
+        })
+    }
+
+    fn function_type(&mut self) -> Result<types::Function, ParseError> {
+        let position = self.position();
+
+        self.type_()?
+            .into_function()
+            .ok_or_else(|| ParseError::new("function type expected", position))
+    }
+
+    fn atomic_type(&mut self) -> Result<Type, ParseError> {
+        let type_ = match self.peek() {
+            Token::Keyword("boolean") => Type::Boolean,
+            Token::Keyword("number") => Type::Number,
+            Token::Keyword("string") => Type::ByteString,
+            Token::Keyword("variant") => Type::Variant,
+            Token::Keyword(keyword) if integer_type(keyword).is_some() => {
+                integer_type(keyword).unwrap().into()
+            }
+            Token::Keyword("sum") => {
+                self.advance();
+
+                return Ok(types::Sum::new(self.name()?).into());
+            }
+            Token::Keyword("lazy") => {
+                self.advance();
+
+                return Ok(types::Lazy::new(self.atomic_type()?).into());
+            }
+            Token::Name(_) => return Ok(self.record_type()?.into()),
+            Token::Symbol("'") => return Ok(Type::Variable(self.type_variable()?)),
+            Token::Symbol("{") => return Ok(self.tuple_type()?.into()),
+            Token::Symbol("(") => {
+                self.advance();
+                let type_ = self.type_()?;
+                self.symbol(")")?;
+
+                return Ok(type_);
+            }
+            _ => return Err(self.unexpected("type")),
+        };
+
+        self.advance();
+
+        Ok(type_)
+    }
+
+    fn record_type(&mut self) -> Result<types::Record, ParseError> {
+        let name = self.name()?;
+
+        Ok(types::Record::with_arguments(
+            name,
+            if self.peek() == &Token::Symbol("<") {
+                self.advance();
+                self.list(">", Self::type_)?
+            } else {
+                vec![]
+            },
+        ))
+    }
+
+    fn tuple_type(&mut self) -> Result<types::Tuple, ParseError> {
+        self.symbol("{")?;
+
+        Ok(
+            if matches!(self.peek(), Token::Name(_)) && self.peek_next() == &Token::Symbol(":") {
+                types::Tuple::with_fields(self.list("}", Self::field)?)
+            } else {
+                types::Tuple::new(self.list("}", Self::type_)?)
+            },
+        )
+    }
+
+    // Parses type arguments only if they are followed by the given symbol
+    // because `<` can be a comparison operator in expressions.
+    fn type_arguments_before(&mut self, symbol: &'static str) -> Option<Vec<Type>> {
+        let index = self.index;
+
+        if self.peek() == &Token::Symbol("<") {
+            self.advance();
+
+            if let Ok(arguments) = self.list(">", Self::type_) {
+                if self.peek() == &Token::Symbol(symbol) {
+                    return Some(arguments);
+                }
+            }
+        }
+
+        self.index = index;
+
+        None
+    }
+
+    pub fn expression(&mut self) -> Result<Expression, ParseError> {
+        Ok(match self.peek() {
+            Token::Keyword("let") => {
+                self.advance();
+
+                if self.peek_next() == &Token::Symbol(":") {
+                    let name = self.name()?;
+                    self.symbol(":")?;
+                    let type_ = self.type_()?;
+                    self.symbol("=")?;
+                    let bound_expression = self.expression()?;
+                    self.keyword("in")?;
+
+                    Let::new(name, type_, bound_expression, self.expression()?).into()
+                } else {
+                    let type_ = if self.peek() == &Token::Symbol("{") {
+                        self.tuple_type()?.into()
+                    } else {
+                        Type::from(self.record_type()?)
+                    };
+                    self.symbol("{")?;
+                    let elements = self.list("}", Self::argument)?;
+                    self.symbol("=")?;
+                    let bound_expression = self.expression()?;
+                    self.keyword("in")?;
+
+                    LetRecord::new(type_, elements, bound_expression, self.expression()?).into()
+                }
+            }
+            Token::Keyword("letrec") => {
+                self.advance();
+                let mut definitions = vec![self.local_definition()?];
+
+                while self.peek() == &Token::Keyword("and") {
+                    self.advance();
+                    definitions.push(self.local_definition()?);
+                }
+
+                self.keyword("in")?;
+
+                LetRecursive::with_definitions(definitions, self.expression()?).into()
+            }
+            Token::Keyword("if") => {
+                self.advance();
+                let condition = self.expression()?;
+                self.keyword("then")?;
+                let then = self.expression()?;
+                self.keyword("else")?;
+
+                If::new(condition, then, self.expression()?).into()
+            }
+            Token::Keyword("clone") => {
+                self.advance();
+                let variables = self.variables()?;
+                self.keyword("in")?;
+
+                CloneVariables::new(variables, self.expression()?).into()
+            }
+            Token::Keyword("drop") => {
+                self.advance();
+                let variables = self.variables()?;
+                self.keyword("in")?;
+
+                DropVariables::new(variables, self.expression()?).into()
+            }
+            _ => self.disjunction()?,
+        })
+    }
+
+    fn variables(&mut self) -> Result<HashMap<String, Type>, ParseError> {
+        self.symbol("{")?;
+
+        Ok(self
+            .list("}", Self::argument)?
+            .into_iter()
+            .map(|argument| (argument.name().into(), argument.type_().clone()))
+            .collect())
+    }
+
+    fn disjunction(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.conjunction()?;
+
+        while self.peek() == &Token::Symbol("||") {
+            self.advance();
+            lhs = BooleanOperation::new(BooleanOperator::Or, lhs, self.conjunction()?).into();
+        }
+
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.comparison_operation()?;
+
+        while self.peek() == &Token::Symbol("&&") {
+            self.advance();
+            lhs = BooleanOperation::new(BooleanOperator::And, lhs, self.comparison_operation()?)
+                .into();
+        }
+
+        Ok(lhs)
+    }
+
+    fn comparison_operation(&mut self) -> Result<Expression, ParseError> {
+        let lhs = self.additive_operation()?;
+
+        let operator = match self.peek() {
+            Token::Symbol("==") => ComparisonOperator::Equal,
+            Token::Symbol("!=") => ComparisonOperator::NotEqual,
+            Token::Symbol("<") => ComparisonOperator::LessThan,
+            Token::Symbol(">") => ComparisonOperator::GreaterThan,
+            Token::Symbol("<=") => ComparisonOperator::LessThanOrEqual,
+            Token::Symbol(">=") => ComparisonOperator::GreaterThanOrEqual,
+            _ => return Ok(lhs),
+        };
+
+        self.advance();
+        let type_ = self.operand_type()?;
+
+        Ok(ComparisonOperation::new(type_, operator, lhs, self.additive_operation()?).into())
+    }
+
+    fn additive_operation(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.multiplicative_operation()?;
+
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol("+") => ArithmeticOperator::Add,
+                Token::Symbol("-") => ArithmeticOperator::Subtract,
+                _ => return Ok(lhs),
+            };
+
+            self.advance();
+            let type_ = self.operand_type()?;
+            lhs = ArithmeticOperation::new(type_, operator, lhs, self.multiplicative_operation()?)
+                .into();
+        }
+    }
+
+    fn multiplicative_operation(&mut self) -> Result<Expression, ParseError> {
+        let mut lhs = self.prefix_operation()?;
+
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol("*") => ArithmeticOperator::Multiply,
+                Token::Symbol("/") => ArithmeticOperator::Divide,
+                Token::Symbol("%") => ArithmeticOperator::Remainder,
+                _ => return Ok(lhs),
+            };
+
+            self.advance();
+            let type_ = self.operand_type()?;
+            lhs = ArithmeticOperation::new(type_, operator, lhs, self.prefix_operation()?).into();
+        }
+    }
+
+    // Parses an optional operand type of a binary operator which defaults to
+    // a number type.
+    fn operand_type(&mut self) -> Result<Type, ParseError> {
+        Ok(if self.peek() == &Token::Symbol("[") {
+            self.advance();
+            let type_ = self.type_()?;
+            self.symbol("]")?;
+
+            type_
+        } else {
+            Type::Number
+        })
+    }
+
+    fn prefix_operation(&mut self) -> Result<Expression, ParseError> {
+        match self.peek() {
+            Token::Symbol("!") => {
+                self.advance();
+
+                Ok(Not::new(self.prefix_operation()?).into())
+            }
+            // Minus signs followed by numbers are parsed as negative literals.
+            Token::Symbol("-")
+                if !matches!(
+                    self.peek_next(),
+                    Token::Keyword("nan")
+                        | Token::Keyword("infinity")
+                        | Token::Number(_)
+                        | Token::Integer(_)
+                ) =>
+            {
+                self.advance();
+                let type_ = self.operand_type()?;
+
+                Ok(Negation::new(type_, self.prefix_operation()?).into())
+            }
+            _ => self.power_operation(),
+        }
+    }
+
+    fn power_operation(&mut self) -> Result<Expression, ParseError> {
+        let lhs = self.postfix_operation()?;
+
+        if self.peek() != &Token::Symbol("^") {
+            return Ok(lhs);
+        }
+
+        self.advance();
+        let type_ = self.operand_type()?;
+
+        Ok(ArithmeticOperation::new(
+            type_,
+            ArithmeticOperator::Power,
+            lhs,
+            self.prefix_operation()?,
+        )
+        .into())
+    }
+
+    fn postfix_operation(&mut self) -> Result<Expression, ParseError> {
+        let mut expression = self.primary_expression()?;
+
+        loop {
+            expression = match self.peek() {
+                Token::Symbol("[") | Token::Symbol("<") => {
+                    let type_arguments = if self.peek() == &Token::Symbol("<") {
+                        match self.type_arguments_before("[") {
+                            Some(arguments) => arguments,
+                            None => return Ok(expression),
+                        }
+                    } else {
+                        vec![]
+                    };
+
+                    self.advance();
+                    let type_ = self.function_type()?;
+                    self.symbol("]")?;
+                    self.symbol("(")?;
+                    let argument = self.expression()?;
+                    self.symbol(")")?;
+
+                    FunctionApplication::with_type_arguments(
+                        type_,
+                        expression,
+                        type_arguments,
+                        argument,
+                    )
+                    .into()
+                }
+                Token::Symbol(".") => {
+                    self.advance();
+                    let type_ = if self.peek() == &Token::Symbol("{") {
+                        self.tuple_type()?.into()
+                    } else {
+                        Type::from(self.record_type()?)
+                    };
+
+                    if self.peek() == &Token::Symbol("{") {
+                        self.advance();
+                        let fields = self.list("}", Self::record_update_field)?;
+
+                        RecordUpdate::new(type_, expression, fields).into()
+                    } else {
+                        self.symbol(".")?;
+
+                        RecordElement::with_field(type_, self.record_field()?, expression).into()
+                    }
+                }
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    fn record_field(&mut self) -> Result<RecordField, ParseError> {
+        Ok(if matches!(self.peek(), Token::Name(_)) {
+            RecordField::Name(self.name()?)
+        } else {
+            RecordField::Index(self.index()?)
+        })
+    }
+
+    fn record_update_field(&mut self) -> Result<RecordUpdateField, ParseError> {
+        let field = self.record_field()?;
+        self.symbol("=")?;
+
+        Ok(RecordUpdateField::with_field(field, self.expression()?))
+    }
+
+    fn primary_expression(&mut self) -> Result<Expression, ParseError> {
+        let position = self.position();
+
+        Ok(match self.peek().clone() {
+            Token::Keyword("true") => {
+                self.advance();
+                true.into()
+            }
+            Token::Keyword("false") => {
+                self.advance();
+                false.into()
+            }
+            Token::Keyword("nan") | Token::Keyword("infinity") | Token::Number(_) => {
+                self.number()?.into()
+            }
+            Token::Integer(_) => self.integer(false)?.into(),
+            Token::Symbol("-") => {
+                self.advance();
+
+                if matches!(self.peek(), Token::Integer(_)) {
+                    return Ok(self.integer(true)?.into());
+                } else if !matches!(
+                    self.peek(),
+                    Token::Keyword("nan") | Token::Keyword("infinity") | Token::Number(_)
+                ) {
+                    return Err(self.unexpected("number"));
+                }
+
+                (-self.number()?).into()
+            }
+            Token::ByteString(_) => ByteString::new(self.byte_string()?).into(),
+            Token::Keyword("case") => self.case()?,
+            Token::Keyword("try") => self.try_operation()?.into(),
+            Token::Keyword("sum") => {
+                self.advance();
+                self.symbol("[")?;
+                let type_ = types::Sum::new(self.name()?);
+                self.symbol(",")?;
+                let constructor = self.record_type()?;
+                self.symbol("]")?;
+                self.symbol("(")?;
+                let payload = self.expression()?;
+                self.symbol(")")?;
+
+                Sum::new(type_, constructor, payload).into()
+            }
+            Token::Keyword("variant") => {
+                self.advance();
+                self.symbol("[")?;
+                let type_ = self.type_()?;
+                self.symbol("]")?;
+                self.symbol("(")?;
+                let payload = self.expression()?;
+                self.symbol(")")?;
+
+                Variant::new(type_, payload).into()
+            }
+            Token::Keyword("lazy") => {
+                self.advance();
+                self.symbol("[")?;
+                let type_ = self.type_()?;
+                self.symbol("]")?;
+                self.symbol("(")?;
+                let expression = self.expression()?;
+                self.symbol(")")?;
+
+                Lazy::new(type_, expression).into()
+            }
+            Token::Keyword("force") => {
+                let [expression] = self.builtin_arguments("force")?;
+
+                Force::new(expression).into()
+            }
+            Token::Keyword("convert") => self.numeric_conversion()?.into(),
+            Token::Keyword("length") => {
+                let [string] = self.builtin_arguments("length")?;
+
+                ByteStringLength::new(string).into()
+            }
+            Token::Keyword("concat") => {
+                let [lhs, rhs] = self.builtin_arguments("concat")?;
+
+                ByteStringConcatenation::new(lhs, rhs).into()
+            }
+            Token::Keyword("slice") => {
+                let [string, start, end] = self.builtin_arguments("slice")?;
+
+                ByteStringSlice::new(string, start, end).into()
+            }
+            Token::Symbol("(") => {
+                self.advance();
+                let expression = self.expression()?;
+                self.symbol(")")?;
+
+                expression
+            }
+            Token::Symbol("{") => {
+                let type_ = self.tuple_type()?;
+                self.symbol("{")?;
+
+                Record::new(type_, self.list("}", Self::expression)?).into()
+            }
+            Token::Name(name) => {
+                self.advance();
+                let type_arguments = self.type_arguments_before("{");
+
+                if self.peek() == &Token::Symbol("{") {
+                    self.advance();
+
+                    Record::new(
+                        types::Record::with_arguments(name, type_arguments.unwrap_or_default()),
+                        self.list("}", Self::expression)?,
+                    )
+                    .into()
+                } else {
+                    Variable::new(name).into()
+                }
+            }
+            _ => {
+                return Err(ParseError::new(
+                    format!("expected expression, found {}", self.peek()),
+                    position,
+                ))
+            }
+        })
+    }
+
+    // Case expressions over sums and literals have sum names and literal
+    // types in brackets respectively.
+    fn case(&mut self) -> Result<Expression, ParseError> {
+        self.keyword("case")?;
+
+        let type_ = if self.peek() == &Token::Symbol("[") {
+            self.advance();
+            let type_ = if matches!(self.peek(), Token::Name(_)) {
+                types::Sum::new(self.name()?).into()
+            } else {
+                self.type_()?
+            };
+            self.symbol("]")?;
+
+            Some(type_)
+        } else {
+            None
+        };
+        let literal = matches!(&type_, Some(type_) if !matches!(type_, Type::Sum(_)));
+
+        let argument = self.expression()?;
+        self.keyword("of")?;
+        self.symbol("{")?;
+
+        let mut alternatives = vec![];
+        let mut literal_alternatives = vec![];
+        let mut default_alternative = None;
+
+        loop {
+            if self.peek() == &Token::Symbol("}") {
+                break;
+            } else if default_alternative.is_some() {
+                return Err(self.unexpected("`}` after default alternative"));
+            }
+
+            if literal && !matches!(self.peek(), Token::Name(_)) {
+                let literal = self.literal()?;
+                self.symbol("=>")?;
+
+                literal_alternatives.push(LiteralAlternative::new(literal, self.expression()?));
+            } else {
+                let name = self.name()?;
+
+                if !literal && self.peek() == &Token::Symbol(":") {
+                    self.advance();
+                    let type_ = self.type_()?;
+                    self.symbol("=>")?;
+
+                    alternatives.push(Alternative::new(type_, name, self.expression()?));
+                } else {
+                    self.symbol("=>")?;
+
+                    default_alternative = Some(DefaultAlternative::new(name, self.expression()?));
+                }
+            }
+
+            if self.peek() == &Token::Symbol(",") {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.symbol("}")?;
+
+        Ok(match type_ {
+            Some(Type::Sum(type_)) => {
+                SumCase::new(type_, argument, alternatives, default_alternative).into()
+            }
+            Some(type_) => {
+                LiteralCase::new(type_, argument, literal_alternatives, default_alternative).into()
+            }
+            None => Case::new(argument, alternatives, default_alternative).into(),
+        })
+    }
+
+    fn try_operation(&mut self) -> Result<TryOperation, ParseError> {
+        self.keyword("try")?;
+        let operand = self.expression()?;
+        self.keyword("of")?;
+        self.symbol("{")?;
+        let name = self.name()?;
+        self.symbol(":")?;
+        let type_ = self.type_()?;
+        self.symbol("=>")?;
+        let then = self.expression()?;
+
+        if self.peek() == &Token::Symbol(",") {
+            self.advance();
+        }
+
+        self.symbol("}")?;
+
+        Ok(TryOperation::new(operand, name, type_, then))
+    }
+
+    fn literal(&mut self) -> Result<Literal, ParseError> {
+        if !matches!(
+            self.peek(),
+            Token::Keyword("true" | "false" | "nan" | "infinity")
+                | Token::Number(_)
+                | Token::Integer(_)
+                | Token::ByteString(_)
+                | Token::Symbol("-")
+        ) {
+            return Err(self.unexpected("literal"));
+        }
+
+        Ok(match self.primary_expression()? {
+            Expression::Boolean(boolean) => boolean.into(),
+            Expression::ByteString(string) => string.into(),
+            Expression::Integer(integer) => integer.into(),
+            Expression::Number(number) => number.into(),
+            _ => unreachable!("literal expression"),
+        })
+    }
+
+    fn numeric_conversion(&mut self) -> Result<NumericConversion, ParseError> {
+        self.keyword("convert")?;
+        self.symbol("[")?;
+        let from = self.type_()?;
+        self.symbol(",")?;
+        let to = self.type_()?;
+
+        let rounding = if self.peek() == &Token::Symbol(",") {
+            self.advance();
+            let position = self.position();
+
+            match self.name()?.as_str() {
+                "ceiling" => Rounding::Ceiling,
+                "floor" => Rounding::Floor,
+                "round" => Rounding::Round,
+                "truncate" => Rounding::Truncate,
+                _ => return Err(ParseError::new("unknown rounding mode", position)),
+            }
+        } else {
+            Rounding::Truncate
+        };
+
+        self.symbol("]")?;
+        self.symbol("(")?;
+        let argument = self.expression()?;
+        self.symbol(")")?;
+
+        Ok(NumericConversion::new(from, to, rounding, argument))
+    }
+
+    fn builtin_arguments<const N: usize>(
+        &mut self,
+        keyword: &'static str,
+    ) -> Result<[Expression; N], ParseError> {
+        let position = self.position();
+
+        self.keyword(keyword)?;
+        self.symbol("(")?;
+
+        self.list(")", Self::expression)?
+            .try_into()
+            .map_err(|_| ParseError::new(format!("expected {} arguments", N), position))
+    }
+
+    fn number(&mut self) -> Result<f64, ParseError> {
+        let position = self.position();
+
+        let number = match self.peek() {
+            Token::Keyword("nan") => f64::NAN,
+            Token::Keyword("infinity") => f64::INFINITY,
+            Token::Number(number) => number
+                .parse()
+                .map_err(|_| ParseError::new("invalid number literal", position))?,
+            _ => return Err(self.unexpected("number")),
+        };
+
+        self.advance();
+
+        Ok(number)
+    }
+
+    fn integer(&mut self, negative: bool) -> Result<Integer, ParseError> {
+        let position = self.position();
+        let literal = match self.peek() {
+            Token::Integer(literal) => literal.clone(),
+            _ => return Err(self.unexpected("integer")),
+        };
+        let index = literal.find(['i', 'u']).unwrap();
+        let type_ = integer_type(&literal[index..])
+            .ok_or_else(|| ParseError::new("invalid integer type", position))?;
+        let maximum = if type_.is_signed() {
+            (1u64 << (type_.bits() - 1)) - if negative { 0 } else { 1 }
+        } else if negative {
+            0
+        } else {
+            u64::MAX >> (64 - type_.bits())
+        };
+        let value = literal[..index]
+            .parse::<u64>()
+            .ok()
+            .filter(|&value| value <= maximum)
+            .ok_or_else(|| ParseError::new("integer literal out of range", position))?;
+
+        self.advance();
+
+        Ok(Integer::new(
+            type_,
+            if negative {
+                value.wrapping_neg()
+            } else {
+                value
+            },
+        ))
+    }
+
+    fn index(&mut self) -> Result<usize, ParseError> {
+        let position = self.position();
+
+        let index = match self.peek() {
+            Token::Number(number) => number
+                .parse()
+                .map_err(|_| ParseError::new("invalid element index", position))?,
+            _ => return Err(self.unexpected("element index")),
+        };
+
+        self.advance();
+
+        Ok(index)
+    }
+
+    fn byte_string(&mut self) -> Result<Vec<u8>, ParseError> {
+        match self.peek().clone() {
+            Token::ByteString(string) => {
+                self.advance();
+                Ok(string)
+            }
+            _ => Err(self.unexpected("string literal")),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        match self.peek().clone() {
+            Token::Name(name) => {
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("name")),
+        }
+    }
+
+    // Parses comma-separated elements with an optional trailing comma until
+    // the given closing symbol.
+    fn list<T>(
+        &mut self,
+        close: &'static str,
+        element: impl Fn(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut elements = vec![];
+
+        while self.peek() != &Token::Symbol(close) {
+            elements.push(element(self)?);
+
+            if self.peek() == &Token::Symbol(",") {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.symbol(close)?;
+
+        Ok(elements)
+    }
+
+    fn keyword(&mut self, keyword: &'static str) -> Result<(), ParseError> {
+        self.expect(&Token::Keyword(keyword), &format!("`{}`", keyword))
+    }
+
+    fn symbol(&mut self, symbol: &'static str) -> Result<(), ParseError> {
+        self.expect(&Token::Symbol(symbol), &format!("`{}`", symbol))
+    }
+
+    fn expect(&mut self, token: &Token, expected: &str) -> Result<(), ParseError> {
+        if self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        ParseError::new(
+            format!("expected {}, found {}", expected, self.peek()),
+            self.position(),
+        )
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn peek_next(&self) -> &Token {
+        &self.tokens[(self.index + 1).min(self.tokens.len() - 1)].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.index].1
+    }
+
+    fn advance(&mut self) {
+        if self.index + 1 < self.tokens.len() {
+            self.index += 1;
+        }
+    }
+}
+
+fn integer_type(name: &str) -> Option<types::Integer> {
+    Some(match name {
+        "i8" => types::Integer::Signed8,
+        "i32" => types::Integer::Signed32,
+        "i64" => types::Integer::Signed64,
+        "u8" => types::Integer::Unsigned8,
+        "u32" => types::Integer::Unsigned32,
+        "u64" => types::Integer::Unsigned64,
+        _ => return None,
+    })
+}
@@ -2,6 +2,7 @@ use super::type_::Type;
 use std::sync::Arc;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Function {
     argument: Arc<Type>,
     result: Arc<Type>,
@@ -0,0 +1,24 @@
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Integer {
+    Signed8,
+    Signed32,
+    Signed64,
+    Unsigned8,
+    Unsigned32,
+    Unsigned64,
+}
+
+impl Integer {
+    pub fn bits(self) -> usize {
+        match self {
+            Self::Signed8 | Self::Unsigned8 => 8,
+            Self::Signed32 | Self::Unsigned32 => 32,
+            Self::Signed64 | Self::Unsigned64 => 64,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, Self::Signed8 | Self::Signed32 | Self::Signed64)
+    }
+}
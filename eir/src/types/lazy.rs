@@ -0,0 +1,20 @@
+use super::type_::Type;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Lazy {
+    type_: Arc<Type>,
+}
+
+impl Lazy {
+    pub fn new(type_: impl Into<Type>) -> Self {
+        Self {
+            type_: type_.into().into(),
+        }
+    }
+
+    pub fn type_(&self) -> &Type {
+        &self.type_
+    }
+}
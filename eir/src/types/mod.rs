@@ -1,9 +1,17 @@
 mod function;
+mod integer;
+mod lazy;
 mod record;
 mod record_body;
+mod sum;
+mod tuple;
 mod type_;
 
 pub use function::*;
+pub use integer::*;
+pub use lazy::*;
 pub use record::*;
 pub use record_body::*;
+pub use sum::*;
+pub use tuple::*;
 pub use type_::*;
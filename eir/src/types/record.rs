@@ -1,14 +1,29 @@
+use super::type_::Type;
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Record {
     name: String,
+    arguments: Vec<Type>,
 }
 
 impl Record {
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self::with_arguments(name, vec![])
+    }
+
+    pub fn with_arguments(name: impl Into<String>, arguments: Vec<Type>) -> Self {
+        Self {
+            name: name.into(),
+            arguments,
+        }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn arguments(&self) -> &[Type] {
+        &self.arguments
+    }
 }
@@ -1,16 +1,40 @@
 use super::type_::Type;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RecordBody {
     elements: Vec<Type>,
+    field_names: Option<Vec<String>>,
 }
 
 impl RecordBody {
     pub const fn new(elements: Vec<Type>) -> Self {
-        RecordBody { elements }
+        RecordBody {
+            elements,
+            field_names: None,
+        }
+    }
+
+    pub fn with_fields(fields: Vec<(String, Type)>) -> Self {
+        let (field_names, elements) = fields.into_iter().unzip();
+
+        RecordBody {
+            elements,
+            field_names: Some(field_names),
+        }
     }
 
     pub fn elements(&self) -> &[Type] {
         &self.elements
     }
+
+    pub fn field_names(&self) -> Option<&[String]> {
+        self.field_names.as_deref()
+    }
+
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.field_names()?
+            .iter()
+            .position(|field_name| field_name == name)
+    }
 }
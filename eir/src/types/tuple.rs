@@ -0,0 +1,59 @@
+use super::{record_body::RecordBody, type_::Type};
+use std::sync::Arc;
+
+// Tuples with field names are anonymous records.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Tuple {
+    elements: Vec<Type>,
+    field_names: Option<Arc<[String]>>,
+}
+
+impl Tuple {
+    pub const fn new(elements: Vec<Type>) -> Self {
+        Self {
+            elements,
+            field_names: None,
+        }
+    }
+
+    pub fn with_fields(fields: Vec<(String, Type)>) -> Self {
+        let (field_names, elements): (Vec<_>, _) = fields.into_iter().unzip();
+
+        Self {
+            elements,
+            field_names: Some(field_names.into()),
+        }
+    }
+
+    pub fn elements(&self) -> &[Type] {
+        &self.elements
+    }
+
+    pub fn field_names(&self) -> Option<&[String]> {
+        self.field_names.as_deref()
+    }
+
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.field_names()?
+            .iter()
+            .position(|field_name| field_name == name)
+    }
+
+    pub fn body(&self) -> RecordBody {
+        if let Some(names) = self.field_names() {
+            RecordBody::with_fields(names.iter().cloned().zip(self.elements.clone()).collect())
+        } else {
+            RecordBody::new(self.elements.clone())
+        }
+    }
+}
+
+impl From<RecordBody> for Tuple {
+    fn from(body: RecordBody) -> Self {
+        Self {
+            elements: body.elements().to_vec(),
+            field_names: body.field_names().map(Arc::from),
+        }
+    }
+}
@@ -1,12 +1,20 @@
-use super::{function::Function, record::Record};
+use super::{
+    function::Function, integer::Integer, lazy::Lazy, record::Record, sum::Sum, tuple::Tuple,
+};
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Type {
     Boolean,
     ByteString,
     Function(Function),
+    Integer(Integer),
+    Lazy(Lazy),
     Number,
     Record(Record),
+    Sum(Sum),
+    Tuple(Tuple),
+    Variable(String),
     Variant,
 }
 
@@ -32,8 +40,32 @@ impl From<Function> for Type {
     }
 }
 
+impl From<Integer> for Type {
+    fn from(integer: Integer) -> Self {
+        Self::Integer(integer)
+    }
+}
+
+impl From<Lazy> for Type {
+    fn from(lazy: Lazy) -> Self {
+        Self::Lazy(lazy)
+    }
+}
+
 impl From<Record> for Type {
     fn from(record: Record) -> Self {
         Self::Record(record)
     }
 }
+
+impl From<Sum> for Type {
+    fn from(sum: Sum) -> Self {
+        Self::Sum(sum)
+    }
+}
+
+impl From<Tuple> for Type {
+    fn from(tuple: Tuple) -> Self {
+        Self::Tuple(tuple)
+    }
+}